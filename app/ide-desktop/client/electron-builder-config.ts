@@ -199,8 +199,9 @@ export function createElectronBuilderConfig(passedArgs: Arguments): electronBuil
       // almost zero.
       // This type assertion is UNSAFE, and any users MUST verify that
       // they are passing a valid value to `target`.
+      // The zip archive is required by the auto-updater, which cannot install from the disk image.
       // eslint-disable-next-line no-restricted-syntax, @typescript-eslint/no-explicit-any, @typescript-eslint/no-unsafe-assignment
-      target: (passedArgs.target as any) ?? 'dmg',
+      target: (passedArgs.target as any) ?? ['dmg', 'zip'],
       icon: `${passedArgs.iconsDist}/icon.icns`,
       category: 'public.app-category.developer-tools',
       darkModeSupport: true,
@@ -219,7 +220,9 @@ export function createElectronBuilderConfig(passedArgs: Arguments): electronBuil
     win: {
       // Compression is not used as the build time is huge and file size saving
       // almost zero.
-      target: passedArgs.target ?? 'dir',
+      // The installer is built by our own bundler from the `dir` target. The NSIS installer is
+      // used only by the auto-updater, which cannot install from our installer.
+      target: passedArgs.target ?? ['dir', 'nsis'],
       icon: `${passedArgs.iconsDist}/icon.ico`,
     },
     linux: {
@@ -249,6 +252,8 @@ export function createElectronBuilderConfig(passedArgs: Arguments): electronBuil
       runAfterFinish: false,
     },
     nsis: {
+      // Distinct from the name of the installer built by our own bundler.
+      artifactName: 'enso-${os}-${arch}-' + BUILD_INFO.version + '-nsis.${ext}',
       // Disables "block map" generation during electron building. Block maps
       // can be used for incremental package update on client-side. However,
       // their generation can take long time (even 30 mins), so we removed it
@@ -4,7 +4,6 @@ use crate::version::Kind;
 
 use anyhow::Context;
 use aws_sdk_s3::model::ObjectCannedAcl;
-use aws_sdk_s3::types::ByteStream;
 use s3::BucketContext;
 
 
@@ -115,8 +114,8 @@ pub async fn update_manifest(repo_context: &impl IsRepo, edition_file: &Path) ->
 
     let new_edition_filename =
         edition_file.file_name().context("Edition file path is missing filename!")?;
-    bucket_context
-        .put(new_edition_filename.as_str(), ByteStream::from_path(&edition_file).await?)
+    let new_edition_filename = new_edition_filename.as_str();
+    ide_ci::artifact_store::upload_unverified(&bucket_context, new_edition_filename, edition_file)
         .await?;
 
     bucket_context.put_yaml("manifest.yaml", &new_manifest).await?;
@@ -5,7 +5,7 @@ use crate::prelude::*;
 use crate::paths::generated;
 
 use ide_ci::programs::docker::BuildOptions;
-use ide_ci::programs::docker::ImageId;
+use ide_ci::programs::docker::BuildOutput;
 use ide_ci::programs::Docker;
 
 
@@ -16,19 +16,40 @@ pub const NAME: &str = "runtime";
 /// Region where the ECR repository with Runtime images is located.
 pub const REGION: &str = "eu-west-1";
 
+/// Tag of the image in the ECR repository that stores the layer cache of the Runtime builds.
+pub const CACHE_TAG: &str = "buildcache";
+
+/// Name of the `buildx` builder that builds the Runtime image, as the default builder cannot
+/// export the layer cache to the registry.
+pub const BUILDER: &str = "enso-runtime";
+
+/// Options for building the Runtime Docker image from the Engine package.
+///
+/// The image is labelled with its version, following the OCI annotation conventions.
+pub fn build_options(
+    dockerfile: &generated::RepoRootToolsCiDocker,
+    engine_package_root: &generated::EnginePackage,
+    version: &Version,
+) -> BuildOptions {
+    let mut opts = BuildOptions::new(engine_package_root);
+    opts.file = Some(dockerfile.dockerfile.to_path_buf());
+    opts.add_build_context_local("docker-tools", dockerfile);
+    opts.labels.insert("org.opencontainers.image.title".into(), "Enso Runtime".into());
+    opts.labels.insert("org.opencontainers.image.version".into(), version.to_string());
+    opts
+}
+
 /// Build the Runtime Docker image from the Engine package.
-#[instrument(fields(%dockerfile, %engine_package_root))]
+#[instrument(skip(opts), fields(%dockerfile, %engine_package_root))]
 pub async fn build_runtime_image(
     dockerfile: generated::RepoRootToolsCiDocker,
     engine_package_root: generated::EnginePackage,
-    tag: String,
-) -> Result<ImageId> {
-    let mut opts = BuildOptions::new(&engine_package_root);
-    opts.file = Some(dockerfile.dockerfile.to_path_buf());
-    opts.tags.push(tag);
-    opts.add_build_context_local("docker-tools", &dockerfile);
-    let id = Docker.build(opts).await?;
-    Ok(id)
+    version: &Version,
+    opts: impl FnOnce(&mut BuildOptions),
+) -> Result<BuildOutput> {
+    let mut build_options = build_options(&dockerfile, &engine_package_root, version);
+    opts(&mut build_options);
+    Docker.build_image(build_options).await
 }
 
 #[cfg(test)]
@@ -53,8 +74,12 @@ mod tests {
             root.join("built-distribution/enso-engine-2023.1.1-dev-linux-amd64/enso-2023.1.1-dev"),
         );
         let dockerfile = generated::RepoRootToolsCiDocker::new_root(root.join("tools/ci/docker"));
-        let id = build_runtime_image(dockerfile, engine_package, tag.to_string()).await?;
-        info!("Built image: {}", id);
+        let version = Version::new(2023, 1, 1);
+        let output = build_runtime_image(dockerfile, engine_package, &version, |opts| {
+            opts.tags.push(tag.to_string());
+        })
+        .await?;
+        info!("Built image: {}", output.image_id);
         Ok(())
     }
 }
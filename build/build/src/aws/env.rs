@@ -12,5 +12,5 @@ define_env_var! {
     AWS_ACCESS_KEY_ID, String;
 
     /// The AWS secret access key.
-    AWS_SECRET_ACCESS_KEY, String;
+    AWS_SECRET_ACCESS_KEY, secret String;
 }
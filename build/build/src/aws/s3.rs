@@ -7,6 +7,7 @@ use aws_sdk_s3::output::PutObjectOutput;
 use aws_sdk_s3::types::ByteStream;
 use bytes::Buf;
 use enso_build_base::extensions::path::SplitFilename;
+use ide_ci::artifact_store::ArtifactStore;
 use mime::Mime;
 use walkdir::WalkDir;
 
@@ -136,6 +137,47 @@ impl BucketContext {
     }
 }
 
+/// S3 objects are immutable, so uploads cannot be resumed. Downloads are resumed using ranged
+/// requests.
+#[async_trait]
+impl ArtifactStore for BucketContext {
+    async fn stored_size(&self, name: &str) -> Result<Option<u64>> {
+        let key = self.key(name);
+        let listing = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(&key)
+            .send()
+            .await
+            .with_context(|| format!("Failed to look up {key} in S3 bucket {}.", self.bucket))?;
+        let object = listing.contents().unwrap_or_default().iter().find(|o| o.key() == Some(&key));
+        object.map(|object| Ok(object.size().try_into()?)).transpose()
+    }
+
+    async fn put_from(&self, name: &str, file: &Path, offset: u64) -> Result {
+        ensure!(offset == 0, "S3 does not support resumed uploads.");
+        self.put(name, ByteStream::from_path(file).await?).await?;
+        Ok(())
+    }
+
+    async fn get_from(&self, name: &str, offset: u64, output: &Path) -> Result {
+        let key = self.key(name);
+        let mut request = self.client.get_object().bucket(&self.bucket).key(&key);
+        if offset > 0 {
+            request = request.range(format!("bytes={offset}-"));
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to download {key} from S3 bucket {}.", self.bucket))?;
+        let data = response.body.collect().await?.into_bytes();
+        let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(output).await?;
+        file.write_all(&data).await?;
+        Ok(())
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ContentEncoding {
     Gzip,
@@ -65,13 +65,6 @@ pub const PRIMARY_TARGET: Target = (OS::Linux, Arch::X86_64);
 
 const RELEASE_CLEANING_POLICY: CleaningCondition = CleaningCondition::Always;
 
-pub const RELEASE_TARGETS: [(OS, Arch); 4] = [
-    (OS::Windows, Arch::X86_64),
-    (OS::Linux, Arch::X86_64),
-    (OS::MacOS, Arch::X86_64),
-    (OS::MacOS, Arch::AArch64),
-];
-
 /// Targets for which we run PR checks.
 ///
 /// The macOS AArch64 is intentionally omitted, as the runner availability is limited.
@@ -508,17 +501,18 @@ fn add_release_steps(workflow: &mut Workflow) -> Result {
     let prepare_job_id = workflow.add(PRIMARY_TARGET, DraftRelease);
     let mut packaging_job_ids = vec![];
 
-    // Assumed, because Linux is necessary to deploy ECR runtime image.
-    assert!(RELEASE_TARGETS.into_iter().any(|(os, _)| os == OS::Linux));
-    for target in RELEASE_TARGETS {
+    // Assumed, because the primary target is necessary to deploy ECR runtime image.
+    let release_targets = crate::target::MATRIX.map(crate::target::Target::workflow_target);
+    assert!(release_targets.contains(&PRIMARY_TARGET));
+    for target in release_targets {
         let backend_job_id = workflow.add_dependent(target, job::UploadBackend, [&prepare_job_id]);
 
         let build_ide_job_id =
             workflow.add_dependent(target, UploadIde, [&prepare_job_id, &backend_job_id]);
         packaging_job_ids.push(build_ide_job_id.clone());
 
-        // The backend image is deployed to ECR only on Linux.
-        if target.0 == OS::Linux {
+        // The backend image is deployed to ECR only from the primary target, Linux on x86-64.
+        if target == PRIMARY_TARGET {
             let runtime_requirements = [&prepare_job_id, &backend_job_id];
             let upload_runtime_job_id =
                 workflow.add_dependent(target, job::DeployRuntime, runtime_requirements);
@@ -24,6 +24,7 @@ use ide_ci::actions::workflow::definition::Strategy;
 use ide_ci::actions::workflow::definition::Target;
 use ide_ci::cache::goodie::graalvm;
 use ide_ci::convert_case::ToKebabCase;
+use ide_ci::programs::sccache;
 
 
 
@@ -110,6 +111,21 @@ pub fn plain_job(
     RunStepsBuilder::new(command_line).build_job(name, runs_on)
 }
 
+/// Job that runs the Rust-heavy command line with sccache as the compiler cache. The cache is
+/// stored in the GitHub Actions cache, which the sccache action gives the access to.
+pub fn sccache_job(
+    runs_on: impl RunsOn,
+    name: impl Into<String>,
+    command_line: impl Into<String>,
+) -> Job {
+    RunStepsBuilder::new(format!("{} --sccache", command_line.into()))
+        .customize(|step| {
+            let step = step.with_env(sccache::env::SCCACHE_GHA_ENABLED, "true");
+            vec![step::install_sccache(), step]
+        })
+        .build_job(name, runs_on)
+}
+
 /// Pretty print arguments to `./run` that will invoke SBT with the given command.
 ///
 /// Meant to be used together with [`RunStepsBuilder::new`].
@@ -375,7 +391,7 @@ pub struct Lint;
 
 impl JobArchetype for Lint {
     fn job(&self, target: Target) -> Job {
-        plain_job(target, "Lint", "lint")
+        sccache_job(target, "Lint", "lint")
     }
 }
 
@@ -384,7 +400,7 @@ pub struct NativeTest;
 
 impl JobArchetype for NativeTest {
     fn job(&self, target: Target) -> Job {
-        plain_job(target, "Native Rust tests", "wasm test --no-wasm")
+        sccache_job(target, "Native Rust tests", "wasm test --no-wasm")
     }
 }
 
@@ -405,7 +421,7 @@ impl JobArchetype for GuiBuild {
     fn job(&self, target: Target) -> Job {
         let command: &str = "gui build";
         RunStepsBuilder::new(command)
-            .customize(|step| vec![expose_gui_vars(step)])
+            .customize(|step| vec![step::wasm_size_report_cache(), expose_gui_vars(step)])
             .build_job("GUI build", target)
     }
 }
@@ -415,7 +431,7 @@ pub struct WasmTest;
 
 impl JobArchetype for WasmTest {
     fn job(&self, target: Target) -> Job {
-        plain_job(target, "WASM tests", "wasm test --no-native")
+        sccache_job(target, "WASM tests", "wasm test --no-native")
     }
 }
 
@@ -29,6 +29,30 @@ pub fn test_reporter(
     .with_custom_argument("name", report_name)
 }
 
+/// Restore the WASM size report of the previous run, so the sizes can be compared against it.
+///
+/// The report is saved under a key unique to the run, and the most recent one is restored.
+pub fn wasm_size_report_cache() -> Step {
+    let path = format!("target/{}", crate::project::wasm::size::REPORT_FILENAME);
+    Step {
+        name: Some("Restore previous WASM size report".into()),
+        uses: Some("actions/cache@v4".into()),
+        ..default()
+    }
+    .with_custom_argument("path", path)
+    .with_custom_argument("key", "wasm-size-report-${{ runner.os }}-${{ github.run_id }}")
+    .with_custom_argument("restore-keys", "wasm-size-report-${{ runner.os }}-")
+}
+
+/// Install sccache, so the Rust builds of the job can be run with `--sccache`.
+pub fn install_sccache() -> Step {
+    Step {
+        name: Some("Installing sccache".into()),
+        uses: Some("mozilla-actions/sccache-action@v0.0.9".into()),
+        ..default()
+    }
+}
+
 pub fn stdlib_test_reporter((os, arch): Target, graal_edition: graalvm::Edition) -> Step {
     let step_name = "Standard Library Test Reporter";
     let report_name = format!("Standard Library Tests Report ({graal_edition}, {os}, {arch})");
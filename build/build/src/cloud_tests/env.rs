@@ -10,7 +10,7 @@ pub mod ci_config {
         ENSO_CLOUD_TEST_ACCOUNT_USERNAME, String;
 
         /// Password for an Enso Cloud account used for running Cloud integration tests.
-        ENSO_CLOUD_TEST_ACCOUNT_PASSWORD, String;
+        ENSO_CLOUD_TEST_ACCOUNT_PASSWORD, secret String;
 
         // The Client ID of the User Pool for Enso Cloud Cognito auth flow.
         ENSO_CLOUD_COGNITO_USER_POOL_WEB_CLIENT_ID, String;
@@ -1,5 +1,8 @@
 use crate::prelude::*;
 
+use bytesize::ByteSize;
+use ide_ci::prerequisites;
+use ide_ci::prerequisites::Requirement;
 use ide_ci::program;
 use ide_ci::programs;
 use semver::VersionReq;
@@ -48,7 +51,11 @@ impl RecognizedProgram {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct ConfigRaw {
-    pub required_versions: HashMap<String, String>,
+    pub required_versions:      HashMap<String, String>,
+    #[serde(default)]
+    pub wasm_size_limit:        Option<ByteSize>,
+    #[serde(default)]
+    pub wasm_crate_size_limits: BTreeMap<String, ByteSize>,
 }
 
 /// The configuration of the script that is being provided by the external environment.
@@ -56,22 +63,74 @@ pub struct ConfigRaw {
 /// In our case, it is usually a configuration file in the main repository.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Config {
-    pub required_versions: HashMap<RecognizedProgram, VersionReq>,
+    pub required_versions:      HashMap<RecognizedProgram, VersionReq>,
+    /// Maximum size of each WASM binary produced by the build.
+    pub wasm_size_limit:        Option<ByteSize>,
+    /// Maximum size of the given crates' code in each WASM binary produced by the build.
+    pub wasm_crate_size_limits: BTreeMap<String, ByteSize>,
 }
 
 impl Config {
+    /// The programs required by the build, including the ones with versions pinned in the
+    /// configuration.
+    pub fn requirements(&self) -> Vec<Requirement> {
+        // These are pinned elsewhere (e.g. the Rust version in `rust-toolchain.toml`), so only
+        // their presence is checked.
+        let unversioned = ["cargo", "git", "rustc"].map(Requirement::new);
+        let versioned = self.required_versions.iter().map(|(program, version_req)| {
+            let version_getter = {
+                let program = program.clone();
+                move || {
+                    let program = program.clone();
+                    async move { program.version().await }
+                }
+            };
+            let requirement = match program {
+                RecognizedProgram::Other(name) if name.starts_with("cargo-") =>
+                    Requirement::new(name).without_executable_lookup(),
+                RecognizedProgram::Other(name) => Requirement::new(name),
+            };
+            requirement.with_version(version_req.clone()).with_version_getter(version_getter)
+        });
+        unversioned.into_iter().chain(versioned).map(with_install_hints).collect()
+    }
+
     /// Check whether all the required programs are available and have the required versions.
+    ///
+    /// All the programs are checked, and the problems are reported together.
     pub async fn check_programs(&self) -> Result {
-        let check_tasks = self
-            .required_versions
-            .iter()
-            .map(|(program, version_req)| check_program(program, version_req));
-        let results = futures::future::join_all(check_tasks).await;
-        let errors = results.into_iter().filter_map(Result::err).collect_vec();
-        if !(errors.is_empty()) {
-            bail!("Some required programs are not available or have wrong versions: {errors:?}")
-        }
-        Ok(())
+        let requirements = self.requirements();
+        let report = prerequisites::check(&requirements).await;
+        info!("Checked the required programs:\n{report}");
+        report.ensure_satisfied()
+    }
+}
+
+/// Add hints on how to install the programs that the build might require.
+fn with_install_hints(requirement: Requirement) -> Requirement {
+    const RUSTUP_NOTE: &str = "The toolchain version is pinned in `rust-toolchain.toml` and \
+    will be installed by rustup automatically.";
+    const NODE_NOTE: &str = "Install the Node.js version listed in `.node-version`";
+    match requirement.name.as_str() {
+        "cargo" | "rustc" => requirement
+            .with_hint(format!("Install Rust using rustup: <https://rustup.rs>. {RUSTUP_NOTE}"))
+            .with_hint_for(
+                OS::Windows,
+                format!("Run `rustup-init.exe` from <https://rustup.rs>. {RUSTUP_NOTE}"),
+            ),
+        "git" => requirement
+            .with_hint("Install Git using the system package manager, e.g. `apt install git`.")
+            .with_hint_for(OS::MacOS, "Install Git using `xcode-select --install`.")
+            .with_hint_for(OS::Windows, "Install Git for Windows: `winget install Git.Git`."),
+        "node" => requirement
+            .with_hint(format!("{NODE_NOTE}, e.g. using `fnm install` or `nvm install`."))
+            .with_hint_for(OS::Windows, format!("{NODE_NOTE}, e.g. using `fnm install`.")),
+        "cargo-watch" => requirement.with_hint("Run `cargo install cargo-watch`."),
+        "wasm-pack" => requirement.with_hint(
+            "Run `cargo install wasm-pack`, or use the installer from \
+            <https://rustwasm.github.io/wasm-pack/installer>.",
+        ),
+        _ => requirement,
     }
 }
 
@@ -87,27 +146,12 @@ impl TryFrom<ConfigRaw> for Config {
             );
         }
 
-        Ok(Self { required_versions })
-    }
-}
-
-/// Check if the given program is installed in the system and has the required version.
-pub async fn check_program(program: &RecognizedProgram, version_req: &VersionReq) -> Result {
-    let found = program.version().await?;
-    if !version_req.matches(&found) {
-        bail!(
-            "Found program `{}` in version `{}` that does not fulfill requirement `{}`.",
-            program,
-            found,
-            version_req
-        );
-    } else {
-        info!(
-            "Found program `{}` in supported version `{}` (required `{}`).",
-            program, found, version_req
-        );
+        Ok(Self {
+            required_versions,
+            wasm_size_limit: value.wasm_size_limit,
+            wasm_crate_size_limits: value.wasm_crate_size_limits,
+        })
     }
-    Ok(())
 }
 
 #[cfg(test)]
@@ -12,7 +12,6 @@ use crate::paths::generated;
 
 use artifact::IsArtifact;
 use bundle::IsBundle;
-use ide_ci::cache::goodie::graalvm::Edition;
 use ide_ci::future::AsyncPolicy;
 use ide_ci::github::Repo;
 use package::IsPackage;
@@ -387,7 +386,7 @@ pub async fn deduce_graal(
     build_sbt: &generated::RepoRootBuildSbt,
 ) -> Result<ide_ci::cache::goodie::graalvm::GraalVM> {
     let build_sbt_content = ide_ci::fs::tokio::read_to_string(build_sbt).await?;
-    let graal_edition = env::GRAAL_EDITION.get().map_or(Edition::default(), |e| e);
+    let graal_edition = env::GRAAL_EDITION.get_opt()?.unwrap_or_default();
 
     Ok(ide_ci::cache::goodie::graalvm::GraalVM {
         client,
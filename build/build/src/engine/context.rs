@@ -70,7 +70,7 @@ impl RunContext {
         triple: TargetTriple,
         external_runtime: Option<Arc<EnginePackageProvider>>,
     ) -> Result<Self> {
-        let paths = Paths::new_versions(&inner.repo_root, triple.versions)?;
+        let paths = Paths::new_triple(&inner.repo_root, triple)?;
         let context = RunContext { config: config.into(), inner, paths, external_runtime };
         Ok(context)
     }
@@ -184,8 +184,8 @@ impl RunContext {
         let graalpy = cache::goodie::graalpy::GraalPy {
             client:  self.octocrab.clone(),
             version: graalpy_version,
-            os:      self.paths.triple.os,
-            arch:    self.paths.triple.arch,
+            os:      self.paths.triple.target.os,
+            arch:    self.paths.triple.target.arch,
         };
         graalpy.install_if_missing(&self.cache).await?;
         ide_ci::programs::graalpy::GraalPy.require_present().await?;
@@ -230,7 +230,7 @@ impl RunContext {
             // engine version as part of the key. As such, any change made to engine that does not
             // change its version might break the caches.
             // See (private): https://discord.com/channels/401396655599124480/407883082310352928/939618590158630922
-            ide_ci::fs::remove_dir_if_exists(cache_directory())?;
+            ide_ci::fs::clean::remove_if_exists_async(cache_directory()).await?;
 
             // Remove the benchmark reports. They are not meant currently to be incrementally
             // updated.
@@ -248,7 +248,7 @@ impl RunContext {
                 let test_results_dir = ENSO_TEST_JUNIT_DIR
                     .get()
                     .unwrap_or_else(|_| self.paths.repo_root.target.test_results.path.clone());
-                ide_ci::fs::reset_dir(&test_results_dir)?;
+                ide_ci::fs::clean::reset_dir_async(&test_results_dir).await?;
 
                 // If we are run in CI conditions and we prepared some test results, we want to
                 // upload them as a separate artifact to ease debugging. And we do want to do that
@@ -560,6 +560,7 @@ impl RunContext {
         match operation {
             Operation::Release(ReleaseOperation { command, repo }) => match command {
                 ReleaseCommand::Upload => {
+                    self.paths.triple.target.ensure_released()?;
                     let artifacts = self.build().await?;
                     let release_id = crate::env::ENSO_RELEASE_ID.get()?;
                     let release = ide_ci::github::release::Handle::new(
@@ -574,8 +575,12 @@ impl RunContext {
                         bundle.upload_as_asset(release.clone()).await?;
                     }
                     if TARGET_OS == OS::Linux {
-                        release.upload_asset_file(self.paths.manifest_file()).await?;
-                        release.upload_asset_file(self.paths.launcher_manifest_file()).await?;
+                        let manifests =
+                            [self.paths.manifest_file(), self.paths.launcher_manifest_file()];
+                        for manifest in manifests {
+                            let name = manifest.try_file_name()?.to_owned();
+                            release.upload_asset_file_with_checksum_as(&manifest, name).await?;
+                        }
                     }
                 }
             },
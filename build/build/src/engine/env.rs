@@ -12,7 +12,7 @@ define_env_var! {
     CI_TEST_TIMEFACTOR, usize;
 
     /// Whether flaku tests should be run.
-    CI_TEST_FLAKY_ENABLE, bool;
+    CI_TEST_FLAKY_ENABLE, flag;
 
     /// GraalVM edition. Either Community or Enterprise.
     GRAAL_EDITION, graalvm::Edition;
@@ -141,11 +141,15 @@ impl BuiltEnso {
         let paths = &self.paths;
         // Environment for meta-tests. See:
         // https://github.com/enso-org/enso/tree/develop/test/Meta_Test_Suite_Tests
-        ENSO_META_TEST_COMMAND.set(&self.wrapper_script_path())?;
-        ENSO_META_TEST_ARGS.set(&format!("{} --run", ir_caches.flag()))?;
-
-        ENSO_ENABLE_ASSERTIONS.set("true")?;
-        ENSO_TEST_ANSI_COLORS.set("true")?;
+        //
+        // The variables are restored once the tests are done, so they do not leak into the later
+        // steps of the same run.
+        let _test_environment = [
+            ENSO_META_TEST_COMMAND.scoped_set(&self.wrapper_script_path())?,
+            ENSO_META_TEST_ARGS.scoped_set(&format!("{} --run", ir_caches.flag()))?,
+            ENSO_ENABLE_ASSERTIONS.scoped_set("true")?,
+            ENSO_TEST_ANSI_COLORS.scoped_set("true")?,
+        ];
 
         // Prepare Engine Test Environment
         if let Ok(gdoc_key) = std::env::var("GDOC_KEY") {
@@ -253,7 +257,7 @@ impl BuiltEnso {
         let futures = std_tests.into_iter().map(|test_path| {
             let command: std::result::Result<Command, anyhow::Error> =
                 self.run_test(test_path, ir_caches, environment_overrides.clone());
-            async move { command?.run_ok().await }
+            async move { command?.run_ok_with_configured_timeout().await }
         });
 
         // We need to join all the test tasks here, as they require postgres and httpbin alive.
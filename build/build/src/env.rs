@@ -15,5 +15,5 @@ define_env_var! {
     ENSO_NIGHTLY_EDITIONS_LIMIT, usize;
 
     /// Static token for admin requests on our Lambdas.
-    ENSO_ADMIN_TOKEN, String;
+    ENSO_ADMIN_TOKEN, secret String;
 }
@@ -19,8 +19,10 @@ pub mod env {
 /// It kills the process when dropped.
 #[derive(Debug)]
 pub struct Spawned {
-    pub process: Child,
-    pub url:     Url,
+    pub process:   Child,
+    pub url:       Url,
+    /// Restores the previous value of [`env::ENSO_HTTP_TEST_HTTPBIN_URL`] when dropped.
+    _url_variable: ide_ci::env::Guard,
 }
 
 pub async fn get_and_spawn_httpbin(
@@ -35,14 +37,13 @@ pub async fn get_and_spawn_httpbin(
 
     let url_string = format!("http://localhost:{port}");
     let url = Url::parse(&url_string)?;
-    env::ENSO_HTTP_TEST_HTTPBIN_URL.set(&url)?;
-    Ok(Spawned { url, process })
+    let url_variable = env::ENSO_HTTP_TEST_HTTPBIN_URL.scoped_set(&url)?;
+    Ok(Spawned { url, process, _url_variable: url_variable })
 }
 
 impl Drop for Spawned {
     fn drop(&mut self) {
         debug!("Dropping the httpbin wrapper.");
-        env::ENSO_HTTP_TEST_HTTPBIN_URL.remove();
         self.process.kill_subtree();
     }
 }
@@ -5,4 +5,5 @@
 // === Export ===
 // ==============
 
+pub mod update;
 pub mod web;
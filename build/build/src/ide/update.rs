@@ -0,0 +1,245 @@
+//! Auto-update metadata of the IDE packages.
+//!
+//! The desktop app's updater ([`electron-updater`](https://www.electron.build/auto-update))
+//! checks a [`Manifest`] published alongside the packages, like `latest.yml` on Windows or
+//! `latest-mac.yml` on macOS. The updater installs from its own [packages], which are not always
+//! the ones offered to the users for download. Each packaging step describes its packages in a
+//! manifest fragment. Before the release is published, the fragments of the platforms sharing the
+//! manifest (like both macOS architectures) are [merged](Manifest::merge) into the final files and
+//! the fragments are removed.
+//!
+//! [packages]: Target::update_package_filenames
+
+use crate::prelude::*;
+
+use crate::target::Target;
+use crate::target::MATRIX;
+
+use ide_ci::fs::atomic::Durability;
+use sha2::Digest;
+use std::collections::btree_map::Entry;
+
+
+
+ide_ci::define_env_var! {
+    /// Percentage of the users that should be offered the update, for a staged rollout. If not
+    /// set, the update is offered to all users.
+    ENSO_BUILD_IDE_UPDATE_STAGING_PERCENTAGE, u8;
+}
+
+/// Extension of the manifest fragment describing a single package.
+pub const FRAGMENT_EXTENSION: &str = "update.yml";
+
+/// Compute the SHA-512 checksum of the file, in the base64 encoding used by `electron-updater`.
+pub fn sha512_base64(path: impl AsRef<Path>) -> Result<String> {
+    let mut hasher = sha2::Sha512::new();
+    let mut file = ide_ci::fs::open(&path)?;
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(base64::encode(hasher.finalize()))
+}
+
+/// The staged rollout percentage, see [`ENSO_BUILD_IDE_UPDATE_STAGING_PERCENTAGE`].
+pub fn staging_percentage() -> Result<Option<u8>> {
+    let percentage = ENSO_BUILD_IDE_UPDATE_STAGING_PERCENTAGE.get_opt()?;
+    if let Some(percentage) = percentage {
+        ensure!(
+            (1..=100).contains(&percentage),
+            "{} must be between 1 and 100, got {percentage}.",
+            ENSO_BUILD_IDE_UPDATE_STAGING_PERCENTAGE.name()
+        );
+    }
+    Ok(percentage)
+}
+
+
+
+// ============
+// === File ===
+// ============
+
+/// A package that the update can be installed from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct File {
+    /// The package filename, relative to the manifest.
+    pub url:    String,
+    /// Base64-encoded SHA-512 checksum of the package.
+    pub sha512: String,
+    /// Size of the package in bytes.
+    pub size:   u64,
+}
+
+impl File {
+    /// Describe the package file.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        Ok(Self {
+            url:    path.try_file_name()?.to_string_lossy().into_owned(),
+            sha512: sha512_base64(path)?,
+            size:   ide_ci::fs::metadata(path)?.len(),
+        })
+    }
+}
+
+
+
+// ================
+// === Manifest ===
+// ================
+
+/// Update manifest in the format of `electron-updater`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub version:            Version,
+    pub files:              Vec<File>,
+    /// The primary package, kept for compatibility with the older updaters.
+    pub path:               String,
+    /// Checksum of the primary package, kept for compatibility with the older updaters.
+    pub sha512:             String,
+    /// Time of the release, in the RFC 3339 format.
+    pub release_date:       String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub staging_percentage: Option<u8>,
+}
+
+impl Manifest {
+    /// Describe the packages as the given version. The first package is the primary one.
+    pub fn new(
+        packages: impl IntoIterator<Item = impl AsRef<Path>>,
+        version: Version,
+    ) -> Result<Self> {
+        let files = packages.into_iter().map(File::new).collect::<Result<Vec<_>>>()?;
+        let primary = files.first().context("No packages to describe in the update manifest.")?;
+        Ok(Self {
+            version,
+            path: primary.url.clone(),
+            sha512: primary.sha512.clone(),
+            files,
+            release_date: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            staging_percentage: staging_percentage()?,
+        })
+    }
+
+    /// The platform of the primary package.
+    pub fn target(&self) -> Result<Target> {
+        MATRIX
+            .into_iter()
+            .find(|target| {
+                target.update_package_filenames(&self.version).first() == Some(&self.path)
+            })
+            .with_context(|| format!("No target platform has the IDE package {}.", self.path))
+    }
+
+    /// Add the packages described by the other manifest.
+    ///
+    /// The oldest release date is kept, so the merged manifest does not depend on the order of
+    /// merging.
+    pub fn merge(&mut self, other: Manifest) -> Result {
+        ensure!(
+            self.version == other.version,
+            "Cannot merge update manifests of versions {} and {}.",
+            self.version,
+            other.version
+        );
+        ensure!(
+            self.staging_percentage == other.staging_percentage,
+            "Cannot merge update manifests with different staging percentages."
+        );
+        self.files.extend(other.files);
+        self.release_date = self.release_date.clone().min(other.release_date);
+        Ok(())
+    }
+
+    /// Write the manifest into the given file.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result {
+        ide_ci::fs::atomic::write(path, serde_yaml::to_string(self)?, Durability::Durable)
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        serde_yaml::from_str(s).context("Failed to parse the update manifest.")
+    }
+}
+
+/// Merge the manifest fragments of the packages into the final manifests, keyed by their
+/// [filenames](Target::update_manifest_filename).
+///
+/// The primary package of a merged manifest is the one of the first merged fragment, so the
+/// fragments are merged in the order of their targets in the [`MATRIX`], not in the given order.
+pub fn merge_fragments(
+    fragments: impl IntoIterator<Item = Manifest>,
+) -> Result<BTreeMap<String, Manifest>> {
+    let mut fragments = fragments
+        .into_iter()
+        .map(|fragment| Ok((fragment.target()?, fragment)))
+        .collect::<Result<Vec<_>>>()?;
+    fragments.sort_by_cached_key(|(target, fragment)| {
+        (MATRIX.iter().position(|known| known == target), fragment.path.clone())
+    });
+    let mut manifests = BTreeMap::<String, Manifest>::new();
+    for (target, fragment) in fragments {
+        let filename = target.update_manifest_filename();
+        match manifests.entry(filename) {
+            Entry::Occupied(mut entry) => entry.get_mut().merge(fragment)?,
+            Entry::Vacant(entry) => {
+                entry.insert(fragment);
+            }
+        }
+    }
+    Ok(manifests)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merging_fragments() -> Result {
+        let temp = tempfile::tempdir()?;
+        let version = Version::new(2024, 1, 1);
+        let fragments =
+            [(OS::MacOS, Arch::X86_64), (OS::MacOS, Arch::AArch64), (OS::Linux, Arch::X86_64)]
+                .into_iter()
+                .map(|(os, arch)| {
+                    let target = Target::lookup(os, arch)?;
+                    let packages = target
+                        .update_package_filenames(&version)
+                        .into_iter()
+                        .map(|filename| temp.path().join(filename))
+                        .collect_vec();
+                    for package in &packages {
+                        ide_ci::fs::write(package, "package")?;
+                    }
+                    let manifest = Manifest::new(&packages, version.clone())?;
+                    Manifest::from_str(&serde_yaml::to_string(&manifest)?)
+                })
+                .collect::<Result<Vec<_>>>()?;
+        let linux = &fragments[2];
+        assert_eq!(linux.files[0].size, 7);
+        assert_eq!(linux.sha512, linux.files[0].sha512);
+
+        let manifests = merge_fragments(fragments.clone())?;
+        assert_eq!(manifests.keys().collect_vec(), ["latest-linux.yml", "latest-mac.yml"]);
+        let mac = &manifests["latest-mac.yml"];
+        assert_eq!(mac.path, "enso-mac-x64-2024.1.1.zip");
+        let urls = mac.files.iter().map(|file| file.url.as_str()).collect_vec();
+        assert_eq!(urls, [
+            "enso-mac-x64-2024.1.1.zip",
+            "enso-mac-x64-2024.1.1.dmg",
+            "enso-mac-arm64-2024.1.1.zip",
+            "enso-mac-arm64-2024.1.1.dmg"
+        ]);
+
+        let reversed = merge_fragments(fragments.iter().rev().cloned())?;
+        assert_eq!(reversed, manifests);
+
+        let mut newer = fragments[0].clone();
+        newer.version = Version::new(2024, 1, 2);
+        assert!(newer.merge(fragments[1].clone()).is_err());
+        Ok(())
+    }
+}
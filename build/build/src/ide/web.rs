@@ -5,15 +5,19 @@ use crate::prelude::*;
 use crate::paths::generated;
 use crate::project::gui::BuildInfo;
 use crate::project::IsArtifact;
+use crate::target::PackageFormat;
+use crate::target::Target;
 use crate::version::ENSO_VERSION;
 
 use anyhow::Context;
+use ide_ci::dag::Graph;
+use ide_ci::dag::Step;
 use ide_ci::env::known::electron_builder::WindowsSigningCredentials;
+use ide_ci::fs::atomic::Durability;
 use ide_ci::program::command::FallibleManipulator;
 use ide_ci::program::command::Manipulator;
 use ide_ci::programs::node::PnpmCommand;
 use ide_ci::programs::Pnpm;
-use sha2::Digest;
 use std::process::Stdio;
 use tempfile::TempDir;
 
@@ -101,34 +105,21 @@ pub mod env {
     }
 }
 
-/// Name of the directory with the unpacked Electron package.
+/// Path to the directory with the unpacked Electron package.
 ///
 /// The directory is created by the `electron-builder` utility in the output directory when run
 /// with the `dir` target. It is also usually created for other targets, as it is an intermediate
 /// step in the packaging process.
-///
-/// # Panics
-/// This function panics if the provided OS and architecture combination is not supported.
-pub fn unpacked_dir(output_path: impl AsRef<Path>, os: OS, arch: Arch) -> PathBuf {
-    let segment_name = match (os, arch) {
-        (OS::Linux, Arch::X86_64) => "linux-unpacked",
-        (OS::MacOS, Arch::AArch64) => "mac-arm64",
-        (OS::MacOS, Arch::X86_64) => "mac",
-        (OS::Windows, Arch::X86_64) => "win-unpacked",
-        _ => todo!("{os}-{arch} combination is not supported"),
-    };
-    output_path.as_ref().join(segment_name)
+pub fn unpacked_dir(output_path: impl AsRef<Path>, target: &Target) -> PathBuf {
+    output_path.as_ref().join(target.electron_unpacked_dir())
 }
 
 /// Computes the SHA-256 checksum of a file and writes it to a file.
 ///
 /// This is a Rust equivalent of the `app/ide-desktop/client/tasks/computeHashes.mjs`.
 pub fn store_sha256_checksum(file: impl AsRef<Path>, checksum_file: impl AsRef<Path>) -> Result {
-    let mut hasher = sha2::Sha256::new();
-    let mut file = ide_ci::fs::open(&file)?;
-    std::io::copy(&mut file, &mut hasher)?;
-    let hash = hasher.finalize();
-    ide_ci::fs::write(&checksum_file, format!("{hash:x}"))?;
+    let hash = ide_ci::checksums::sha256_file(&file)?;
+    ide_ci::fs::atomic::write(&checksum_file, hash, Durability::Durable)?;
     Ok(())
 }
 
@@ -161,9 +152,9 @@ pub struct RemoveEmptyCscEnvVars;
 impl Manipulator for RemoveEmptyCscEnvVars {
     fn apply<C: IsCommandWrapper + ?Sized>(&self, command: &mut C) {
         for var in ide_ci::env::known::electron_builder::CI_CSC_SECRETS {
-            match std::env::var(var) {
+            match var.get_raw() {
                 Ok(value) if value.is_empty() => {
-                    command.env_remove(var);
+                    command.env_remove(var.name);
                 }
                 _ => {}
             }
@@ -171,14 +162,6 @@ impl Manipulator for RemoveEmptyCscEnvVars {
     }
 }
 
-pub fn target_os_flag(os: OS) -> Result<&'static str> {
-    match os {
-        OS::Windows => Ok("--win"),
-        OS::Linux => Ok("--linux"),
-        OS::MacOS => Ok("--mac"),
-    }
-}
-
 /// Context information about Project Manager bundle that we provide to the client.
 #[derive(Clone, Debug)]
 pub struct ProjectManagerInfo {
@@ -259,18 +242,22 @@ impl IdeDesktop {
         dest = %output_path.as_ref().display(),
         ?gui,
         ?project_manager,
-        ?target_os,
-        ?target,
+        %target,
+        ?electron_target,
         err))]
     pub async fn dist(
         &self,
         gui: &impl IsArtifact,
         project_manager: &crate::project::backend::Artifact,
         output_path: impl AsRef<Path>,
-        target_os: OS,
-        target: Option<String>,
+        target: Target,
+        electron_target: Option<String>,
     ) -> Result {
         let output_path = output_path.as_ref();
+        // Custom electron-builder targets (like `dir`) do not produce the released package.
+        if electron_target.is_none() {
+            target.ensure_released()?;
+        }
         let electron_config = output_path.join("electron-builder.json");
         if TARGET_OS == OS::MacOS && env::CSC_KEY_PASSWORD.is_set() {
             // This means that we will be doing code signing on MacOS. This requires JDK environment
@@ -283,41 +270,62 @@ impl IdeDesktop {
 
         crate::web::install(&self.repo_root).await?;
         let pm_bundle = ProjectManagerInfo::new(project_manager)?;
-        self.pnpm()?
-            .set_env(env::ENSO_BUILD_GUI, gui.as_ref())?
-            .set_env(env::ENSO_BUILD_IDE, output_path)?
-            .try_applying(&pm_bundle)?
-            .run("build:ide")
-            .run_ok()
-            .await?;
+        let build_ide = async {
+            self.pnpm()?
+                .set_env(env::ENSO_BUILD_GUI, gui.as_ref())?
+                .set_env(env::ENSO_BUILD_IDE, output_path)?
+                .try_applying(&pm_bundle)?
+                .run("build:ide")
+                .run_ok()
+                .await
+        };
 
         let icons_dist = TempDir::new()?;
         let icons_dist = icons_dist.into_path();
-        let icons_build = self.build_icons(&icons_dist);
-        let icons = icons_build.await?;
+        let icons = IconsArtifacts(icons_dist.clone());
+        let build_icons = self.build_icons(&icons_dist).void_ok();
 
-        let target_args = match target {
-            Some(target) => vec!["--target".to_string(), target],
+        let target_args = match electron_target {
+            Some(electron_target) => vec!["--target".to_string(), electron_target],
             None => vec![],
         };
 
-        self.pnpm()?
-            .try_applying(&icons)?
-            .apply(&RemoveEmptyCscEnvVars)
-            // .env("DEBUG", "electron-builder")
-            .set_env(env::ENSO_BUILD_GUI, gui.as_ref())?
-            .set_env(env::ENSO_BUILD_IDE, output_path)?
-            .set_env(env::ENSO_BUILD_PROJECT_MANAGER, project_manager.as_ref())?
-            .set_env(enso_install_config::ENSO_BUILD_ELECTRON_BUILDER_CONFIG, &electron_config)?
-            .run("dist:ide")
-            .arg("--")
-            .arg(target_os_flag(target_os)?)
-            .args(target_args)
-            .run_ok()
+        let builder_config_variable = enso_install_config::ENSO_BUILD_ELECTRON_BUILDER_CONFIG;
+        let electron_builder = async {
+            self.pnpm()?
+                .try_applying(&icons)?
+                .apply(&RemoveEmptyCscEnvVars)
+                // .env("DEBUG", "electron-builder")
+                .set_env(env::ENSO_BUILD_GUI, gui.as_ref())?
+                .set_env(env::ENSO_BUILD_IDE, output_path)?
+                .set_env(env::ENSO_BUILD_PROJECT_MANAGER, project_manager.as_ref())?
+                .set_env(builder_config_variable, &electron_config)?
+                .run("dist:ide")
+                .arg("--")
+                .arg(target.electron_builder_flag())
+                .args(target_args)
+                .run_ok()
+                .await
+        };
+
+        // The IDE sources and icons are independent, so they are built concurrently.
+        Graph::new()?
+            .with(Step::new("Build IDE sources", build_ide).with_output("ide-sources"))
+            .with(Step::new("Build icons", build_icons).with_output("icons"))
+            .with(
+                Step::new("Run electron-builder", electron_builder)
+                    .with_input("ide-sources")
+                    .with_input("icons"),
+            )
+            .run()
             .await?;
 
-        // On Windows we build our own installer by invoking `enso_install_config::bundler::bundle`.
-        if TARGET_OS == OS::Windows {
+        let version = ENSO_VERSION.get()?;
+        let ide_artifacts = crate::project::ide::Artifact::new(&target, &version, output_path);
+
+        // The Windows installer is built by our own bundler,
+        // `enso_install_config::bundler::bundle`.
+        if target.package == PackageFormat::WindowsInstaller {
             let code_signing_certificate = WindowsSigningCredentials::new_from_env()
                 .await
                 .inspect_err(|e| {
@@ -325,24 +333,27 @@ impl IdeDesktop {
                 })
                 .ok();
 
-            let ide_artifacts = crate::project::ide::Artifact::new(
-                target_os,
-                TARGET_ARCH,
-                &ENSO_VERSION.get()?,
-                output_path,
-            );
-
             let config = enso_install_config::bundler::Config {
                 electron_builder_config:  electron_config,
-                unpacked_electron_bundle: unpacked_dir(output_path, target_os, TARGET_ARCH),
+                unpacked_electron_bundle: unpacked_dir(output_path, &target),
                 repo_root:                self.repo_root.to_path_buf(),
                 output_file:              ide_artifacts.image.clone(),
                 intermediate_dir:         output_path.to_path_buf(),
                 certificate:              code_signing_certificate,
             };
-            enso_install_config::bundler::bundle(config).await?;
+            let bundle = enso_install_config::bundler::bundle(config);
+            ide_ci::step!("Bundle Windows installer", bundle).await?;
             store_sha256_checksum(&ide_artifacts.image, &ide_artifacts.image_checksum)?;
         }
+
+        // Custom electron-builder targets (like `dir`) might not produce the packages.
+        if ide_artifacts.update_packages.iter().all(|package| package.exists()) {
+            let packages = &ide_artifacts.update_packages;
+            let manifest = crate::ide::update::Manifest::new(packages, version)?;
+            manifest.write(&ide_artifacts.update_manifest)?;
+        } else {
+            warn!("No auto-update packages found, the auto-update manifest will not be generated.");
+        }
         Ok(())
     }
 }
@@ -127,6 +127,7 @@ pub async fn download(cache: &Cache, octocrab: &Octocrab) -> Result<Box<Path>> {
                         mime::APPLICATION_OCTET_STREAM.as_ref(),
                     ),
                 )]),
+                sha256:             None,
             },
         })
         .await?
@@ -40,6 +40,7 @@ pub mod repo;
 pub mod rust;
 pub mod source;
 pub mod sqlserver;
+pub mod target;
 pub mod version;
 pub mod web;
 
@@ -4,7 +4,7 @@ pub mod s3 {
         ide_ci::define_env_var! {
             ENSO_LIB_S3_AWS_ACCESS_KEY_ID, String;
             ENSO_LIB_S3_AWS_REGION, String;
-            ENSO_LIB_S3_AWS_SECRET_ACCESS_KEY, String;
+            ENSO_LIB_S3_AWS_SECRET_ACCESS_KEY, secret String;
         }
     }
 }
@@ -15,7 +15,7 @@ pub mod snowflake {
         ide_ci::define_env_var! {
             ENSO_SNOWFLAKE_ACCOUNT, String;
             ENSO_SNOWFLAKE_USER, String;
-            ENSO_SNOWFLAKE_PASSWORD, String;
+            ENSO_SNOWFLAKE_PASSWORD, secret String;
             ENSO_SNOWFLAKE_DATABASE, String;
             ENSO_SNOWFLAKE_SCHEMA, String;
             ENSO_SNOWFLAKE_WAREHOUSE, String;
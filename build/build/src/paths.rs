@@ -1,7 +1,10 @@
 use crate::prelude::*;
 
+use crate::target::Target;
 use crate::version::Versions;
 
+use ide_ci::artifact_store::GitHubArtifacts;
+
 use std::env::consts::EXE_EXTENSION;
 use std::env::consts::EXE_SUFFIX;
 
@@ -64,15 +67,15 @@ pub fn new_repo_root(repo_root: impl Into<PathBuf>, triple: &TargetTriple) -> ge
 
 #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
 pub struct TargetTriple {
-    pub os:       OS,
-    pub arch:     Arch,
+    #[serde(flatten)]
+    pub target:   Target,
     pub versions: Versions,
 }
 
 impl TargetTriple {
-    /// Create a new triple with OS and architecture are inferred from the hosting system.
+    /// Create a new triple with the target platform inferred from the hosting system.
     pub fn new(versions: Versions) -> Self {
-        Self { os: TARGET_OS, arch: TARGET_ARCH, versions }
+        Self { target: Target::host(), versions }
     }
 
     /// Get the triple effectively used by the Engine build.
@@ -86,7 +89,7 @@ impl TargetTriple {
 
 impl Display for TargetTriple {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}-{}-{}", self.versions.version, self.os, self.arch)
+        write!(f, "{}-{}", self.versions.version, self.target)
     }
 }
 
@@ -167,15 +170,17 @@ impl Paths {
     }
 
     pub async fn upload_edition_file_artifact(&self) -> Result {
-        ide_ci::actions::artifacts::upload_single_file(
-            self.edition_file(),
+        ide_ci::artifact_store::upload(
+            &GitHubArtifacts,
             EDITION_FILE_ARTIFACT_NAME,
+            self.edition_file(),
         )
         .await
     }
 
     pub async fn download_edition_file_artifact(&self) -> Result {
-        ide_ci::actions::artifacts::download_single_file_artifact(
+        ide_ci::artifact_store::download(
+            &GitHubArtifacts,
             EDITION_FILE_ARTIFACT_NAME,
             self.edition_file(),
         )
@@ -141,8 +141,9 @@ pub trait IsTarget: Clone + Debug + Sized + Send + Sync + 'static {
     ) -> BoxFuture<'static, Result<Self::Artifact>> {
         let FetchTargetJob { inner: source, destination } = job;
         let this = self.clone();
+        let name = self.artifact_name();
         let span = debug_span!("Getting artifact from an external source");
-        match source {
+        let fetch = match source {
             ExternalSource::OngoingCiRun(OngoingCiRunSource { artifact_name }) => async move {
                 artifacts::retrieve_compressed_directory(artifact_name, &destination).await?;
                 this.adapt_artifact(destination).await
@@ -155,9 +156,8 @@ pub trait IsTarget: Clone + Debug + Sized + Send + Sync + 'static {
             }
             .boxed(),
             ExternalSource::Release(release) => self.download_asset(context, release, destination),
-        }
-        .instrument(span)
-        .boxed()
+        };
+        ide_ci::step!("Fetch {name}", fetch.instrument(span)).boxed()
     }
 
     /// Produce an artifact from build inputs.
@@ -170,12 +170,15 @@ pub trait IsTarget: Clone + Debug + Sized + Send + Sync + 'static {
         let upload_artifacts = job.should_upload_artifact;
         let artifact_fut = self.build_internal(context, job.map(|job| job.input));
         let this = self.clone();
+        let name = self.artifact_name();
         async move {
-            let artifact = artifact_fut.await.context(format!("Failed to build {this:?}."))?;
+            let artifact = ide_ci::step!("Build {name}", artifact_fut)
+                .await
+                .context(format!("Failed to build {this:?}."))?;
             // We upload only built artifacts. There would be no point in uploading something that
             // we've just downloaded. That's why the uploading code is here.
             if upload_artifacts {
-                this.perhaps_upload_artifact(&artifact).await?;
+                ide_ci::step!("Upload {name}", this.perhaps_upload_artifact(&artifact)).await?;
             }
             Ok(artifact)
         }
@@ -7,6 +7,7 @@ use crate::prelude::*;
 use crate::ide::web::IdeDesktop;
 use crate::paths::generated::RepoRootAppGui2Dist;
 use crate::paths::generated::RepoRootDistGui2Assets;
+use crate::project::wasm::size;
 use crate::project::Context;
 use crate::project::IsArtifact;
 use crate::project::IsTarget;
@@ -92,6 +93,11 @@ impl IsTarget for Gui {
             .await?;
             crate::web::install(repo_root).await?;
             crate::web::run_script(repo_root, crate::web::Script::Build).await?;
+            let budget = size::Budget::from_config(&crate::config::load()?);
+            ide_ci::step!("Check WASM size", async {
+                size::check(&repo_root.app.gui_2.dist, &repo_root.target, &budget)
+            })
+            .await?;
             ide_ci::fs::mirror_directory(
                 &repo_root.app.gui_2.dist,
                 &destination.join(RepoRootDistGui2Assets::segment_name()),
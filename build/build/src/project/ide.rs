@@ -3,6 +3,7 @@ use crate::prelude::*;
 
 use crate::project::gui::ide_desktop_from_context;
 use crate::project::Context;
+use crate::target::Target;
 
 use ide_ci::actions::artifacts::upload_compressed_directory;
 use ide_ci::actions::artifacts::upload_single_file;
@@ -20,27 +21,28 @@ pub struct Artifact {
     pub image:               PathBuf,
     /// File with the checksum of the image.
     pub image_checksum:      PathBuf,
+    /// Packages that the auto-updater installs the updates from, see
+    /// [`Target::update_package_filenames`]. They might include the image.
+    pub update_packages:     Vec<PathBuf>,
+    /// File with the auto-update manifest fragment describing the image, see
+    /// [`crate::ide::update`].
+    pub update_manifest:     PathBuf,
 }
 
 impl Artifact {
-    pub fn new(
-        target_os: OS,
-        target_arch: Arch,
-        version: &Version,
-        dist_dir: impl AsRef<Path>,
-    ) -> Self {
-        let unpacked = crate::ide::web::unpacked_dir(&dist_dir, target_os, target_arch);
-        let unpacked_executable = match target_os {
-            OS::Linux => "enso",
-            OS::MacOS => "Enso.app",
-            OS::Windows => "Enso.exe",
-        }
-        .into();
-
-        let image_filename = electron_image_filename(target_os, target_arch, version);
-        let image = dist_dir.as_ref().join(image_filename);
+    pub fn new(target: &Target, version: &Version, dist_dir: impl AsRef<Path>) -> Self {
+        let unpacked = crate::ide::web::unpacked_dir(&dist_dir, target);
+        let unpacked_executable = target.unpacked_executable().into();
+        let image = dist_dir.as_ref().join(target.ide_image_filename(version));
+        let update_packages = target
+            .update_package_filenames(version)
+            .into_iter()
+            .map(|filename| dist_dir.as_ref().join(filename))
+            .collect();
         Self {
+            update_packages,
             image_checksum: image.with_extension("sha256"),
+            update_manifest: image.with_extension(crate::ide::update::FRAGMENT_EXTENSION),
             image,
             unpacked,
             unpacked_executable,
@@ -55,6 +57,7 @@ impl Artifact {
             let packed_artifact_name = format!("{prefix}-{TARGET_OS}");
             upload_single_file(&self.image, &packed_artifact_name).await?;
             upload_single_file(&self.image_checksum, &packed_artifact_name).await?;
+            upload_single_file(&self.update_manifest, &packed_artifact_name).await?;
         } else {
             info!("Not in the CI environment, will not upload the artifacts.")
         }
@@ -92,14 +95,7 @@ pub struct BuildInput {
 
 #[derive(Clone, Copy, Debug)]
 pub struct Ide {
-    pub target_os:   OS,
-    pub target_arch: Arch,
-}
-
-impl Default for Ide {
-    fn default() -> Self {
-        Self { target_os: TARGET_OS, target_arch: TARGET_ARCH }
-    }
+    pub target: Target,
 }
 
 impl Ide {
@@ -111,32 +107,12 @@ impl Ide {
     ) -> BoxFuture<'static, Result<Artifact>> {
         let BuildInput { version, project_manager, gui, electron_target, artifact_name: _ } = input;
         let ide_desktop = ide_desktop_from_context(context);
-        let target_os = self.target_os;
-        let target_arch = self.target_arch;
+        let target = self.target;
         async move {
             let (gui, project_manager) = try_join!(gui, project_manager)?;
-            ide_desktop
-                .dist(&gui, &project_manager, &output_path, target_os, electron_target)
-                .await?;
-            Ok(Artifact::new(target_os, target_arch, &version, output_path))
+            ide_desktop.dist(&gui, &project_manager, &output_path, target, electron_target).await?;
+            Ok(Artifact::new(&target, &version, output_path))
         }
         .boxed()
     }
 }
-
-/// Filename of the image that electron-builder will produce.
-pub fn electron_image_filename(target_os: OS, target_arch: Arch, version: &Version) -> String {
-    // Electron-builder does something like this:
-    // https://github.com/electron-userland/electron-builder/blob/master/packages/builder-util/src/arch.ts
-    let arch_string = match (target_os, target_arch) {
-        (OS::Linux, Arch::X86_64) => "x86_64",
-        (_, Arch::X86_64) => "x64",
-        (_, Arch::AArch64) => "arm64",
-        _ => todo!("{target_os}-{target_arch} combination is not supported"),
-    };
-    match target_os {
-        OS::Linux => format!("enso-linux-{arch_string}-{version}.AppImage"),
-        OS::MacOS => format!("enso-mac-{arch_string}-{version}.dmg"),
-        OS::Windows => format!("enso-win-{arch_string}-{version}.exe"),
-    }
-}
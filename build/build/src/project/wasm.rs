@@ -11,6 +11,7 @@ use ide_ci::programs::Cargo;
 // ==============
 
 pub mod env;
+pub mod size;
 pub mod test;
 
 
@@ -23,6 +24,7 @@ pub mod test;
     Default,
     strum::Display,
     strum::EnumString,
+    strum::VariantNames,
     PartialEq,
     Eq
 )]
@@ -43,6 +45,7 @@ pub enum ProfilingLevel {
     Default,
     strum::Display,
     strum::EnumString,
+    strum::VariantNames,
     PartialEq,
     Eq
 )]
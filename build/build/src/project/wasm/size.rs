@@ -0,0 +1,494 @@
+//! Size analysis of the WASM binaries produced by the build, and enforcement of the size budgets.
+//!
+//! The size of each binary is broken down in a way similar to `twiggy`: the function bodies are
+//! attributed to crates based on their (possibly mangled) names from the `name` custom section,
+//! and the remaining sections are listed under their own names. If the binary was stripped of the
+//! names, all the code is listed as `[unnamed code]`.
+//!
+//! The breakdown is compared against the report stored by the previous run, so the size changes
+//! are visible in the build log. On CI, the previous report is restored from the cache by the
+//! workflow.
+
+use crate::prelude::*;
+
+use crate::config::Config;
+
+use bytesize::ByteSize;
+use std::cmp::Reverse;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// Name of the size report file, stored in the build's target directory.
+pub const REPORT_FILENAME: &str = "wasm-size-report.json";
+
+/// Number of the largest entries listed for each binary.
+pub const LISTED_ENTRIES: usize = 15;
+
+/// Entry gathering the code of functions without names.
+pub const UNNAMED_CODE: &str = "[unnamed code]";
+
+/// Entry gathering the code of functions whose names could not be attributed to a crate.
+pub const OTHER_CODE: &str = "[other code]";
+
+const WASM_MAGIC: &[u8] = b"\0asm";
+const CUSTOM_SECTION_ID: u8 = 0;
+const IMPORT_SECTION_ID: u8 = 2;
+const CODE_SECTION_ID: u8 = 10;
+const FUNCTION_NAMES_SUBSECTION_ID: u8 = 1;
+const FUNCTION_IMPORT_KIND: u8 = 0;
+const TABLE_IMPORT_KIND: u8 = 1;
+const MEMORY_IMPORT_KIND: u8 = 2;
+const GLOBAL_IMPORT_KIND: u8 = 3;
+const TAG_IMPORT_KIND: u8 = 4;
+
+
+
+// ==============
+// === Reader ===
+// ==============
+
+/// Reader of the primitive values of the WASM binary format.
+#[derive(Clone, Copy, Debug)]
+struct Reader<'a> {
+    data: &'a [u8],
+    pos:  usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        let byte = *self.data.get(self.pos).context("Unexpected end of the WASM binary.")?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(count).filter(|end| *end <= self.data.len());
+        let end = end.context("Unexpected end of the WASM binary.")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Unsigned LEB128-encoded integer.
+    fn leb128(&mut self) -> Result<u64> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        bail!("Invalid LEB128-encoded integer in the WASM binary.")
+    }
+
+    fn length(&mut self) -> Result<usize> {
+        Ok(usize::try_from(self.leb128()?)?)
+    }
+
+    fn name(&mut self) -> Result<&'a str> {
+        let length = self.length()?;
+        Ok(std::str::from_utf8(self.bytes(length)?)?)
+    }
+
+    fn limits(&mut self) -> Result {
+        let has_maximum = self.byte()? & 1 != 0;
+        self.leb128()?;
+        if has_maximum {
+            self.leb128()?;
+        }
+        Ok(())
+    }
+}
+
+
+
+// =================
+// === Breakdown ===
+// =================
+
+/// Size of a WASM binary, broken down by crates and sections.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Breakdown {
+    /// Size of the whole binary in bytes.
+    pub total:   u64,
+    /// Sizes of the crates' code and of the non-code sections in bytes.
+    pub entries: BTreeMap<String, u64>,
+}
+
+impl Breakdown {
+    /// Analyze the WASM binary.
+    pub fn analyze(wasm: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(wasm);
+        ensure!(reader.bytes(4)? == WASM_MAGIC, "Not a WASM binary.");
+        reader.bytes(4)?;
+
+        let mut imported_functions = 0;
+        let mut function_sizes = Vec::new();
+        let mut function_names = HashMap::new();
+        let mut entries = BTreeMap::<String, u64>::new();
+        let mut code_overhead = 0;
+        while !reader.is_empty() {
+            let start = reader.pos;
+            let id = reader.byte()?;
+            let length = reader.length()?;
+            let mut section = Reader::new(reader.bytes(length)?);
+            let size = (reader.pos - start) as u64;
+            match id {
+                IMPORT_SECTION_ID => {
+                    imported_functions = count_imported_functions(&mut section)?;
+                    *entries.entry("[import]".into()).or_default() += size;
+                }
+                CODE_SECTION_ID => {
+                    for _ in 0..section.leb128()? {
+                        let body_start = section.pos;
+                        let body_length = section.length()?;
+                        section.bytes(body_length)?;
+                        function_sizes.push((section.pos - body_start) as u64);
+                    }
+                    code_overhead = size - function_sizes.iter().sum::<u64>();
+                }
+                CUSTOM_SECTION_ID => {
+                    let name = section.name()?;
+                    if name == "name" {
+                        function_names = read_function_names(&mut section)?;
+                    }
+                    *entries.entry(format!("[custom {name}]")).or_default() += size;
+                }
+                _ => *entries.entry(format!("[{}]", section_name(id))).or_default() += size,
+            }
+        }
+
+        if code_overhead > 0 {
+            *entries.entry("[code]".into()).or_default() += code_overhead;
+        }
+        for (index, size) in function_sizes.into_iter().enumerate() {
+            let entry = match function_names.get(&(imported_functions + index as u64)) {
+                Some(name) => crate_name(name).unwrap_or(OTHER_CODE),
+                None => UNNAMED_CODE,
+            };
+            *entries.entry(entry.into()).or_default() += size;
+        }
+        Ok(Self { total: wasm.len() as u64, entries })
+    }
+}
+
+fn section_name(id: u8) -> String {
+    match id {
+        1 => "type".into(),
+        3 => "function".into(),
+        4 => "table".into(),
+        5 => "memory".into(),
+        6 => "global".into(),
+        7 => "export".into(),
+        8 => "start".into(),
+        9 => "element".into(),
+        11 => "data".into(),
+        12 => "data count".into(),
+        13 => "tag".into(),
+        _ => format!("section {id}"),
+    }
+}
+
+fn count_imported_functions(section: &mut Reader) -> Result<u64> {
+    let mut functions = 0;
+    for _ in 0..section.leb128()? {
+        section.name()?;
+        section.name()?;
+        match section.byte()? {
+            FUNCTION_IMPORT_KIND => {
+                section.leb128()?;
+                functions += 1;
+            }
+            TABLE_IMPORT_KIND => {
+                section.byte()?;
+                section.limits()?;
+            }
+            MEMORY_IMPORT_KIND => section.limits()?,
+            GLOBAL_IMPORT_KIND => {
+                section.bytes(2)?;
+            }
+            TAG_IMPORT_KIND => {
+                section.byte()?;
+                section.leb128()?;
+            }
+            kind => bail!("Unknown import kind {kind} in the WASM binary."),
+        }
+    }
+    Ok(functions)
+}
+
+fn read_function_names(section: &mut Reader) -> Result<HashMap<u64, String>> {
+    let mut names = HashMap::new();
+    while !section.is_empty() {
+        let id = section.byte()?;
+        let length = section.length()?;
+        let mut subsection = Reader::new(section.bytes(length)?);
+        if id == FUNCTION_NAMES_SUBSECTION_ID {
+            for _ in 0..subsection.leb128()? {
+                let index = subsection.leb128()?;
+                names.insert(index, subsection.name()?.to_owned());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Name of the crate defining the function, if it can be determined from the function's name.
+///
+/// Both the legacy-mangled (`_ZN4core3fmt5write17h…E`) and demangled (`core::fmt::write`) names
+/// are supported. For trait implementations (`<alloc::vec::Vec<T> as core::ops::Drop>::drop`),
+/// the crate of the implementing type is used.
+pub fn crate_name(function_name: &str) -> Option<&str> {
+    if let Some(mangled) = function_name.strip_prefix("_ZN") {
+        let digits = mangled.find(|c: char| !c.is_ascii_digit())?;
+        let length = mangled[..digits].parse::<usize>().ok()?;
+        let name = mangled.get(digits..digits + length)?;
+        // Trait implementations are mangled as `_$LT$crate..path..Type$u20$as$u20$...`.
+        let name = name.strip_prefix("_$LT$").unwrap_or(name);
+        let name = name.split("..").next()?;
+        (!name.is_empty()).then_some(name)
+    } else {
+        let path = function_name.trim_start_matches('<');
+        let (name, _) = path.split_once("::")?;
+        let is_identifier = name.chars().all(|c| c.is_alphanumeric() || c == '_');
+        (is_identifier && !name.is_empty()).then_some(name)
+    }
+}
+
+
+
+// ==============
+// === Budget ===
+// ==============
+
+/// The maximum allowed sizes of the WASM binaries.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Budget {
+    /// Maximum size of each binary.
+    pub total:  Option<ByteSize>,
+    /// Maximum size of the code of the given crates in each binary.
+    pub crates: BTreeMap<String, ByteSize>,
+}
+
+impl Budget {
+    /// The budget configured in the `build-config.yaml`.
+    pub fn from_config(config: &Config) -> Self {
+        Self { total: config.wasm_size_limit, crates: config.wasm_crate_size_limits.clone() }
+    }
+
+    /// Descriptions of the ways in which the binary exceeds the budget.
+    ///
+    /// A budgeted crate missing from the binary is a violation too, as its budget could not be
+    /// checked. This happens e.g. when the binary is stripped of the function names.
+    pub fn violations(&self, binary: &str, breakdown: &Breakdown) -> Vec<String> {
+        let total = self.total.filter(|limit| breakdown.total > limit.as_u64()).map(|limit| {
+            let (size, limit) = (format_size(breakdown.total), format_size(limit.as_u64()));
+            format!("{binary} has {size}, exceeding the limit of {limit}.")
+        });
+        let crates = self.crates.iter().filter_map(|(crate_name, limit)| {
+            let Some(&size) = breakdown.entries.get(crate_name) else {
+                return Some(format!(
+                    "Crate {crate_name} is not found in {binary}, so its size cannot be checked. \
+                    Is the binary stripped of the function names?"
+                ));
+            };
+            (size > limit.as_u64()).then(|| {
+                let (size, limit) = (format_size(size), format_size(limit.as_u64()));
+                format!(
+                    "Crate {crate_name} in {binary} has {size}, exceeding the limit of {limit}."
+                )
+            })
+        });
+        total.into_iter().chain(crates).collect()
+    }
+}
+
+
+
+// ==============
+// === Report ===
+// ==============
+
+/// Size breakdowns of all the WASM binaries produced by the build.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    /// Breakdowns of the binaries, identified by their [normalized names](binary_name).
+    pub binaries: BTreeMap<String, Breakdown>,
+}
+
+impl Report {
+    /// Analyze all the WASM binaries in the directory tree.
+    #[context("Failed to analyze the WASM binaries in {}.", root.as_ref().display())]
+    pub fn analyze_tree(root: impl AsRef<Path>) -> Result<Self> {
+        let mut binaries = BTreeMap::new();
+        for entry in walkdir::WalkDir::new(root.as_ref()) {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type().is_file() && path.extension().is_some_and(|e| e == "wasm") {
+                let breakdown = Breakdown::analyze(&ide_ci::fs::read(path)?)
+                    .with_context(|| format!("Failed to analyze {}.", path.display()))?;
+                binaries.insert(binary_name(path), breakdown);
+            }
+        }
+        Ok(Self { binaries })
+    }
+
+    /// Describe the report, including the size changes since the previous one.
+    pub fn describe(&self, previous: Option<&Report>) -> String {
+        let mut description = String::new();
+        for (name, breakdown) in &self.binaries {
+            let previous = previous.and_then(|previous| previous.binaries.get(name));
+            let total_change = size_change(breakdown.total, previous.map(|p| p.total));
+            description
+                .push_str(&format!("{name}: {}{total_change}\n", format_size(breakdown.total)));
+            let entries = breakdown.entries.iter().sorted_by_key(|(_, size)| Reverse(**size));
+            for (entry, size) in entries.take(LISTED_ENTRIES) {
+                let previous_size = previous.map(|p| p.entries.get(entry).copied().unwrap_or(0));
+                let change = size_change(*size, previous_size);
+                let share = *size as f64 / breakdown.total.max(1) as f64 * 100.0;
+                description.push_str(&format!(
+                    "  {:>10} {share:5.1}%  {entry}{change}\n",
+                    format_size(*size)
+                ));
+            }
+            if breakdown.entries.len() > LISTED_ENTRIES {
+                let more = breakdown.entries.len() - LISTED_ENTRIES;
+                description.push_str(&format!("  ... and {more} more entries\n"));
+            }
+        }
+        description
+    }
+}
+
+/// Name identifying the binary across builds.
+///
+/// The content hash that bundlers add to the file name (e.g. `rust_ffi_bg-1a2B3c4D.wasm`) is
+/// removed, as it changes with every build.
+pub fn binary_name(path: &Path) -> String {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    let is_hash =
+        |hash: &str| hash.len() == 8 && hash.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match stem.rsplit_once('-') {
+        Some((name, hash)) if is_hash(hash) => name.to_owned(),
+        _ => stem.into_owned(),
+    }
+}
+
+/// Format the size using binary units, like the limits in the `build-config.yaml`.
+pub fn format_size(bytes: u64) -> String {
+    ByteSize(bytes).to_string_as(true)
+}
+
+fn size_change(size: u64, previous: Option<u64>) -> String {
+    match previous {
+        Some(previous) if previous < size => format!(" (+{})", format_size(size - previous)),
+        Some(previous) if previous > size => format!(" (-{})", format_size(previous - size)),
+        Some(_) => String::new(),
+        None => " (new)".into(),
+    }
+}
+
+/// Report the sizes of the WASM binaries in the directory tree and check them against the budget.
+///
+/// The report is stored in the `target_dir` and used as the baseline by the next run. The build
+/// fails if any binary exceeds the budget.
+pub fn check(root: impl AsRef<Path>, target_dir: impl AsRef<Path>, budget: &Budget) -> Result {
+    let report = Report::analyze_tree(root)?;
+    if report.binaries.is_empty() {
+        debug!("No WASM binaries found, skipping the size check.");
+        return Ok(());
+    }
+    let report_path = target_dir.as_ref().join(REPORT_FILENAME);
+    let previous = report_path
+        .exists()
+        .then(|| ide_ci::fs::read_json::<Report>(&report_path))
+        .transpose()
+        .unwrap_or_else(|e| {
+            warn!("Ignoring the unreadable previous WASM size report: {e:?}");
+            None
+        });
+    info!("WASM binary sizes:\n{}", report.describe(previous.as_ref()));
+    ide_ci::fs::create_dir_if_missing(&target_dir)?;
+    ide_ci::fs::write_json(&report_path, &report)?;
+
+    let violations = report
+        .binaries
+        .iter()
+        .flat_map(|(name, breakdown)| budget.violations(name, breakdown))
+        .collect_vec();
+    ensure!(violations.is_empty(), "WASM size budget exceeded:\n{}", violations.join("\n"));
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module importing one function and defining two, with names from two crates.
+    fn sample_module() -> Vec<u8> {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        // Type section: a single `() -> ()` function type.
+        wasm.extend([1, 4, 1, 0x60, 0, 0]);
+        // Import section: `env.f` function of type 0.
+        wasm.extend([2, 9, 1, 3, b'e', b'n', b'v', 1, b'f', 0, 0]);
+        // Function section: two functions of type 0.
+        wasm.extend([3, 3, 2, 0, 0]);
+        // Code section: bodies of 4 and 2 bytes, each prefixed by its length.
+        wasm.extend([10, 9, 2, 4, 0, 1, 1, 0x0b, 2, 0, 0x0b]);
+        let names = [(1, "_ZN8rust_ffi5parse17h0123456789abcdefE"), (2, "core::fmt::write")];
+        let mut function_names = vec![names.len() as u8];
+        for (index, name) in names {
+            function_names.extend([index, name.len() as u8]);
+            function_names.extend(name.as_bytes());
+        }
+        let mut name_section = vec![4];
+        name_section.extend(b"name");
+        name_section.extend([FUNCTION_NAMES_SUBSECTION_ID, function_names.len() as u8]);
+        name_section.extend(function_names);
+        wasm.extend([CUSTOM_SECTION_ID, name_section.len() as u8]);
+        wasm.extend(name_section);
+        wasm
+    }
+
+    #[test]
+    fn breakdown_by_crate() -> Result {
+        let wasm = sample_module();
+        let breakdown = Breakdown::analyze(&wasm)?;
+        assert_eq!(breakdown.total, wasm.len() as u64);
+        assert_eq!(breakdown.entries.values().sum::<u64>(), breakdown.total - 8);
+        assert_eq!(breakdown.entries.get("rust_ffi"), Some(&5));
+        assert_eq!(breakdown.entries.get("core"), Some(&3));
+        assert_eq!(breakdown.entries.get("[code]"), Some(&3));
+
+        let budget = Budget { total: None, crates: [("core".into(), ByteSize(2))].into() };
+        let violations = budget.violations("sample", &breakdown);
+        assert_eq!(violations, ["Crate core in sample has 3 B, exceeding the limit of 2 B."]);
+
+        let budget = Budget { total: None, crates: [("missing".into(), ByteSize(2))].into() };
+        assert_eq!(budget.violations("sample", &breakdown).len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(crate_name("_ZN4core3fmt5write17h0123456789abcdefE"), Some("core"));
+        assert_eq!(crate_name("<alloc::vec::Vec<T> as core::ops::Drop>::drop"), Some("alloc"));
+        assert_eq!(crate_name("__wbindgen_malloc"), None);
+        assert_eq!(binary_name(Path::new("assets/rust_ffi_bg-1a2B3c4D.wasm")), "rust_ffi_bg");
+        assert_eq!(binary_name(Path::new("pkg/rust_ffi_bg.wasm")), "rust_ffi_bg");
+    }
+}
@@ -165,7 +165,7 @@ pub async fn test_all(repo_root: PathBuf, browsers: &[Browser]) -> Result {
                     )
                 })?)
                 .apply(&cargo::Color::Always)
-                .run_ok()
+                .run_ok_with_configured_timeout()
                 .await?;
         } else {
             debug!("Skipping crate with no wasm tests: {member_str}");
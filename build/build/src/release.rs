@@ -5,18 +5,25 @@ use crate::prelude::*;
 use crate::changelog::Changelog;
 use crate::context::BuildContext;
 use crate::env::ENSO_ADMIN_TOKEN;
+use crate::ide::update;
 use crate::paths::generated;
 use crate::paths::TargetTriple;
 use crate::paths::EDITION_FILE_ARTIFACT_NAME;
 use crate::project;
+use crate::target::Target;
 use crate::version;
 use crate::version::promote::Designation;
 use crate::version::Versions;
 
+use ide_ci::artifact_store::GitHubArtifacts;
+use ide_ci::checksums;
 use ide_ci::github;
 use ide_ci::io::web::handle_error_response;
+use ide_ci::programs::docker::BuildOptions;
+use ide_ci::programs::docker::BuildOutput;
 use ide_ci::programs::Docker;
 use ide_ci::programs::SevenZip;
+use ide_ci::release_notes;
 use octocrab::models::repos::Release;
 use octocrab::params::repos::Reference;
 use reqwest::Response;
@@ -142,6 +149,9 @@ pub async fn publish_release(context: &BuildContext) -> Result {
     let release = release_handle.get().await?;
     ensure!(release.draft, "Release has been already published!");
 
+    upload_update_manifests(&remote_repo, &release_handle).await?;
+    upload_checksums(&remote_repo, &release_handle).await?;
+
     debug!("Found the target release, will publish it.");
     release_handle.publish().await?;
     debug!("Done. Release URL: {}", release.url);
@@ -154,7 +164,8 @@ pub async fn publish_release(context: &BuildContext) -> Result {
     .edition_yaml;
 
 
-    ide_ci::actions::artifacts::download_single_file_artifact(
+    ide_ci::artifact_store::download(
+        &GitHubArtifacts,
         EDITION_FILE_ARTIFACT_NAME,
         &edition_file_path,
     )
@@ -177,6 +188,120 @@ pub async fn publish_release(context: &BuildContext) -> Result {
     Ok(())
 }
 
+/// Remove the asset left by a previous attempt to publish the release, together with its checksum
+/// manifest fragment.
+async fn remove_previous_asset(
+    release: &github::release::Handle,
+    info: &Release,
+    name: &str,
+) -> Result {
+    let names = [name.to_string(), checksums::fragment_name(name)];
+    for asset in info.assets.iter().filter(|asset| names.contains(&asset.name)) {
+        warn!("Replacing {} left by a previous attempt to publish the release.", asset.name);
+        release.delete_asset(asset).await?;
+    }
+    Ok(())
+}
+
+/// Merge the auto-update manifest fragments uploaded by the IDE packaging jobs and upload the
+/// resulting manifests, like `latest.yml`, that the desktop app's updater checks.
+///
+/// The fragments are removed from the release once they are merged.
+#[context("Failed to upload the auto-update manifests of the release {}.", release.id)]
+pub async fn upload_update_manifests(
+    repo: &github::repo::Handle<github::Repo>,
+    release: &github::release::Handle,
+) -> Result {
+    let info = release.get().await?;
+    let suffix = format!(".{}", update::FRAGMENT_EXTENSION);
+    let fragment_assets =
+        info.assets.iter().filter(|asset| asset.name.ends_with(&suffix)).collect_vec();
+    let mut fragments = Vec::new();
+    for asset in &fragment_assets {
+        debug!("Reading the auto-update manifest fragment {}.", asset.name);
+        let text = repo.download_asset(asset.id).await?.text().await?;
+        fragments.push(update::Manifest::from_str(&text)?);
+    }
+    if fragments.is_empty() {
+        warn!("The release has no auto-update manifest fragments, the IDE will not be updated.");
+        return Ok(());
+    }
+
+    let temp = tempdir()?;
+    for (filename, manifest) in update::merge_fragments(fragments)? {
+        let path = temp.path().join(&filename);
+        manifest.write(&path)?;
+        remove_previous_asset(release, &info, &filename).await?;
+        release.upload_asset_file_with_checksum_as(&path, &filename).await?;
+        info!("Uploaded {filename} describing {} packages.", manifest.files.len());
+    }
+    for asset in fragment_assets {
+        release.delete_asset(asset).await?;
+    }
+    Ok(())
+}
+
+/// Upload the checksum manifest of all the release's assets, so the downloads can be verified.
+///
+/// The manifest is merged from the fragments uploaded along with the assets, see
+/// [`checksums::FRAGMENT_EXTENSION`], which are then removed from the release. A manifest left by
+/// a previous attempt to publish the release is replaced. The manifest is signed if a signing key
+/// is configured, see [`ide_ci::checksums::sign`].
+#[context("Failed to upload the checksums of the release {}.", release.id)]
+pub async fn upload_checksums(
+    repo: &github::repo::Handle<github::Repo>,
+    release: &github::release::Handle,
+) -> Result {
+    let info = release.get().await?;
+    let fragment_suffix = format!(".{}", checksums::FRAGMENT_EXTENSION);
+    let is_manifest =
+        |name: &str| [checksums::MANIFEST_FILENAME, checksums::SIGNATURE_FILENAME].contains(&name);
+    let mut manifest = checksums::Manifest::new();
+    let mut fragments = Vec::new();
+    for asset in info.assets.iter().filter(|asset| asset.name.ends_with(&fragment_suffix)) {
+        debug!("Reading the checksum manifest fragment {}.", asset.name);
+        let text = repo.download_asset(asset.id).await?.text().await?;
+        manifest.merge(text.parse()?)?;
+        fragments.push(asset);
+    }
+    // The fragments merged by a previous attempt are already removed, but their entries are kept
+    // in its manifest. The fragments uploaded since then take precedence.
+    if let Some(previous) =
+        info.assets.iter().find(|asset| asset.name == checksums::MANIFEST_FILENAME)
+    {
+        let text = repo.download_asset(previous.id).await?.text().await?;
+        let previous: checksums::Manifest = text.parse()?;
+        for (name, checksum) in previous.entries {
+            manifest.entries.entry(name).or_insert(checksum);
+        }
+    }
+    let assets = info
+        .assets
+        .iter()
+        .map(|asset| asset.name.as_str())
+        .filter(|name| !name.ends_with(&fragment_suffix) && !is_manifest(name))
+        .collect::<BTreeSet<_>>();
+    manifest.entries.retain(|name, _| assets.contains(name.as_str()));
+    let missing = assets.iter().filter(|name| !manifest.entries.contains_key(**name)).join(", ");
+    ensure!(missing.is_empty(), "No checksums were uploaded for: {missing}.");
+    ensure!(!manifest.entries.is_empty(), "The release has no assets.");
+
+    for name in [checksums::MANIFEST_FILENAME, checksums::SIGNATURE_FILENAME] {
+        remove_previous_asset(release, &info, name).await?;
+    }
+    let temp = tempdir()?;
+    let manifest_path = manifest.write(temp.path())?;
+    release.upload_asset_file(&manifest_path).await?;
+    if let Some(signature_path) = checksums::sign(&manifest_path).await? {
+        release.upload_asset_file(&signature_path).await?;
+    }
+    for fragment in fragments {
+        release.delete_asset(fragment).await?;
+    }
+    info!("Uploaded the checksums of {} release assets.", manifest.entries.len());
+    Ok(())
+}
+
 /// Perform basic check if the release contains advertised assets.
 ///
 /// This should be run only on a published (non-draft) release, as asset download URLs change after
@@ -227,12 +352,15 @@ pub async fn get_engine_package<R: IsRepo>(
 
 /// Download the Enso Engine distribution from the GitHub release and build Runtime Docker image
 /// from it.
+///
+/// The build options can be adjusted by `opts`, e.g. to set the tags or the layer caches.
 pub async fn generate_runtime_image(
     context: &BuildContext,
-    tag: impl Into<String>,
-) -> Result<ide_ci::programs::docker::ImageId> {
+    opts: impl FnOnce(&mut BuildOptions),
+) -> Result<BuildOutput> {
     // Our runtime images always target Linux.
-    let linux_triple = TargetTriple { os: OS::Linux, ..context.triple.clone() };
+    let linux = Target::for_platform(OS::Linux, context.triple.target.arch);
+    let linux_triple = TargetTriple { target: linux, ..context.triple.clone() };
     let temp_for_extraction = tempdir()?;
     let engine_package = get_engine_package(
         &context.remote_repo_handle(),
@@ -243,7 +371,8 @@ pub async fn generate_runtime_image(
     crate::aws::ecr::runtime::build_runtime_image(
         context.repo_root.tools.ci.docker.clone(),
         engine_package,
-        tag.into(),
+        &context.triple.versions.version,
+        opts,
     )
     .await
 }
@@ -251,15 +380,29 @@ pub async fn generate_runtime_image(
 /// Perform deploy of the backend to the ECR.
 ///
 /// Downloads the Engine package from the release, builds the runtime image from it and pushes it
-/// to our ECR.
+/// to our ECR. The layers are cached in the repository, so unchanged layers are neither rebuilt
+/// nor uploaded again.
 pub async fn deploy_to_ecr(context: &BuildContext, repository: String) -> Result {
     let client = crate::aws::ecr::client_from_env().await;
     let repository_uri = crate::aws::ecr::get_repository_uri(&client, &repository).await?;
     let tag = format!("{}:{}", repository_uri, context.triple.versions.version);
-    // We don't care about the image ID, we will refer to it by the tag.
-    let _image_id = generate_runtime_image(context, &tag).await?;
+    let cache = format!("{repository_uri}:{}", crate::aws::ecr::runtime::CACHE_TAG);
     let credentials = crate::aws::ecr::get_credentials(&client).await?;
-    Docker.while_logged_in(credentials, || async move { Docker.push(&tag).await }).await?;
+    Docker.ensure_container_builder(crate::aws::ecr::runtime::BUILDER).await?;
+    let output = Docker
+        .while_logged_in(credentials, || {
+            generate_runtime_image(context, |opts| {
+                opts.use_builder(crate::aws::ecr::runtime::BUILDER);
+                opts.tags.push(tag.clone());
+                opts.use_registry_cache(cache);
+                opts.push();
+            })
+        })
+        .await?;
+    match output.pinned_reference(&repository_uri) {
+        Some(reference) => info!("Pushed {tag} as {reference}."),
+        None => warn!("Pushed {tag}, but its digest was not reported."),
+    }
     Ok(())
 }
 
@@ -274,7 +417,8 @@ pub async fn put_files_gzipping(
     for file in files {
         let gzipped_file = file.with_parent(&temp_for_gzipping).with_appended_extension("gz");
         SevenZip.pack(&gzipped_file, [file]).await?;
-        bucket.put_file(&gzipped_file).await?;
+        let name = gzipped_file.try_file_name()?;
+        ide_ci::artifact_store::upload_unverified(bucket, name.as_str(), &gzipped_file).await?;
     }
     Ok(())
 }
@@ -298,6 +442,36 @@ pub async fn notify_cloud_about_gui(version: &Version) -> Result<Response> {
     handle_error_response(response).await
 }
 
+/// Generate the release notes and the changelog from the commits since the `from` revision.
+///
+/// If `from` is not given, the latest stable release is used. The results are written as
+/// `release-notes.md` and `changelog.json` into the `output` directory.
+pub async fn generate_release_notes(
+    context: &BuildContext,
+    from: Option<String>,
+    to: &str,
+    output: &Path,
+) -> Result<release_notes::Changelog> {
+    let git = context.git().await?;
+    let from = match from {
+        Some(from) => Some(from),
+        None => {
+            let releases = version::promote::Releases::from_remote(&git).await?;
+            let latest_stable = releases.latest_of_kind(version::Kind::Stable);
+            latest_stable.map(|version| Versions::new(version.clone()).tag())
+        }
+    };
+    let changelog = release_notes::Changelog::from_history(&git, from.as_deref(), to).await?;
+    changelog.write(&context.remote_repo, output)?;
+    info!(
+        "Generated release notes for {} changes since {}, suggesting a {:?} version bump.",
+        changelog.entries.len(),
+        from.as_deref().unwrap_or("the beginning"),
+        changelog.bump
+    );
+    Ok(changelog)
+}
+
 /// Generate a new version number of a requested kind.
 pub async fn resolve_version_designation(
     context: &BuildContext,
@@ -350,7 +524,7 @@ mod tests {
                 cache:     Cache::new_default().await?,
             },
             remote_repo: github::Repo::new("enso-org", "enso"),
-            triple:      TargetTriple::new(Versions::new(version.clone())),
+            triple:      triple.clone(),
         };
 
         let release_body = generate_release_body(&context).await?;
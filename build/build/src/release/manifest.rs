@@ -5,7 +5,6 @@
 use crate::prelude::*;
 
 use crate::paths::TargetTriple;
-use crate::project;
 use crate::version::Versions;
 
 
@@ -27,20 +26,14 @@ pub struct Asset {
 
 impl Asset {
     pub fn new(url: Url, triple: &TargetTriple) -> Self {
-        let target_pretty = match (triple.os, triple.arch) {
-            (OS::Windows, Arch::X86_64) => "Windows".into(),
-            (OS::Linux, Arch::X86_64) => "Linux".into(),
-            (OS::MacOS, Arch::X86_64) => "macOS (Intel)".into(),
-            (OS::MacOS, Arch::AArch64) => "macOS (Apple silicon)".into(),
-            (os, arch) => format!("{os} {arch}"),
-        };
-        Self { os: triple.os, arch: triple.arch, url, target_pretty }
+        let target = &triple.target;
+        let target_pretty = target.pretty_name.into();
+        Self { os: target.os, arch: target.arch, url, target_pretty }
     }
 
     /// Description od the asset with IDE image.
     pub fn new_ide(repo: &impl IsRepo, triple: &TargetTriple) -> Self {
-        let filename =
-            project::ide::electron_image_filename(triple.os, triple.arch, &triple.versions.version);
+        let filename = triple.target.ide_image_filename(&triple.versions.version);
         let url = ide_ci::github::release::download_asset(repo, &triple.versions.version, filename);
         Self::new(url, triple)
     }
@@ -49,7 +42,7 @@ impl Asset {
     pub fn new_engine(repo: &impl IsRepo, triple: &TargetTriple) -> Self {
         use crate::paths::generated::RepoRootBuiltDistributionEnsoBundleTriple;
         let stem = RepoRootBuiltDistributionEnsoBundleTriple::segment_name(triple.to_string());
-        let ext = ide_ci::github::release::archive_extension_for(triple.os);
+        let ext = ide_ci::github::release::archive_extension_for(triple.target.os);
         let filename = format!("{stem}.{ext}");
         let url = ide_ci::github::release::download_asset(repo, &triple.versions.version, filename);
         Self::new(url, triple)
@@ -76,8 +69,8 @@ pub struct Assets {
 impl Assets {
     pub fn new(repo: &impl IsRepo, version: &Version) -> Self {
         let mut ret = Self { ide: vec![], engine: vec![], version: version.clone() };
-        for (os, arch) in crate::ci_gen::RELEASE_TARGETS {
-            let triple = TargetTriple { os, arch, versions: Versions::new(version.clone()) };
+        for target in crate::target::MATRIX {
+            let triple = TargetTriple { target, versions: Versions::new(version.clone()) };
             ret.ide.push(Asset::new_ide(repo, &triple));
             ret.engine.push(Asset::new_engine(repo, &triple));
         }
@@ -31,7 +31,7 @@ pub fn cargo_run_generator_cmd(repo_root: &Path, binary_name: &str) -> Result<Co
 pub async fn generate_java_to(repo_root: &Path, output_path: &Path) -> Result {
     // Generator requires that output directory exists.
     // Also, we remove its previous content so the old artifacts don't pollute the output.
-    ide_ci::fs::tokio::reset_dir(&output_path).await?;
+    ide_ci::fs::clean::reset_dir_async(output_path).await?;
     cargo_run_generator_cmd(repo_root, GENERATOR_BIN_NAME)?
         .arg("--")
         .arg(output_path)
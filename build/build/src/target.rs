@@ -0,0 +1,316 @@
+//! Platforms that the Enso packages are built for.
+//!
+//! Each supported platform is an entry in the [`MATRIX`]. The platform-specific naming
+//! conventions (of the Rust toolchain, electron-builder and the release assets) are derived from
+//! the entry, so supporting a new platform (e.g. Linux on AArch64) should be a matter of adding
+//! a new entry, rather than updating conditional logic all over the build script.
+//!
+//! The build script can still run on the platforms missing from the matrix, using a
+//! [derived](Target::derived) target. Only the packaging requires the platform to be
+//! [released](Target::ensure_released).
+
+use crate::prelude::*;
+
+
+
+/// All the platforms that we build and release the packages for.
+pub const MATRIX: [Target; 4] = [
+    Target::new(OS::Windows, Arch::X86_64, Libc::Msvc, PackageFormat::WindowsInstaller, "Windows"),
+    Target::new(OS::Linux, Arch::X86_64, Libc::Gnu, PackageFormat::AppImage, "Linux"),
+    Target::new(OS::MacOS, Arch::X86_64, Libc::System, PackageFormat::Dmg, "macOS (Intel)"),
+    Target::new(
+        OS::MacOS,
+        Arch::AArch64,
+        Libc::System,
+        PackageFormat::Dmg,
+        "macOS (Apple silicon)",
+    ),
+];
+
+
+
+// ============
+// === Libc ===
+// ============
+
+/// The C runtime library that the native binaries are linked against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Libc {
+    /// The GNU C library.
+    Gnu,
+    /// The musl library, used for statically linked Linux binaries.
+    Musl,
+    /// The Microsoft Visual C++ runtime.
+    Msvc,
+    /// The platform has a single system C library, like `libSystem` on macOS.
+    System,
+}
+
+impl Libc {
+    /// The environment component of the Rust target triple, if the platform uses one.
+    pub fn rust_environment(self) -> Option<&'static str> {
+        match self {
+            Libc::Gnu => Some("gnu"),
+            Libc::Musl => Some("musl"),
+            Libc::Msvc => Some("msvc"),
+            Libc::System => None,
+        }
+    }
+}
+
+
+
+// =====================
+// === PackageFormat ===
+// =====================
+
+/// Format of the IDE package distributed to the users.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PackageFormat {
+    /// Self-contained Linux executable.
+    AppImage,
+    /// macOS disk image.
+    Dmg,
+    /// Windows installer, built by our own bundler rather than electron-builder.
+    WindowsInstaller,
+}
+
+impl PackageFormat {
+    /// Extension of the package file.
+    pub fn extension(self) -> &'static str {
+        match self {
+            PackageFormat::AppImage => "AppImage",
+            PackageFormat::Dmg => "dmg",
+            PackageFormat::WindowsInstaller => "exe",
+        }
+    }
+}
+
+
+
+// ==============
+// === Target ===
+// ==============
+
+/// A platform that the packages are built for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Target {
+    pub os:          OS,
+    pub arch:        Arch,
+    pub libc:        Libc,
+    pub package:     PackageFormat,
+    /// User-friendly name of the platform, used e.g. on the website.
+    pub pretty_name: &'static str,
+}
+
+impl Target {
+    pub const fn new(
+        os: OS,
+        arch: Arch,
+        libc: Libc,
+        package: PackageFormat,
+        pretty_name: &'static str,
+    ) -> Self {
+        Self { os, arch, libc, package, pretty_name }
+    }
+
+    /// Find the entry for the given platform in the [`MATRIX`].
+    pub fn lookup(os: OS, arch: Arch) -> Result<Self> {
+        MATRIX.into_iter().find(|target| target.os == os && target.arch == arch).with_context(
+            || {
+                let supported = MATRIX.iter().join(", ");
+                format!("Target {os}-{arch} is not supported. Supported targets: {supported}.")
+            },
+        )
+    }
+
+    /// The platform with the conventional settings for the given operating system.
+    pub fn derived(os: OS, arch: Arch) -> Self {
+        let (libc, package, pretty_name) = match os {
+            OS::Windows => (Libc::Msvc, PackageFormat::WindowsInstaller, "Windows"),
+            OS::Linux => (Libc::Gnu, PackageFormat::AppImage, "Linux"),
+            OS::MacOS => (Libc::System, PackageFormat::Dmg, "macOS"),
+        };
+        Self::new(os, arch, libc, package, pretty_name)
+    }
+
+    /// The entry for the given platform in the [`MATRIX`], or the [derived](Self::derived) one if
+    /// the platform is not released.
+    pub fn for_platform(os: OS, arch: Arch) -> Self {
+        Self::lookup(os, arch).unwrap_or_else(|_| Self::derived(os, arch))
+    }
+
+    /// The platform that the build script is running on.
+    pub fn host() -> Self {
+        Self::for_platform(TARGET_OS, TARGET_ARCH)
+    }
+
+    /// Fail if the packages are not released for this platform, i.e. it is not in the [`MATRIX`].
+    pub fn ensure_released(&self) -> Result {
+        Self::lookup(self.os, self.arch).map(|_| ())
+    }
+
+    /// The platform as used in the CI workflow definitions.
+    pub fn workflow_target(self) -> ide_ci::actions::workflow::definition::Target {
+        (self.os, self.arch)
+    }
+
+    /// The Rust target triple, like `x86_64-unknown-linux-gnu`.
+    pub fn rust_triple(&self) -> String {
+        let (vendor, os) = match self.os {
+            OS::Windows => ("pc", "windows"),
+            OS::Linux => ("unknown", "linux"),
+            OS::MacOS => ("apple", "darwin"),
+        };
+        let arch = match self.arch {
+            Arch::X86_64 => "x86_64",
+            Arch::AArch64 => "aarch64",
+            Arch::X86 => "i686",
+            Arch::Wasm32 => "wasm32",
+        };
+        let mut triple = format!("{arch}-{vendor}-{os}");
+        if let Some(environment) = self.libc.rust_environment() {
+            triple.push('-');
+            triple.push_str(environment);
+        }
+        triple
+    }
+
+    /// The platform name used by electron-builder.
+    pub fn electron_platform(&self) -> &'static str {
+        match self.os {
+            OS::Windows => "win",
+            OS::Linux => "linux",
+            OS::MacOS => "mac",
+        }
+    }
+
+    /// The electron-builder command line flag selecting this platform.
+    pub fn electron_builder_flag(&self) -> &'static str {
+        match self.os {
+            OS::Windows => "--win",
+            OS::Linux => "--linux",
+            OS::MacOS => "--mac",
+        }
+    }
+
+    /// The architecture name used by electron-builder in the package names.
+    ///
+    /// See <https://github.com/electron-userland/electron-builder/blob/master/packages/builder-util/src/arch.ts>.
+    pub fn electron_arch(&self) -> &'static str {
+        match (self.package, self.arch) {
+            (PackageFormat::AppImage, Arch::X86_64) => "x86_64",
+            (_, Arch::X86_64) => "x64",
+            (_, Arch::AArch64) => "arm64",
+            (_, Arch::X86) => "ia32",
+            (_, Arch::Wasm32) => "wasm32",
+        }
+    }
+
+    /// Name of the directory with the unpacked Electron package, created by electron-builder.
+    pub fn electron_unpacked_dir(&self) -> String {
+        let platform = self.electron_platform();
+        match (self.os, self.arch) {
+            (OS::MacOS, Arch::X86_64) => platform.into(),
+            (OS::MacOS, _) => format!("{platform}-{}", self.electron_arch()),
+            (_, Arch::X86_64) => format!("{platform}-unpacked"),
+            _ => format!("{platform}-{}-unpacked", self.electron_arch()),
+        }
+    }
+
+    /// Entry point within the unpacked Electron package.
+    pub fn unpacked_executable(&self) -> &'static str {
+        match self.os {
+            OS::Windows => "Enso.exe",
+            OS::Linux => "enso",
+            OS::MacOS => "Enso.app",
+        }
+    }
+
+    /// Filename of the `electron-updater` manifest describing the latest IDE package.
+    ///
+    /// The packages for different architectures of Windows and macOS share a single manifest.
+    pub fn update_manifest_filename(&self) -> String {
+        match (self.os, self.arch) {
+            (OS::Windows, _) => "latest.yml".into(),
+            (OS::MacOS, _) => "latest-mac.yml".into(),
+            (OS::Linux, Arch::X86_64) => "latest-linux.yml".into(),
+            (OS::Linux, _) => format!("latest-linux-{}.yml", self.electron_arch()),
+        }
+    }
+
+    /// Filename of the IDE package.
+    pub fn ide_image_filename(&self, version: &Version) -> String {
+        let platform = self.electron_platform();
+        let arch = self.electron_arch();
+        format!("enso-{platform}-{arch}-{version}.{}", self.package.extension())
+    }
+
+    /// Filenames of the packages that `electron-updater` installs the updates from, the primary
+    /// one first.
+    ///
+    /// The updater cannot install from the macOS disk image nor from the installer built by our
+    /// bundler, so electron-builder additionally builds a zip archive and an NSIS installer.
+    pub fn update_package_filenames(&self, version: &Version) -> Vec<String> {
+        let platform = self.electron_platform();
+        let arch = self.electron_arch();
+        match self.package {
+            PackageFormat::AppImage => vec![self.ide_image_filename(version)],
+            PackageFormat::Dmg => vec![
+                format!("enso-{platform}-{arch}-{version}.zip"),
+                self.ide_image_filename(version),
+            ],
+            PackageFormat::WindowsInstaller =>
+                vec![format!("enso-{platform}-{arch}-{version}-nsis.exe")],
+        }
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.os, self.arch)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platform_names() -> Result {
+        let version = Version::new(2024, 1, 1);
+        let linux = Target::lookup(OS::Linux, Arch::X86_64)?;
+        assert_eq!(linux.rust_triple(), "x86_64-unknown-linux-gnu");
+        assert_eq!(linux.electron_unpacked_dir(), "linux-unpacked");
+        assert_eq!(linux.ide_image_filename(&version), "enso-linux-x86_64-2024.1.1.AppImage");
+
+        let apple_silicon = Target::lookup(OS::MacOS, Arch::AArch64)?;
+        assert_eq!(apple_silicon.rust_triple(), "aarch64-apple-darwin");
+        assert_eq!(apple_silicon.electron_unpacked_dir(), "mac-arm64");
+        assert_eq!(apple_silicon.ide_image_filename(&version), "enso-mac-arm64-2024.1.1.dmg");
+        let mac_packages = ["enso-mac-arm64-2024.1.1.zip", "enso-mac-arm64-2024.1.1.dmg"];
+        assert_eq!(apple_silicon.update_package_filenames(&version), mac_packages);
+
+        let windows = Target::lookup(OS::Windows, Arch::X86_64)?;
+        assert_eq!(windows.rust_triple(), "x86_64-pc-windows-msvc");
+        assert_eq!(windows.electron_unpacked_dir(), "win-unpacked");
+        assert_eq!(windows.ide_image_filename(&version), "enso-win-x64-2024.1.1.exe");
+        assert_eq!(windows.update_package_filenames(&version), ["enso-win-x64-2024.1.1-nsis.exe"]);
+
+        let linux_arm =
+            Target::new(OS::Linux, Arch::AArch64, Libc::Gnu, PackageFormat::AppImage, "");
+        assert_eq!(linux_arm.electron_unpacked_dir(), "linux-arm64-unpacked");
+        assert_eq!(linux_arm.ide_image_filename(&version), "enso-linux-arm64-2024.1.1.AppImage");
+        assert_eq!(linux_arm.update_manifest_filename(), "latest-linux-arm64.yml");
+
+        assert!(Target::lookup(OS::Windows, Arch::AArch64).is_err());
+        let windows_arm = Target::for_platform(OS::Windows, Arch::AArch64);
+        assert_eq!(windows_arm.rust_triple(), "aarch64-pc-windows-msvc");
+        assert!(windows_arm.ensure_released().is_err());
+        assert!(Target::for_platform(OS::Linux, Arch::X86_64).ensure_released().is_ok());
+        Ok(())
+    }
+}
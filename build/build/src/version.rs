@@ -40,7 +40,7 @@ define_env_var! {
     ENSO_EDITION, String;
 
     /// Whether the development-specific Engine features should be disabled.
-    ENSO_RELEASE_MODE, bool;
+    ENSO_RELEASE_MODE, flag;
 }
 
 
@@ -7,6 +7,7 @@ use crate::version::nightly::NightlyPrerelease;
 use chrono::Datelike;
 use ide_ci::programs::git;
 use ide_ci::programs::git::Ref;
+use ide_ci::release_notes::Bump;
 
 
 
@@ -35,6 +36,19 @@ pub enum Designation {
     Nightly,
 }
 
+impl Designation {
+    /// The designation of a release containing changes requiring the given version bump.
+    ///
+    /// Following our versioning scheme, features and breaking changes go into a new stable
+    /// release, while a release with fixes only can be a patch.
+    pub fn for_bump(bump: Bump) -> Self {
+        match bump {
+            Bump::Major | Bump::Minor => Designation::Stable,
+            Bump::Patch => Designation::Patch,
+        }
+    }
+}
+
 pub async fn releases_on_remote(git: &git::Context) -> Result<Vec<Version>> {
     let remote_tags = git.list_remote_tags().await?;
     Ok(remote_tags
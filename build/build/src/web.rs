@@ -4,7 +4,7 @@
 
 use crate::prelude::*;
 
-use ide_ci::io::retry;
+use ide_ci::program::command::retry::RetryPolicy;
 use ide_ci::programs::Pnpm;
 
 
@@ -29,7 +29,7 @@ static ONCE_INSTALL: tokio::sync::OnceCell<Result> = tokio::sync::OnceCell::cons
 pub fn install(repo_root: impl AsRef<Path>) -> BoxFuture<'static, Result> {
     let repo_root = repo_root.as_ref().to_owned();
     ONCE_INSTALL
-        .get_or_init(move || retry(move || install_internal_run(&repo_root)))
+        .get_or_init(move || install_internal_run(&repo_root))
         .map(|fut_res_ref| {
             fut_res_ref
                 .as_ref()
@@ -43,7 +43,9 @@ pub fn install(repo_root: impl AsRef<Path>) -> BoxFuture<'static, Result> {
 fn install_internal_run(path: &Path) -> impl Future<Output = Result> + 'static {
     Pnpm.cmd().and_then_async(move |cmd| {
         let err = format!("Failed to install NPM dependencies in {}.", path.display());
-        cmd.with_current_dir(path).install().run_ok().context(err)
+        let mut cmd = cmd.with_current_dir(path);
+        cmd.install();
+        cmd.into_inner().run_ok_with_retries(RetryPolicy::default()).context(err)
     })
 }
 
@@ -12,7 +12,7 @@ use crate::github::Repo;
 define_env_var! {
     /// Always set to true when being run under GitHub Actions runner. Also, this is often set on
     /// other CI systems.
-    CI, bool;
+    CI, flag;
 
     /// The name of the action currently running, or the id of a step. For example, for an action,
     /// `__repo-owner_name-of-action-repo`.
@@ -29,7 +29,7 @@ define_env_var! {
 
     /// Always set to true when GitHub Actions is running the workflow.  You can use this variable
     /// to differentiate when tests are being run locally or by GitHub Actions.
-    GITHUB_ACTIONS, bool;
+    GITHUB_ACTIONS, flag;
 
     /// The name of the person or app that initiated the workflow. For example, `octocat`.
     GITHUB_ACTOR, String;
@@ -89,7 +89,7 @@ define_env_var! {
     GITHUB_REF_NAME, String;
 
     /// true if branch protections are configured for the ref that triggered the workflow run.
-    GITHUB_REF_PROTECTED, bool;
+    GITHUB_REF_PROTECTED, flag;
 
     /// The type of ref that triggered the workflow run. Valid values are `branch` or `tag`.
     GITHUB_REF_TYPE, String;
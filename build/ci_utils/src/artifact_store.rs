@@ -0,0 +1,382 @@
+//! Provider-agnostic storage of build artifacts.
+//!
+//! The providers (GitHub Actions artifacts, S3 buckets, local directories) implement the
+//! [`ArtifactStore`] trait with a few primitive operations. The [`upload`] and [`download`]
+//! functions build on them to provide resumable transfers with integrity verification:
+//! * each artifact is accompanied by a `<name>.sha256` checksum artifact, which is verified after
+//!   download;
+//! * an interrupted transfer is resumed from the already transferred part, if the provider supports
+//!   it.
+//!
+//! Stores published as they are (like the public S3 buckets) use [`upload_unverified`] instead, so
+//! no checksum artifacts are added to them.
+
+use crate::prelude::*;
+
+use crate::actions::artifacts::download::ArtifactDownloader;
+use crate::actions::artifacts::download::FileToDownload;
+use crate::actions::artifacts::run_session::SessionClient;
+use crate::program::command::retry::RetryPolicy;
+
+use sha2::Digest;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+
+
+
+/// Extension of the artifacts holding the checksums.
+pub const CHECKSUM_EXTENSION: &str = "sha256";
+
+/// Size of the chunks in which the data is copied by the [`LocalDirectory`] store.
+pub const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+
+
+// =====================
+// === ArtifactStore ===
+// =====================
+
+/// Storage that artifacts can be uploaded to and downloaded from.
+#[async_trait]
+pub trait ArtifactStore: Debug + Send + Sync {
+    /// Size of the stored artifact in bytes, or `None` if there is no such artifact.
+    ///
+    /// For the providers that support resuming, this is the size of the already uploaded part.
+    async fn stored_size(&self, name: &str) -> Result<Option<u64>>;
+
+    /// Store the contents of the file, starting at `offset`, after the first `offset` bytes of the
+    /// already stored artifact.
+    ///
+    /// The providers that do not support resuming should fail if `offset` is not zero.
+    async fn put_from(&self, name: &str, file: &Path, offset: u64) -> Result;
+
+    /// Append the contents of the artifact, starting at `offset`, to the `output` file.
+    async fn get_from(&self, name: &str, offset: u64, output: &Path) -> Result;
+
+    /// Whether [`ArtifactStore::put_from`] supports non-zero offsets.
+    fn supports_resumed_upload(&self) -> bool {
+        false
+    }
+}
+
+/// Name of the artifact holding the checksum of the given artifact.
+pub fn checksum_name(name: &str) -> String {
+    format!("{name}.{CHECKSUM_EXTENSION}")
+}
+
+/// Compute SHA-256 checksum of the file, as a lowercase hex string.
+pub fn sha256_of_file(path: impl AsRef<Path>) -> Result<String> {
+    let mut hasher = sha2::Sha256::new();
+    std::io::copy(&mut crate::fs::open(path)?, &mut hasher)?;
+    Ok(data_encoding::HEXLOWER.encode(&hasher.finalize()))
+}
+
+/// Read the stored checksum of the artifact, if there is one.
+async fn stored_checksum(store: &dyn ArtifactStore, name: &str) -> Result<Option<String>> {
+    let checksum_name = checksum_name(name);
+    if store.stored_size(&checksum_name).await?.is_none() {
+        return Ok(None);
+    }
+    let temp = tempfile::tempdir()?;
+    let path = temp.path().join(&checksum_name);
+    store.get_from(&checksum_name, 0, &path).await?;
+    Ok(Some(crate::fs::read_to_string(&path)?.trim().to_lowercase()))
+}
+
+/// Upload the file as an artifact, together with its checksum.
+///
+/// If a previous upload of the same file was interrupted and the store supports it, the upload
+/// is resumed. Failed transfers are retried.
+#[context("Failed to upload {} as artifact {name} to {store:?}.", path.as_ref().display())]
+pub async fn upload(store: &dyn ArtifactStore, name: &str, path: impl AsRef<Path>) -> Result {
+    let path = path.as_ref();
+    let size = crate::fs::metadata(path)?.len();
+    let checksum = {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || sha256_of_file(path)).await??
+    };
+
+    // The checksum is stored first, so any partially stored data can be attributed to it.
+    let resumable = store.supports_resumed_upload()
+        && stored_checksum(store, name).await?.as_ref() == Some(&checksum);
+    if !resumable {
+        let temp = tempfile::tempdir()?;
+        let checksum_path = temp.path().join(checksum_name(name));
+        crate::fs::write(&checksum_path, &checksum)?;
+        store.put_from(&checksum_name(name), &checksum_path, 0).await?;
+    }
+
+    let mut resume = resumable;
+    RetryPolicy::default()
+        .run(|| {
+            // Only the first attempt may need to discard data of a different file. The stored size
+            // is not queried otherwise, as it may require additional permissions.
+            let may_resume =
+                std::mem::replace(&mut resume, true) && store.supports_resumed_upload();
+            async move {
+                let stored =
+                    if may_resume { store.stored_size(name).await?.unwrap_or(0) } else { 0 };
+                let offset = if stored <= size { stored } else { 0 };
+                if offset > 0 {
+                    info!("Resuming upload of {name} at {offset}/{size} bytes.");
+                }
+                if offset < size || size == 0 {
+                    store.put_from(name, path, offset).await?;
+                }
+                Ok(())
+            }
+        })
+        .await
+}
+
+/// Upload the file as an artifact, without the checksum artifact. Failed transfers are retried.
+///
+/// Meant for the stores whose contents are published as they are, like the public S3 buckets,
+/// where the checksum artifacts would be visible to the users.
+#[context("Failed to upload {} as artifact {name} to {store:?}.", path.as_ref().display())]
+pub async fn upload_unverified(
+    store: &dyn ArtifactStore,
+    name: &str,
+    path: impl AsRef<Path>,
+) -> Result {
+    let path = path.as_ref();
+    RetryPolicy::default().run(|| store.put_from(name, path, 0)).await
+}
+
+/// Download the artifact to the given file, verifying its checksum.
+///
+/// The data is first downloaded to a `.part` file next to the target. If such file is left by an
+/// interrupted download, the download is resumed. Failed transfers are retried.
+#[context("Failed to download artifact {name} from {store:?}.")]
+pub async fn download(store: &dyn ArtifactStore, name: &str, output: impl AsRef<Path>) -> Result {
+    let output = output.as_ref();
+    let expected_checksum = stored_checksum(store, name).await?;
+    if expected_checksum.is_none() {
+        warn!("No checksum stored for artifact {name}, it will not be verified.");
+    }
+    crate::fs::create_parent_dir_if_missing(output)?;
+    let partial = output.with_appended_extension("part");
+    let resumed = partial_size(&partial) > 0;
+    if let Some(expected_checksum) = &expected_checksum {
+        // The interrupted download might have transferred all the data already. Resuming it would
+        // request an empty range, which the providers reject.
+        if resumed && checksum_of(&partial).await? == *expected_checksum {
+            info!("Download of {name} was already complete.");
+            return crate::fs::rename(&partial, output);
+        }
+    }
+    fetch_resuming(store, name, &partial).await?;
+
+    if let Some(expected_checksum) = expected_checksum {
+        let mut actual_checksum = checksum_of(&partial).await?;
+        if actual_checksum != expected_checksum && resumed {
+            warn!("Resumed download of {name} is corrupted, downloading it again.");
+            crate::fs::remove_file_if_exists(&partial)?;
+            fetch_resuming(store, name, &partial).await?;
+            actual_checksum = checksum_of(&partial).await?;
+        }
+        if actual_checksum != expected_checksum {
+            // The partial data is corrupted, so it must not be resumed from.
+            crate::fs::remove_file_if_exists(&partial)?;
+            bail!("Checksum mismatch: expected {expected_checksum}, got {actual_checksum}.");
+        }
+    }
+    crate::fs::rename(&partial, output)
+}
+
+/// Size of the partially downloaded file, or 0 if there is none.
+fn partial_size(partial: &Path) -> u64 {
+    partial.metadata().map_or(0, |metadata| metadata.len())
+}
+
+/// Compute the checksum of the file without blocking the runtime.
+async fn checksum_of(path: &Path) -> Result<String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || sha256_of_file(path)).await?
+}
+
+/// Download the artifact to the `partial` file, resuming from its current size. Failed transfers
+/// are retried.
+///
+/// If a resumed transfer fails without transferring anything, the partial data is likely stale
+/// (e.g. left by a different version of the artifact) or already complete. It is removed, so the
+/// next attempt starts over.
+async fn fetch_resuming(store: &dyn ArtifactStore, name: &str, partial: &Path) -> Result {
+    RetryPolicy::default()
+        .run(|| async {
+            let offset = partial_size(partial);
+            if offset > 0 {
+                info!("Resuming download of {name} at {offset} bytes.");
+            }
+            let result = store.get_from(name, offset, partial).await;
+            if result.is_err() && offset > 0 && partial_size(partial) == offset {
+                warn!("Failed to resume download of {name}, it will be started over.");
+                crate::fs::remove_file_if_exists(partial)?;
+            }
+            result
+        })
+        .await
+}
+
+/// Append the contents of `source` file, starting at `offset`, to the `target` file.
+///
+/// The data is copied in chunks, so an interrupted copy leaves a consistent prefix behind.
+pub fn append_from(source: &Path, offset: u64, target: &Path) -> Result {
+    let mut source = crate::fs::open(source)?;
+    source.seek(SeekFrom::Start(offset))?;
+    let mut target = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(target)
+        .with_context(|| format!("Failed to open {} for appending.", target.display()))?;
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        let read = source.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        target.write_all(&buffer[..read])?;
+        target.flush()?;
+    }
+    Ok(())
+}
+
+
+
+// ======================
+// === LocalDirectory ===
+// ======================
+
+/// Artifacts stored as files in a local directory. Supports resuming uploads.
+#[derive(Clone, Debug)]
+pub struct LocalDirectory {
+    /// Directory containing the artifacts.
+    pub root: PathBuf,
+}
+
+impl LocalDirectory {
+    /// Store the artifacts in the given directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for LocalDirectory {
+    async fn stored_size(&self, name: &str) -> Result<Option<u64>> {
+        let path = self.root.join(name);
+        Ok(path.exists().then(|| path.metadata()).transpose()?.map(|metadata| metadata.len()))
+    }
+
+    async fn put_from(&self, name: &str, file: &Path, offset: u64) -> Result {
+        let target = self.root.join(name);
+        crate::fs::create_parent_dir_if_missing(&target)?;
+        let stored = self.stored_size(name).await?.unwrap_or(0);
+        ensure!(stored >= offset, "Cannot resume at {offset}, only {stored} bytes are stored.");
+        // Discard anything stored past the offset.
+        std::fs::OpenOptions::new().create(true).write(true).open(&target)?.set_len(offset)?;
+        let file = file.to_path_buf();
+        tokio::task::spawn_blocking(move || append_from(&file, offset, &target)).await?
+    }
+
+    async fn get_from(&self, name: &str, offset: u64, output: &Path) -> Result {
+        let source = self.root.join(name);
+        let output = output.to_path_buf();
+        tokio::task::spawn_blocking(move || append_from(&source, offset, &output)).await?
+    }
+
+    fn supports_resumed_upload(&self) -> bool {
+        true
+    }
+}
+
+
+
+// =======================
+// === GitHubArtifacts ===
+// =======================
+
+/// Artifacts of the current GitHub Actions workflow run. Each artifact holds a single file.
+///
+/// Uploads cannot be resumed, but the artifact service uploads the data in chunks internally.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GitHubArtifacts;
+
+#[async_trait]
+impl ArtifactStore for GitHubArtifacts {
+    async fn stored_size(&self, name: &str) -> Result<Option<u64>> {
+        let artifacts = SessionClient::new_from_env()?.list_artifacts().await?;
+        let artifact = artifacts.into_iter().find(|artifact| artifact.name == name);
+        Ok(artifact.and_then(|artifact| artifact.size.try_into().ok()))
+    }
+
+    async fn put_from(&self, name: &str, file: &Path, offset: u64) -> Result {
+        ensure!(offset == 0, "GitHub artifacts do not support resumed uploads.");
+        crate::actions::artifacts::upload_single_file(file, name).await
+    }
+
+    async fn get_from(&self, name: &str, offset: u64, output: &Path) -> Result {
+        let downloader = ArtifactDownloader::new(SessionClient::new_from_env()?, name).await?;
+        let [item] = downloader.file_items().collect_vec()[..] else {
+            bail!("The artifact {name} does not contain only a single file.");
+        };
+        let temp = tempfile::tempdir()?;
+        let downloaded = temp.path().join(name);
+        let file = FileToDownload {
+            target:                 downloaded.clone(),
+            remote_source_location: item.content_location.clone(),
+        };
+        downloader.download_file_item(&file).await?;
+        // The service does not support ranges, so the already downloaded part is skipped locally.
+        let output = output.to_path_buf();
+        tokio::task::spawn_blocking(move || append_from(&downloaded, offset, &output)).await?
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn local_round_trip_with_resume() -> Result {
+        let temp = tempfile::tempdir()?;
+        let store = LocalDirectory::new(temp.path().join("store"));
+        let source = temp.path().join("source.bin");
+        let contents = (0..100_000u32).flat_map(u32::to_le_bytes).collect_vec();
+        crate::fs::write(&source, &contents)?;
+
+        // Simulate an interrupted upload: the checksum and a part of the data are stored.
+        let checksum = sha256_of_file(&source)?;
+        crate::fs::create_dir_if_missing(&store.root)?;
+        crate::fs::write(store.root.join(checksum_name("data")), &checksum)?;
+        crate::fs::write(store.root.join("data"), &contents[..1000])?;
+        upload(&store, "data", &source).await?;
+        assert_eq!(crate::fs::read(store.root.join("data"))?, contents);
+
+        // Simulate an interrupted download.
+        let output = temp.path().join("output.bin");
+        crate::fs::write(output.with_appended_extension("part"), &contents[..5000])?;
+        download(&store, "data", &output).await?;
+        assert_eq!(crate::fs::read(&output)?, contents);
+
+        // A complete download is not requested again.
+        let complete = temp.path().join("complete.bin");
+        crate::fs::write(complete.with_appended_extension("part"), &contents)?;
+        download(&store, "data", &complete).await?;
+        assert_eq!(crate::fs::read(&complete)?, contents);
+
+        // A stale partial download is discarded.
+        let stale = temp.path().join("stale.bin");
+        crate::fs::write(stale.with_appended_extension("part"), b"stale data of other version")?;
+        download(&store, "data", &stale).await?;
+        assert_eq!(crate::fs::read(&stale)?, contents);
+
+        // Corrupted data is detected.
+        crate::fs::write(store.root.join("data"), b"corrupted")?;
+        assert!(download(&store, "data", temp.path().join("corrupted.bin")).await.is_err());
+        Ok(())
+    }
+}
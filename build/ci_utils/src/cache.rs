@@ -1,5 +1,8 @@
 use crate::prelude::*;
 
+use crate::cache::lock::EntryLock;
+use crate::fs::atomic::Durability;
+
 use anyhow::Context;
 use enso_build_base::extensions::future::TryFutureExt;
 use sha2::Digest;
@@ -15,6 +18,7 @@ pub mod artifact;
 pub mod asset;
 pub mod download;
 pub mod goodie;
+pub mod lock;
 
 pub use goodie::Goodie;
 
@@ -25,6 +29,12 @@ pub use goodie::Goodie;
 /// This value can be bumped to invalidate all the hashes.
 pub const VERSION: u8 = 2;
 
+define_env_var! {
+    /// Maximum total size of the cache, in bytes. When the cache is opened and its size exceeds
+    /// this limit, the least recently used entries are removed.
+    ENSO_BUILD_CACHE_MAX_SIZE, u64;
+}
+
 /// Default location of the cache root.
 pub fn default_path() -> Result<PathBuf> {
     Ok(dirs::data_local_dir()
@@ -132,7 +142,70 @@ impl Cache {
         let root = path.into();
         crate::fs::tokio::create_dir_if_missing(&root).await?;
         debug!("Prepared cache in {}", root.display());
-        Ok(Self { root })
+        let cache = Self { root };
+        if let Some(max_size) = ENSO_BUILD_CACHE_MAX_SIZE.get_opt()? {
+            cache.evict(max_size).await?;
+        }
+        Ok(cache)
+    }
+
+    /// Remove the least recently used entries until the total size of the cache is within the
+    /// limit (in bytes).
+    ///
+    /// Entries that are currently in use (i.e. locked) are skipped.
+    pub async fn evict(&self, max_size: u64) -> Result {
+        let mut entries = self.entries()?;
+        let mut total_size: u64 = entries.iter().map(|entry| entry.size).sum();
+        if total_size <= max_size {
+            return Ok(());
+        }
+        info!("Cache size {total_size} exceeds the limit {max_size}, removing old entries.");
+        entries.sort_by_key(|entry| entry.last_used);
+        for entry in entries {
+            if total_size <= max_size {
+                break;
+            }
+            let Some(_lock) = EntryLock::try_acquire(self.lock_path(&entry.digest))? else {
+                debug!("Not evicting entry {}, as it is in use.", entry.digest);
+                continue;
+            };
+            debug!("Evicting entry {} ({} bytes).", entry.digest, entry.size);
+            let entry_dir = self.root.join(&entry.digest);
+            crate::fs::remove_file_if_exists(entry_dir.with_appended_extension("json"))?;
+            crate::fs::clean::remove_if_exists_async(&entry_dir).await?;
+            total_size = total_size.saturating_sub(entry.size);
+        }
+        Ok(())
+    }
+
+    /// Describe the entries currently stored in the cache.
+    pub fn entries(&self) -> Result<Vec<EntryInfo>> {
+        let mut ret = Vec::new();
+        for dir_entry in crate::fs::read_dir(&self.root)? {
+            let path = dir_entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let Some(digest) = path.file_name().map(|name| name.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+            let index = path.with_appended_extension("json");
+            let last_used = index.metadata().or_else(|_| path.metadata())?.modified()?;
+            let mut size = index.metadata().map_or(0, |metadata| metadata.len());
+            for file in walkdir::WalkDir::new(&path) {
+                let metadata = file?.metadata()?;
+                if metadata.is_file() {
+                    size += metadata.len();
+                }
+            }
+            ret.push(EntryInfo { digest, size, last_used });
+        }
+        Ok(ret)
+    }
+
+    fn lock_path(&self, digest: &str) -> PathBuf {
+        self.root.join(digest).with_appended_extension("lock")
     }
 
     pub fn get<S>(&self, storable: S) -> BoxFuture<'static, Result<S::Output>>
@@ -143,6 +216,9 @@ impl Cache {
             tracing::Span::current().record("digest", digest.as_str());
             let entry_dir = this.root.join(&digest);
             let entry_meta = entry_dir.with_appended_extension("json");
+            // Held until the entry is retrieved or generated, so concurrent processes don't
+            // generate the same entry or read a partially written one.
+            let _lock = EntryLock::acquire(this.lock_path(&digest)).await?;
 
             let retrieve = async {
                 let info = entry_meta.read_to_json::<EntryIndexRequired<S>>()?;
@@ -153,11 +229,16 @@ impl Cache {
             match retrieve.await {
                 Ok(out) => {
                     trace!("Found in cache, skipping generation.");
+                    // The modification time of the index is used to find the least recently used
+                    // entries for eviction.
+                    if let Err(e) = touch(&entry_meta) {
+                        debug!("Failed to update the last use time of the entry: {e:?}");
+                    }
                     Ok(out)
                 }
                 Err(e) => {
                     trace!("Value cannot be retrieved from cache because: {e}");
-                    crate::fs::reset_dir(&entry_dir)?;
+                    crate::fs::clean::reset_dir_async(&entry_dir).await?;
                     let key = storable.key();
                     tracing::Span::current().record("key", tracing::field::debug(&key));
                     let metadata = storable
@@ -166,7 +247,7 @@ impl Cache {
                         .context("Failed to generate the cache entry.")
                         .await?;
                     let info = EntryIndexExtended::<S>::new(metadata, key);
-                    entry_meta.write_as_json(&info)?;
+                    crate::fs::atomic::write_json(&entry_meta, &info, Durability::Atomic)?;
                     storable.adapt(entry_dir, info.inner.metadata).await
                 }
             }
@@ -180,6 +261,24 @@ impl Cache {
     }
 }
 
+/// Information about an entry stored in the cache.
+#[derive(Clone, Debug)]
+pub struct EntryInfo {
+    /// Digest identifying the entry, see [`digest`].
+    pub digest:    String,
+    /// Total size of the entry files, in bytes.
+    pub size:      u64,
+    /// When the entry was last generated or retrieved.
+    pub last_used: std::time::SystemTime,
+}
+
+/// Set the modification time of the file to now.
+fn touch(path: &Path) -> Result {
+    let file = std::fs::File::options().write(true).open(path)?;
+    file.set_modified(std::time::SystemTime::now())?;
+    Ok(())
+}
+
 
 #[cfg(test)]
 mod tests {
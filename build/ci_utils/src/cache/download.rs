@@ -23,6 +23,15 @@ pub struct Key {
     /// the headers set.
     #[serde(with = "http_serde::header_map")]
     pub additional_headers: HeaderMap,
+
+    /// Expected SHA-256 checksum of the file, as a hex string. If set, the download fails when
+    /// the checksum does not match.
+    ///
+    /// Part of the key, so a changed checksum (e.g. for a re-uploaded file) invalidates the entry.
+    /// It is not serialized when missing, so the cache entries of downloads without a checksum are
+    /// not invalidated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -34,11 +43,20 @@ pub struct DownloadFile {
 impl DownloadFile {
     pub fn new(url: impl IntoUrl) -> Result<Self> {
         Ok(Self {
-            key:    Key { url: url.into_url()?, additional_headers: default() },
+            key:    Key {
+                url:                url.into_url()?,
+                additional_headers: default(),
+                sha256:             None,
+            },
             client: ClientBuilder::new().user_agent("enso-build").build()?,
         })
     }
 
+    /// Require the downloaded file to have the given SHA-256 checksum (hex-encoded).
+    pub fn with_sha256(mut self, checksum: impl Into<String>) -> Self {
+        self.key.sha256 = Some(checksum.into());
+        self
+    }
 
     pub fn send_request(&self) -> BoxFuture<'static, Result<Response>> {
         let response = self
@@ -65,6 +83,7 @@ impl Storable for DownloadFile {
         // FIXME use `download_to_dir`
         let response = self.send_request();
         let filename = filename_from_url(&self.key.url);
+        let expected_checksum = self.key.sha256.clone();
         async move {
             let response = response.await?;
             let last_fallback_name = PathBuf::from("data");
@@ -74,6 +93,9 @@ impl Storable for DownloadFile {
                 .unwrap_or(last_fallback_name);
             let output = store.join(&filename);
             stream_response_to_file(response, &output).await?;
+            if let Some(expected_checksum) = expected_checksum {
+                verify_sha256_async(output, expected_checksum).await?;
+            }
             Ok(filename) // We don't store absolute paths to keep cache relocatable.
         }
         .boxed()
@@ -84,7 +106,16 @@ impl Storable for DownloadFile {
         store: PathBuf,
         metadata: Self::Metadata,
     ) -> BoxFuture<'static, Result<Self::Output>> {
-        ready(Ok(store.join(metadata))).boxed()
+        let path = store.join(metadata);
+        // The cached file might have been corrupted since it was downloaded.
+        let expected_checksum = self.key.sha256.clone();
+        async move {
+            if let Some(expected_checksum) = expected_checksum {
+                verify_sha256_async(path.clone(), expected_checksum).await?;
+            }
+            Ok(path)
+        }
+        .boxed()
     }
 
     fn key(&self) -> Self::Key {
@@ -96,3 +127,48 @@ pub async fn download(cache: Cache, url: impl IntoUrl) -> Result<PathBuf> {
     let download = DownloadFile::new(url)?;
     cache.get(download).await
 }
+
+/// Get the SHA-256 checksum published next to the file, as a `<url>.sha256` file.
+///
+/// The checksum file can either contain only the checksum, or follow the `sha256sum` format.
+pub async fn published_sha256(url: &Url) -> Result<String> {
+    let checksum_url = Url::parse(&format!("{url}.sha256"))?;
+    let response = crate::io::web::get(checksum_url.clone()).await?;
+    let text = response.text().await?;
+    let checksum = text.split_whitespace().next();
+    checksum
+        .map(ToString::to_string)
+        .with_context(|| format!("Empty checksum file {checksum_url}."))
+}
+
+/// Check that the file has the expected SHA-256 checksum (hex-encoded, case-insensitive).
+#[context("Failed to verify the checksum of {}.", path.as_ref().display())]
+pub fn verify_sha256(path: impl AsRef<Path>, expected: &str) -> Result {
+    let actual = crate::checksums::sha256_file(&path)?;
+    ensure!(
+        actual.eq_ignore_ascii_case(expected.trim()),
+        "Checksum mismatch: expected {expected}, got {actual}."
+    );
+    Ok(())
+}
+
+/// Like [`verify_sha256`], but hashes the file on a thread that may block.
+pub async fn verify_sha256_async(path: PathBuf, expected: String) -> Result {
+    tokio::task::spawn_blocking(move || verify_sha256(path, &expected)).await?
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_is_serialized_only_if_set() -> Result {
+        let download = DownloadFile::new("https://example.com/archive.tar.gz")?;
+        let key = serde_json::to_value(download.key())?;
+        assert!(key.get("sha256").is_none());
+        let key = serde_json::to_value(download.with_sha256("0123abcd").key())?;
+        assert_eq!(key["sha256"], "0123abcd");
+        Ok(())
+    }
+}
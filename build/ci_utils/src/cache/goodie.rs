@@ -96,3 +96,20 @@ pub fn download_try_future_url(
     }
     .boxed()
 }
+
+/// Like [`download_try_future_url`], but verifies the archive against the SHA-256 checksum
+/// published next to it (see [`cache::download::published_sha256`]).
+pub fn download_verified_future_url(
+    url: impl Future<Output = Result<Url>> + Send + 'static,
+    cache: &Cache,
+) -> BoxFuture<'static, Result<PathBuf>> {
+    let cache = cache.clone();
+    async move {
+        let url = url.await?;
+        let checksum = cache::download::published_sha256(&url).await?;
+        let archive_source = cache::download::DownloadFile::new(url)?.with_sha256(checksum);
+        let package = cache::archive::ExtractedArchive { archive_source, path_to_extract: None };
+        cache.get(package).await
+    }
+    .boxed()
+}
@@ -101,7 +101,8 @@ pub struct GraalVM {
 
 impl Goodie for GraalVM {
     fn get(&self, cache: &Cache) -> BoxFuture<'static, Result<PathBuf>> {
-        goodie::download_try_future_url(self.url(), cache)
+        // Both the GitHub releases of GraalVM CE and the Oracle downloads publish the checksums.
+        goodie::download_verified_future_url(self.url(), cache)
     }
 
 
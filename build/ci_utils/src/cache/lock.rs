@@ -0,0 +1,111 @@
+//! Lock files guarding the cache entries against concurrent access.
+//!
+//! Multiple build script processes (e.g. parallel jobs on the same runner) may share the cache.
+//! Each entry is guarded by a lock file containing the PID of the owner. A lock left behind by a
+//! process that is no longer running is considered stale and is taken over.
+
+use crate::prelude::*;
+
+use std::io::ErrorKind;
+use std::time::Duration;
+use sysinfo::Pid;
+
+
+
+/// How often the lock file is checked while waiting for another process to release it.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Exclusive lock of a cache entry. Released (by removing the lock file) when dropped.
+#[derive(Debug)]
+pub struct EntryLock {
+    path: PathBuf,
+}
+
+impl EntryLock {
+    /// Acquire the lock, waiting for other processes to release it.
+    #[context("Failed to lock the cache entry using {}.", path.as_ref().display())]
+    pub async fn acquire(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut reported_owner = None;
+        let mut waited_for_pid = false;
+        loop {
+            if let Some(lock) = Self::try_acquire(&path)? {
+                return Ok(lock);
+            }
+            match read_owner(&path) {
+                Some(owner) if is_running(owner) => {
+                    if reported_owner != Some(owner) {
+                        info!("Waiting for process {owner} to release {}.", path.display());
+                        reported_owner = Some(owner);
+                    }
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+                // The owner might have just created the file and not yet written its PID.
+                None if !waited_for_pid => {
+                    waited_for_pid = true;
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+                _ => {
+                    warn!("Removing stale cache lock {}.", path.display());
+                    crate::fs::remove_file_if_exists(&path)?;
+                    waited_for_pid = false;
+                }
+            }
+        }
+    }
+
+    /// Acquire the lock if it is not held by anyone.
+    pub fn try_acquire(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        let file = std::fs::OpenOptions::new().write(true).create_new(true).open(path);
+        match file {
+            Ok(mut file) => {
+                use std::io::Write;
+                write!(file, "{}", std::process::id())?;
+                Ok(Some(Self { path: path.to_path_buf() }))
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to create {}.", path.display())),
+        }
+    }
+}
+
+impl Drop for EntryLock {
+    fn drop(&mut self) {
+        if let Err(e) = crate::fs::remove_file_if_exists(&self.path) {
+            warn!("Failed to release the cache lock: {e:?}");
+        }
+    }
+}
+
+/// Read the PID of the process holding the lock.
+fn read_owner(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Check if the process with the given PID is still running.
+fn is_running(pid: u32) -> bool {
+    pid == std::process::id() || sysinfo::System::new().refresh_process(Pid::from_u32(pid))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn locking() -> Result {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("entry.lock");
+        let lock = EntryLock::acquire(&path).await?;
+        assert!(EntryLock::try_acquire(&path)?.is_none());
+        drop(lock);
+        assert!(!path.exists());
+
+        // Lock left by a process that does not exist anymore is taken over.
+        crate::fs::write(&path, u32::MAX.to_string())?;
+        let _lock = EntryLock::acquire(&path).await?;
+        assert_eq!(read_owner(&path), Some(std::process::id()));
+        Ok(())
+    }
+}
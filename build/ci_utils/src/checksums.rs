@@ -0,0 +1,267 @@
+//! Checksum manifests of the release artifacts, so the downloads can be verified.
+//!
+//! The [`Manifest`] uses the format of the `sha256sum` tool, so it can be checked with
+//! `sha256sum --check SHA256SUMS` as well. If a signing key is configured through
+//! [`ENSO_BUILD_CHECKSUMS_SIGNING_KEY`], the manifest gets a detached GPG signature
+//! (`SHA256SUMS.asc`), which [`verify_directory`] checks before trusting the checksums.
+//!
+//! The release assets are uploaded by several jobs, so each of them uploads a manifest fragment
+//! (see [`FRAGMENT_EXTENSION`]) describing its assets, computed from the local files. The fragments
+//! are [merged](Manifest::merge) into the release's manifest when it is published.
+
+use crate::prelude::*;
+
+use crate::fs::atomic::Durability;
+use crate::programs::gpg::Gpg;
+
+use sha2::Digest;
+
+
+
+define_env_var! {
+    /// GPG key used to sign the checksum manifest. If not set, the manifest is not signed.
+    ENSO_BUILD_CHECKSUMS_SIGNING_KEY, String;
+}
+
+/// Name of the checksum manifest file.
+pub const MANIFEST_FILENAME: &str = "SHA256SUMS";
+
+/// Name of the detached signature of the checksum manifest.
+pub const SIGNATURE_FILENAME: &str = "SHA256SUMS.asc";
+
+/// Extension of the manifest fragments, describing the assets uploaded by a single job.
+pub const FRAGMENT_EXTENSION: &str = "sha256sums";
+
+/// Name of the manifest fragment describing the asset of the given name.
+pub fn fragment_name(asset_name: impl AsRef<str>) -> String {
+    format!("{}.{FRAGMENT_EXTENSION}", asset_name.as_ref())
+}
+
+/// Compute the SHA-256 checksum of the file, as a lowercase hex string.
+pub fn sha256_file(path: impl AsRef<Path>) -> Result<String> {
+    let mut hasher = sha2::Sha256::new();
+    let mut file = crate::fs::open(&path)?;
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(data_encoding::HEXLOWER.encode(&hasher.finalize()))
+}
+
+
+
+// ================
+// === Manifest ===
+// ================
+
+/// SHA-256 checksums of files, identified by their names.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    /// Lowercase hex checksums, keyed by the file names.
+    pub entries: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// Create an empty manifest.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Add the checksum of a file with the given name.
+    ///
+    /// Fails if the name is not a plain file name, as the manifest describes a single directory.
+    pub fn add(&mut self, name: impl Into<String>, checksum: impl AsRef<str>) -> Result {
+        let name = name.into();
+        let checksum = checksum.as_ref().to_lowercase();
+        ensure!(
+            !name.is_empty() && !name.contains(['/', '\\']),
+            "Invalid file name in the checksum manifest: `{name}`."
+        );
+        ensure!(
+            checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit()),
+            "Invalid SHA-256 checksum of `{name}`: `{checksum}`."
+        );
+        self.entries.insert(name, checksum);
+        Ok(())
+    }
+
+    /// Add the checksum of the file, under its file name.
+    pub fn add_file(&mut self, path: impl AsRef<Path>) -> Result {
+        let path = path.as_ref();
+        let name = path.try_file_name()?.to_string_lossy().into_owned();
+        self.add(name, sha256_file(path)?)
+    }
+
+    /// Describe the given files.
+    pub fn from_files(files: impl IntoIterator<Item = impl AsRef<Path>>) -> Result<Self> {
+        let mut manifest = Self::new();
+        for file in files {
+            manifest.add_file(file)?;
+        }
+        Ok(manifest)
+    }
+
+    /// Add all the entries of the other manifest.
+    ///
+    /// Fails if both manifests describe the same file with different checksums.
+    pub fn merge(&mut self, other: Manifest) -> Result {
+        for (name, checksum) in other.entries {
+            if let Some(existing) = self.entries.get(&name) {
+                ensure!(
+                    existing == &checksum,
+                    "Conflicting checksums of `{name}`: {existing} and {checksum}."
+                );
+            }
+            self.entries.insert(name, checksum);
+        }
+        Ok(())
+    }
+
+    /// Read the manifest from the file.
+    #[context("Failed to read the checksum manifest {}.", path.as_ref().display())]
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        crate::fs::read_to_string(&path)?.parse()
+    }
+
+    /// Write the manifest into the directory, returning the path to the written file.
+    pub fn write(&self, directory: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = directory.as_ref().join(MANIFEST_FILENAME);
+        crate::fs::atomic::write(&path, self.to_string(), Durability::Durable)?;
+        Ok(path)
+    }
+
+    /// Check the files in the directory against the manifest. Returns the number of verified files.
+    ///
+    /// All the files are checked and all the mismatches are reported together. Unless
+    /// `ignore_missing` is set, the files missing from the directory are reported too.
+    pub fn verify(&self, directory: impl AsRef<Path>, ignore_missing: bool) -> Result<usize> {
+        let directory = directory.as_ref();
+        let mut verified = 0;
+        let mut failures = Vec::new();
+        for (name, expected) in &self.entries {
+            let path = directory.join(name);
+            if !path.exists() {
+                if !ignore_missing {
+                    failures.push(format!("{name}: missing"));
+                }
+                continue;
+            }
+            match sha256_file(&path) {
+                Ok(actual) if &actual == expected => verified += 1,
+                Ok(actual) => failures.push(format!("{name}: expected {expected}, got {actual}")),
+                Err(e) => failures.push(format!("{name}: {e}")),
+            }
+        }
+        ensure!(failures.is_empty(), "Checksum verification failed:\n{}", failures.join("\n"));
+        ensure!(verified > 0, "None of the files from the checksum manifest were found.");
+        Ok(verified)
+    }
+}
+
+impl Display for Manifest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (name, checksum) in &self.entries {
+            writeln!(f, "{checksum}  {name}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut manifest = Self::new();
+        for line in s.lines().map(str::trim_end).filter(|line| !line.is_empty()) {
+            let (checksum, name) = line
+                .split_once(' ')
+                .with_context(|| format!("Invalid manifest line: `{line}`."))?;
+            // The name is prefixed with ` ` in text mode and with `*` in binary mode.
+            let name = name.strip_prefix([' ', '*']).unwrap_or(name);
+            manifest.add(name, checksum)?;
+        }
+        Ok(manifest)
+    }
+}
+
+
+
+// ===============
+// === Signing ===
+// ===============
+
+/// Sign the manifest file if a signing key is configured. Returns the path to the signature.
+pub async fn sign(manifest: impl AsRef<Path>) -> Result<Option<PathBuf>> {
+    let manifest = manifest.as_ref();
+    let Some(key) = ENSO_BUILD_CHECKSUMS_SIGNING_KEY.get_opt()? else {
+        warn!(
+            "{} is not set, the checksum manifest is not signed.",
+            ENSO_BUILD_CHECKSUMS_SIGNING_KEY.name()
+        );
+        return Ok(None);
+    };
+    let signature = manifest.with_file_name(SIGNATURE_FILENAME);
+    Gpg.detach_sign(&key, manifest, &signature).await?;
+    Ok(Some(signature))
+}
+
+/// Verify the downloaded files in the directory against the manifest stored alongside them.
+///
+/// If the manifest's signature is present, it is verified first, using the keys in the GPG
+/// keyring. A missing signature is an error only if `require_signature` is set, as the releases
+/// built without a signing key have none.
+#[context("Failed to verify the checksums of the files in {}.", directory.as_ref().display())]
+pub async fn verify_directory(
+    directory: impl AsRef<Path>,
+    ignore_missing: bool,
+    require_signature: bool,
+) -> Result {
+    let directory = directory.as_ref();
+    let manifest_path = directory.join(MANIFEST_FILENAME);
+    let signature_path = directory.join(SIGNATURE_FILENAME);
+    if signature_path.exists() {
+        Gpg.verify(&signature_path, &manifest_path).await?;
+        info!("The signature of the checksum manifest is valid.");
+    } else if require_signature {
+        bail!("No {SIGNATURE_FILENAME} found, the checksum manifest's origin cannot be verified.");
+    } else {
+        warn!("No {SIGNATURE_FILENAME} found, the checksum manifest's origin is not verified.");
+    }
+    let verified = Manifest::read(&manifest_path)?.verify(directory, ignore_missing)?;
+    info!("Verified the checksums of {verified} files.");
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_verification() -> Result {
+        let temp = tempfile::tempdir()?;
+        let hello = temp.path().join("hello.txt");
+        crate::fs::write(&hello, "hello")?;
+        let manifest = Manifest::from_files([&hello])?;
+        let expected =
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  hello.txt\n";
+        assert_eq!(manifest.to_string(), expected);
+
+        let path = manifest.write(temp.path())?;
+        assert_eq!(Manifest::read(&path)?, manifest);
+        assert_eq!(manifest.verify(temp.path(), false)?, 1);
+
+        crate::fs::write(&hello, "tampered")?;
+        let error = manifest.verify(temp.path(), false).unwrap_err();
+        assert!(error.to_string().contains("hello.txt: expected 2cf24dba"));
+
+        let binary_mode = format!("{} *other.zip", "0".repeat(64));
+        let other = Manifest::from_str(&binary_mode)?;
+        assert!(other.verify(temp.path(), false).is_err());
+        assert!(other.verify(temp.path(), true).is_err(), "Nothing was verified.");
+
+        let mut merged = manifest.clone();
+        merged.merge(other.clone())?;
+        assert_eq!(merged.entries.len(), 2);
+        let conflicting = format!("{}  hello.txt", "1".repeat(64));
+        assert!(merged.merge(conflicting.parse()?).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+//! Concurrent execution of build steps ordered by their dependencies.
+//!
+//! Each [`Step`] declares the named resources it consumes (inputs) and produces (outputs). A step
+//! depends on the steps producing its inputs; inputs not produced by any step are assumed to be
+//! available up front. The [`Graph`] runs the steps as soon as their dependencies complete, with
+//! at most [`Graph::max_parallel`] steps running at once.
+//!
+//! Every step is run as a [timed step](crate::timing::step), so its logs are grouped under its
+//! name and its duration is included in the timing report.
+//!
+//! If the graph has a [state database](Graph::with_state), the steps with a
+//! [fingerprint](Step::with_fingerprint) are skipped when their inputs have not changed since
+//! their last successful run.
+
+use crate::prelude::*;
+
+use crate::fingerprint::Decision;
+use crate::fingerprint::Fingerprint;
+use crate::fingerprint::StateDatabase;
+use futures::stream::FuturesUnordered;
+use std::collections::VecDeque;
+
+
+
+define_env_var! {
+    /// Maximum number of build steps run at once. Defaults to the number of available CPUs.
+    ENSO_BUILD_MAX_PARALLEL_STEPS, usize;
+}
+
+/// The default maximum number of build steps run at once.
+pub fn default_max_parallel() -> Result<usize> {
+    match ENSO_BUILD_MAX_PARALLEL_STEPS.get_opt()? {
+        Some(max_parallel) => Ok(max_parallel.max(1)),
+        None => Ok(std::thread::available_parallelism().map_or(1, usize::from)),
+    }
+}
+
+
+
+// ============
+// === Step ===
+// ============
+
+/// A build step: a named action together with the resources it consumes and produces.
+#[derive_where(Debug)]
+pub struct Step<'a> {
+    /// Name of the step, used in the logs.
+    pub name:    String,
+    /// Resources required by this step.
+    pub inputs:  Vec<String>,
+    /// Resources produced by this step.
+    pub outputs: Vec<String>,
+    #[derive_where(skip)]
+    action:      BoxFuture<'a, Result>,
+    #[derive_where(skip)]
+    fingerprint: Option<BoxFuture<'a, Result<Fingerprint>>>,
+}
+
+impl<'a> Step<'a> {
+    /// Create a step running the given action. The action is not started until it is scheduled.
+    pub fn new(name: impl Into<String>, action: impl Future<Output = Result> + Send + 'a) -> Self {
+        Self {
+            name:        name.into(),
+            inputs:      default(),
+            outputs:     default(),
+            action:      action.boxed(),
+            fingerprint: None,
+        }
+    }
+
+    /// Declare a resource required by this step.
+    pub fn with_input(mut self, input: impl Into<String>) -> Self {
+        self.inputs.push(input.into());
+        self
+    }
+
+    /// Declare a resource produced by this step.
+    pub fn with_output(mut self, output: impl Into<String>) -> Self {
+        self.outputs.push(output.into());
+        self
+    }
+
+    /// Describe the inputs of this step, so it can be skipped if they have not changed.
+    ///
+    /// The fingerprint is computed only when the step is scheduled, i.e. after the steps it
+    /// depends on have completed.
+    pub fn with_fingerprint(
+        mut self,
+        fingerprint: impl Future<Output = Result<Fingerprint>> + Send + 'a,
+    ) -> Self {
+        self.fingerprint = Some(fingerprint.boxed());
+        self
+    }
+
+    /// The action of this step, which skips the actual work if the fingerprint is unchanged.
+    fn into_action(self, state: Option<StateDatabase>) -> BoxFuture<'a, Result> {
+        let Step { name, action, fingerprint, .. } = self;
+        let (Some(state), Some(fingerprint)) = (state, fingerprint) else { return action };
+        async move {
+            let fingerprint = match fingerprint.await {
+                Ok(fingerprint) => fingerprint,
+                Err(e) => {
+                    warn!("Failed to compute the fingerprint of step {name}: {e:?}");
+                    return action.await;
+                }
+            };
+            match state.decide(&name, &fingerprint) {
+                Decision::Skip => Ok(()),
+                Decision::Run { .. } => {
+                    action.await?;
+                    state.record_success(&name, fingerprint).await
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+
+
+// =============
+// === Graph ===
+// =============
+
+/// Build steps to be run concurrently, respecting their dependencies.
+#[derive(Debug)]
+pub struct Graph<'a> {
+    steps:            Vec<Step<'a>>,
+    /// Maximum number of steps running at once.
+    pub max_parallel: usize,
+    /// Database of the fingerprints of the successful steps.
+    pub state:        Option<StateDatabase>,
+}
+
+impl<'a> Graph<'a> {
+    /// Create an empty graph with the [default parallelism](default_max_parallel).
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_max_parallel(default_max_parallel()?))
+    }
+
+    /// Create an empty graph running at most `max_parallel` steps at once.
+    pub fn with_max_parallel(max_parallel: usize) -> Self {
+        Self { steps: default(), max_parallel: max_parallel.max(1), state: None }
+    }
+
+    /// Skip the fingerprinted steps whose inputs did not change since their recorded success.
+    pub fn with_state(mut self, state: StateDatabase) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Add a step to the graph.
+    pub fn add(&mut self, step: Step<'a>) -> &mut Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Add a step to the graph.
+    pub fn with(mut self, step: Step<'a>) -> Self {
+        self.add(step);
+        self
+    }
+
+    /// For each step, the indices of the steps that depend on it.
+    ///
+    /// Fails if a resource is produced by more than one step or if the dependencies are cyclic.
+    pub fn dependents(&self) -> Result<Vec<Vec<usize>>> {
+        let mut producers = HashMap::new();
+        for (index, step) in self.steps.iter().enumerate() {
+            for output in &step.outputs {
+                if let Some(other) = producers.insert(output.as_str(), index) {
+                    let other = &self.steps[other].name;
+                    bail!("Resource `{output}` is produced by both `{other}` and `{}`.", step.name);
+                }
+            }
+        }
+        let mut dependents = vec![Vec::new(); self.steps.len()];
+        for (index, step) in self.steps.iter().enumerate() {
+            for input in &step.inputs {
+                match producers.get(input.as_str()) {
+                    Some(&producer) if producer == index =>
+                        bail!("Step `{}` consumes its own output `{input}`.", step.name),
+                    Some(&producer) => dependents[producer].push(index),
+                    None => trace!("Resource `{input}` of `{}` is external.", step.name),
+                }
+            }
+        }
+        for dependents in &mut dependents {
+            dependents.sort_unstable();
+            dependents.dedup();
+        }
+
+        // Kahn's algorithm: if the steps cannot be ordered, there is a cycle.
+        let mut pending = self.dependency_counts(&dependents);
+        let mut ready = (0..self.steps.len()).filter(|&index| pending[index] == 0).collect_vec();
+        let mut ordered = 0;
+        while let Some(index) = ready.pop() {
+            ordered += 1;
+            for &dependent in &dependents[index] {
+                pending[dependent] -= 1;
+                if pending[dependent] == 0 {
+                    ready.push(dependent);
+                }
+            }
+        }
+        if ordered < self.steps.len() {
+            let cyclic = (0..self.steps.len()).filter(|&index| pending[index] > 0);
+            let cyclic = cyclic.map(|index| &self.steps[index].name).join(", ");
+            bail!("Build steps have cyclic dependencies: {cyclic}.");
+        }
+        Ok(dependents)
+    }
+
+    fn dependency_counts(&self, dependents: &[Vec<usize>]) -> Vec<usize> {
+        let mut counts = vec![0; self.steps.len()];
+        for &dependent in dependents.iter().flatten() {
+            counts[dependent] += 1;
+        }
+        counts
+    }
+
+    /// Run all the steps.
+    ///
+    /// After a step fails, no new steps are started. The already running steps are allowed to
+    /// complete, and then the first failure is returned.
+    pub async fn run(self) -> Result {
+        let dependents = self.dependents()?;
+        let mut pending = self.dependency_counts(&dependents);
+        let total = self.steps.len();
+        let max_parallel = self.max_parallel;
+        let mut names = Vec::with_capacity(total);
+        let mut actions = Vec::with_capacity(total);
+        for step in self.steps {
+            names.push(step.name.clone());
+            actions.push(Some(step.into_action(self.state.clone())));
+        }
+
+        let mut ready = (0..total).filter(|&index| pending[index] == 0).collect::<VecDeque<_>>();
+        let mut running = FuturesUnordered::new();
+        let mut completed = 0;
+        let mut failures = Vec::new();
+        loop {
+            while failures.is_empty() && running.len() < max_parallel {
+                let Some(index) = ready.pop_front() else { break };
+                let Some(action) = actions[index].take() else { continue };
+                let name = names[index].clone();
+                debug!("Starting step {name}.");
+                running.push(crate::timing::step(name, action).map(move |result| (index, result)));
+            }
+            let Some((index, result)) = running.next().await else { break };
+            match result {
+                Ok(()) => {
+                    completed += 1;
+                    for &dependent in &dependents[index] {
+                        pending[dependent] -= 1;
+                        if pending[dependent] == 0 {
+                            ready.push_back(dependent);
+                        }
+                    }
+                }
+                Err(error) => {
+                    error!("Step {} failed: {error:?}", names[index]);
+                    failures.push((index, error));
+                }
+            }
+        }
+
+        let skipped = total - completed - failures.len();
+        let mut failures = failures.into_iter();
+        match failures.next() {
+            None => Ok(()),
+            Some((index, error)) => {
+                let also_failed = failures.map(|(index, _)| &names[index]).join(", ");
+                let mut message = format!("Step {} failed", names[index]);
+                if !also_failed.is_empty() {
+                    message.push_str(&format!(" (also failed: {also_failed})"));
+                }
+                message.push_str(&format!(", {skipped} steps were not run."));
+                Err(error.context(message))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::sync::Barrier;
+
+    #[tokio::test]
+    async fn runs_independent_steps_concurrently() -> Result {
+        let log = Mutex::new(Vec::new());
+        let barrier = Barrier::new(2);
+        let step = |name: &'static str| {
+            let (log, barrier) = (&log, &barrier);
+            async move {
+                // Both independent steps must be running at once to pass the barrier.
+                if name != "package" {
+                    barrier.wait().await;
+                }
+                log.lock().unwrap().push(name);
+                Ok(())
+            }
+        };
+        Graph::with_max_parallel(2)
+            .with(Step::new("package", step("package")).with_input("gui").with_input("backend"))
+            .with(Step::new("gui", step("gui")).with_output("gui"))
+            .with(Step::new("backend", step("backend")).with_output("backend"))
+            .run()
+            .await?;
+        assert_eq!(log.lock().unwrap().last(), Some(&"package"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failures_and_cycles() {
+        let ok = || async { Result::Ok(()) };
+        let cyclic = Graph::with_max_parallel(1)
+            .with(Step::new("a", ok()).with_input("b").with_output("a"))
+            .with(Step::new("b", ok()).with_input("a").with_output("b"));
+        assert!(cyclic.run().await.is_err());
+
+        let dependent_ran = Mutex::new(false);
+        let failing = Graph::with_max_parallel(1)
+            .with(Step::new("a", async { bail!("Failure.") }).with_output("a"))
+            .with(
+                Step::new("b", async {
+                    *dependent_ran.lock().unwrap() = true;
+                    Ok(())
+                })
+                .with_input("a"),
+            );
+        let error = failing.run().await.unwrap_err();
+        assert_eq!(error.to_string(), "Step a failed, 1 steps were not run.");
+        assert!(!*dependent_ran.lock().unwrap());
+    }
+}
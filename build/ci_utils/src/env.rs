@@ -5,6 +5,7 @@ use crate::env::accessor::RawVariable;
 use crate::env::accessor::TypedVariable;
 
 use anyhow::Context;
+use std::sync::LazyLock;
 use unicase::UniCase;
 
 
@@ -15,6 +16,12 @@ use unicase::UniCase;
 pub mod accessor;
 pub mod consts;
 pub mod known;
+pub mod path_edit;
+pub mod profile;
+pub mod snapshot;
+
+#[doc(hidden)]
+pub use inventory;
 
 
 
@@ -64,7 +71,17 @@ pub fn remove_var<K: AsRef<OsStr>>(key: K) {
 }
 
 /// Define typed accessors for environment variables. Supported types include `String`, `PathBuf`,
-/// and other types that implement `FromStr`.
+/// `Duration`, and other types that implement `FromStr`.
+///
+/// A `bool` variable accepts only `true` and `false`. Declare it as a `flag` instead to accept
+/// all the common forms, like `1`, `yes` or `off` (see [`accessor::BoolVariable`]).
+///
+/// Enums deriving [`strum::VariantNames`] can be prefixed with `enum` keyword, so the parsing error
+/// lists the accepted values. Passwords, tokens and other sensitive values should be declared as
+/// `secret String`, so they get masked in the logs.
+///
+/// Every declared variable is also registered as a [`Definition`], so the program can list them
+/// with [`definitions`].
 ///
 /// Example:
 /// ```
@@ -77,14 +94,44 @@ pub fn remove_var<K: AsRef<OsStr>>(key: K) {
 /// }
 /// let path = ENV_VAR_NAME.get().unwrap_or_else(|_error| PathBuf::from("default"));
 /// ```
+///
+/// ```
+/// # use std::time::Duration;
+/// # use ide_ci::define_env_var;
+/// # use ide_ci::env::accessor::TypedVariable;
+/// #[derive(strum::Display, strum::EnumString, strum::VariantNames)]
+/// enum Mode {
+///     Fast,
+///     Thorough,
+/// }
+///
+/// define_env_var! {
+///     /// Whether the feature is enabled. Accepts `1`, `yes`, `on`, etc.
+///     FEATURE_ENABLED, flag;
+///     /// How long to wait. Accepts values like `30s` or `5m`.
+///     TIMEOUT, Duration;
+///     /// Mode to run in.
+///     MODE, enum Mode;
+///     /// Token used to authenticate. Its value will be masked in the logs.
+///     ACCESS_TOKEN, secret String;
+/// }
+/// let timeout = TIMEOUT.get_opt()?.unwrap_or(Duration::from_secs(60));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 #[macro_export]
 macro_rules! define_env_var {
+    (@register $name: ident, $secret: literal) => {
+        $crate::env::inventory::submit! {
+            $crate::env::Definition { name: stringify!($name), secret: $secret }
+        }
+    };
     () => {};
     ($(#[$attr:meta])* $name: ident, Vec<PathBuf>; $($tail:tt)*) => {
         #[allow(non_upper_case_globals)]
         $(#[$attr])*
         pub const $name: $crate::env::accessor::PathLike =
             $crate::env::accessor::PathLike(stringify!($name));
+        $crate::define_env_var!(@register $name, false);
         $crate::define_env_var!($($tail)*);
     };
     ($(#[$attr:meta])* $name: ident, PathBuf; $($tail:tt)*) => {
@@ -92,6 +139,39 @@ macro_rules! define_env_var {
         $(#[$attr])*
         pub const $name: $crate::env::accessor::PathBufVariable =
             $crate::env::accessor::PathBufVariable(stringify!($name));
+        $crate::define_env_var!(@register $name, false);
+        $crate::define_env_var!($($tail)*);
+    };
+    ($(#[$attr:meta])* $name: ident, flag; $($tail:tt)*) => {
+        #[allow(non_upper_case_globals)]
+        $(#[$attr])*
+        pub const $name: $crate::env::accessor::BoolVariable =
+            $crate::env::accessor::BoolVariable::new(stringify!($name));
+        $crate::define_env_var!(@register $name, false);
+        $crate::define_env_var!($($tail)*);
+    };
+    ($(#[$attr:meta])* $name: ident, Duration; $($tail:tt)*) => {
+        #[allow(non_upper_case_globals)]
+        $(#[$attr])*
+        pub const $name: $crate::env::accessor::DurationVariable =
+            $crate::env::accessor::DurationVariable::new(stringify!($name));
+        $crate::define_env_var!(@register $name, false);
+        $crate::define_env_var!($($tail)*);
+    };
+    ($(#[$attr:meta])* $name: ident, secret String; $($tail:tt)*) => {
+        #[allow(non_upper_case_globals)]
+        $(#[$attr])*
+        pub const $name: $crate::env::accessor::SecretVariable =
+            $crate::env::accessor::SecretVariable::new(stringify!($name));
+        $crate::define_env_var!(@register $name, true);
+        $crate::define_env_var!($($tail)*);
+    };
+    ($(#[$attr:meta])* $name: ident, enum $ty_name: ty; $($tail:tt)*) => {
+        #[allow(non_upper_case_globals)]
+        $(#[$attr])*
+        pub const $name: $crate::env::accessor::EnumVariable<$ty_name> =
+            $crate::env::accessor::EnumVariable::new(stringify!($name));
+        $crate::define_env_var!(@register $name, false);
         $crate::define_env_var!($($tail)*);
     };
     ($(#[$attr:meta])* $name: ident, String; $($tail:tt)*) => {
@@ -99,6 +179,7 @@ macro_rules! define_env_var {
         $(#[$attr])*
         pub const $name: $crate::env::accessor::SimpleVariable<String, str> =
             $crate::env::accessor::SimpleVariable::new(stringify!($name));
+        $crate::define_env_var!(@register $name, false);
         $crate::define_env_var!($($tail)*);
     };
     ($(#[$attr:meta])* $name: ident, $ty_name: ty; $($tail:tt)*) => {
@@ -106,10 +187,40 @@ macro_rules! define_env_var {
         $(#[$attr])*
         pub const $name: $crate::env::accessor::SimpleVariable<$ty_name> =
             $crate::env::accessor::SimpleVariable::new(stringify!($name));
+        $crate::define_env_var!(@register $name, false);
         $crate::define_env_var!($($tail)*);
     };
 }
 
+/// An environment variable declared with [`define_env_var!`].
+///
+/// Every declaration registers itself, so all the variables of the crates linked into the program
+/// can be listed with [`definitions`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Definition {
+    /// Name of the variable.
+    pub name:   &'static str,
+    /// Whether the variable was declared as `secret String`.
+    pub secret: bool,
+}
+
+inventory::collect!(Definition);
+
+/// All the variables declared with [`define_env_var!`] in the crates linked into the program.
+pub fn definitions() -> impl Iterator<Item = &'static Definition> {
+    inventory::iter::<Definition>.into_iter()
+}
+
+/// Register the values of the set variables declared as `secret String` for masking (see
+/// [`crate::log::register_secret`]), even if their accessors were not used yet.
+pub fn register_declared_secrets() {
+    for definition in definitions().filter(|definition| definition.secret) {
+        if let Some(value) = std::env::var_os(definition.name) {
+            crate::log::register_secret(value.to_string_lossy());
+        }
+    }
+}
+
 /// Get the value of the environment variable. If it is not set, return an error.
 ///
 /// Meant as a replacement for [`std::env::var`] which does not provide a nice error message.
@@ -129,6 +240,87 @@ pub fn prepend_to_path(path: impl AsRef<Path>) -> Result {
     known::PATH.prepend(path)
 }
 
+
+
+// =============
+// === Guard ===
+// =============
+
+/// Restores the previous value of an environment variable when dropped.
+///
+/// Obtained from [`scoped_set`] and similar functions. If the variable was not set before, it is
+/// removed.
+#[derive(Debug)]
+#[must_use = "The variable is restored as soon as the guard is dropped."]
+pub struct Guard {
+    name:     OsString,
+    previous: Option<OsString>,
+}
+
+impl Guard {
+    /// Remember the current value of the variable, so it can be restored later.
+    pub fn new(name: impl AsRef<OsStr>) -> Self {
+        let name = name.as_ref().to_owned();
+        let previous = std::env::var_os(&name);
+        Self { name, previous }
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        match self.previous.take() {
+            Some(previous) => set_var(&self.name, previous),
+            None => remove_var(&self.name),
+        }
+    }
+}
+
+/// Like [`set_var`], but the previous value is restored when the returned guard is dropped.
+pub fn scoped_set<K: AsRef<OsStr>, V: AsRef<OsStr>>(key: K, value: V) -> Guard {
+    let guard = Guard::new(&key);
+    set_var(key, value);
+    guard
+}
+
+/// Like [`remove_var`], but the previous value is restored when the returned guard is dropped.
+pub fn scoped_remove<K: AsRef<OsStr>>(key: K) -> Guard {
+    let guard = Guard::new(&key);
+    remove_var(key);
+    guard
+}
+
+/// Like [`prepend_to_path`], but the previous `PATH` is restored when the returned guard is
+/// dropped.
+pub fn scoped_prepend_path(path: impl AsRef<Path>) -> Result<Guard> {
+    known::PATH.scoped_prepend(path)
+}
+
+/// Serializes the modifications done through [`scoped_set_async`].
+static ASYNC_MODIFICATION_LOCK: LazyLock<Arc<tokio::sync::Mutex<()>>> = LazyLock::new(default);
+
+/// Restores the previous value of an environment variable when dropped, and only then allows other
+/// asynchronous modifications to proceed.
+///
+/// Obtained from [`scoped_set_async`].
+#[derive(Debug)]
+#[must_use = "The variable is restored as soon as the guard is dropped."]
+pub struct AsyncGuard {
+    // Field order matters: the variable must be restored before the lock is released.
+    guard: Guard,
+    _lock: tokio::sync::OwnedMutexGuard<()>,
+}
+
+/// Like [`scoped_set`], but waits until no other task holds an [`AsyncGuard`].
+///
+/// The process environment is shared by all the tasks. If two tasks modify the same variable
+/// concurrently, they may restore the values in the wrong order and leave the variable modified.
+/// Using this function instead of [`scoped_set`] in tasks that may run concurrently prevents this.
+pub async fn scoped_set_async<K: AsRef<OsStr>, V: AsRef<OsStr>>(key: K, value: V) -> AsyncGuard {
+    let lock = ASYNC_MODIFICATION_LOCK.clone().lock_owned().await;
+    let guard = scoped_set(key, value);
+    AsyncGuard { guard, _lock: lock }
+}
+
 /// A modification to some environment variable.
 #[derive(Clone, Debug)]
 pub enum Action {
@@ -176,25 +368,46 @@ impl Modification {
             Action::Set(value) => {
                 set_var(normalized_name, value);
             }
-            Action::PrependPaths(paths_to_prepend) =>
-                if let Ok(old_value) = std::env::var(normalized_name) {
-                    debug!(
-                        "Prepending to {} the following paths: {:?}",
-                        self.variable_name, paths_to_prepend
-                    );
-                    let new_paths_set = paths_to_prepend.iter().collect::<BTreeSet<_>>();
-                    let old_paths = std::env::split_paths(&old_value).collect_vec();
-
-                    let old_paths_filtered =
-                        old_paths.iter().filter(|old_path| !new_paths_set.contains(old_path));
-                    let new_value =
-                        std::env::join_paths(paths_to_prepend.iter().chain(old_paths_filtered))?;
-                    set_var(&*self.variable_name, new_value);
-                } else {
-                    let new_value = std::env::join_paths(paths_to_prepend)?;
-                    set_var(&*self.variable_name, new_value);
-                },
+            Action::PrependPaths(paths_to_prepend) => {
+                path_edit::PathEdit::new_by_name(normalized_name)
+                    .prepend_all(paths_to_prepend.iter().cloned())
+                    .apply()?;
+            }
         };
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_set_restores_previous_value() {
+        const NAME: &str = "ENSO_TEST_SCOPED_SET";
+        remove_var(NAME);
+        {
+            let _outer = scoped_set(NAME, "outer");
+            {
+                let _inner = scoped_set(NAME, "inner");
+                assert_eq!(std::env::var(NAME).as_deref(), Ok("inner"));
+            }
+            assert_eq!(std::env::var(NAME).as_deref(), Ok("outer"));
+            {
+                let _removed = scoped_remove(NAME);
+                assert!(std::env::var_os(NAME).is_none());
+            }
+            assert_eq!(std::env::var(NAME).as_deref(), Ok("outer"));
+        }
+        assert!(std::env::var_os(NAME).is_none());
+    }
+
+    #[test]
+    fn declarations_are_registered() {
+        let definition = |name| definitions().find(|definition| definition.name == name).copied();
+        let github_token = Definition { name: "GITHUB_TOKEN", secret: true };
+        assert_eq!(definition("GITHUB_TOKEN"), Some(github_token));
+        let app_data = Definition { name: "APPDATA", secret: false };
+        assert_eq!(definition("APPDATA"), Some(app_data));
+    }
+}
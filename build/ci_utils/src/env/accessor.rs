@@ -4,6 +4,8 @@ use crate::env::expect_var;
 use crate::env::expect_var_os;
 use crate::program::command::FallibleManipulator;
 
+use std::time::Duration;
+
 
 
 /// An environment variable of known name.
@@ -62,7 +64,22 @@ pub trait TypedVariable: RawVariable {
 
     /// Get the value of this variable.
     fn get(&self) -> Result<Self::Value> {
-        self.parse(self.get_raw()?.as_str())
+        let value = self.get_raw()?;
+        // The value is not included in the context, as it might be a secret.
+        self.parse(&value)
+            .with_context(|| format!("Failed to parse environment variable {}.", self.name()))
+    }
+
+    /// Get the value of this variable, if it is set.
+    ///
+    /// Unlike `get().ok()`, this does not hide the error if the variable is set to an invalid
+    /// value.
+    fn get_opt(&self) -> Result<Option<Self::Value>> {
+        if self.is_set() {
+            self.get().map(Some)
+        } else {
+            Ok(None)
+        }
     }
 
     /// Set the value of this variable.
@@ -72,6 +89,14 @@ pub trait TypedVariable: RawVariable {
         Ok(())
     }
 
+    /// Set the value of this variable until the returned guard is dropped.
+    ///
+    /// See [`crate::env::scoped_set`].
+    fn scoped_set(&self, value: &Self::Borrowed) -> Result<crate::env::Guard> {
+        let value = self.generate(value)?;
+        Ok(crate::env::scoped_set(self.name(), value))
+    }
+
     /// Set the value of this variable, and make it persistent within the current CI job.
     ///
     /// When used in non-CI environments, just sets the variable and outputs log.
@@ -225,11 +250,22 @@ impl TypedVariable for PathLike {
 impl PathLike {
     #[context("Failed to prepend path `{}` to `{}`.", value.as_ref().display(), self.name())]
     pub fn prepend(&self, value: impl AsRef<Path>) -> Result {
-        let value = value.as_ref().to_path_buf();
-        trace!("Prepending {} to {}.", value.display(), self.name());
-        let mut paths = self.get()?;
-        paths.insert(0, value);
-        self.set(&paths)
+        self.edit().prepend(value.as_ref()).apply()?;
+        Ok(())
+    }
+
+    /// Start a declarative edit of this variable, see
+    /// [`PathEdit`](crate::env::path_edit::PathEdit).
+    pub fn edit(&self) -> crate::env::path_edit::PathEdit {
+        crate::env::path_edit::PathEdit::new(self)
+    }
+
+    /// Like [`PathLike::prepend`], but the previous value is restored when the returned guard is
+    /// dropped.
+    pub fn scoped_prepend(&self, value: impl AsRef<Path>) -> Result<crate::env::Guard> {
+        let guard = crate::env::Guard::new(self.name());
+        self.prepend(value)?;
+        Ok(guard)
     }
 }
 
@@ -258,3 +294,333 @@ impl TypedVariable for Separated {
         Ok(value.join(self.separator))
     }
 }
+
+/// Environment variable with a boolean value.
+///
+/// Unlike [`SimpleVariable<bool>`], it accepts all the common truthy and falsy forms, like `1`,
+/// `yes` or `off`. See [`TRUTHY_VALUES`] and [`FALSY_VALUES`].
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, derive_more::Deref)]
+pub struct BoolVariable {
+    #[deref]
+    pub name: &'static str,
+}
+
+/// Values recognized as `true` by [`BoolVariable`]. Comparison is case-insensitive.
+pub const TRUTHY_VALUES: &[&str] = &["1", "true", "yes", "y", "on"];
+
+/// Values recognized as `false` by [`BoolVariable`]. Comparison is case-insensitive.
+pub const FALSY_VALUES: &[&str] = &["0", "false", "no", "n", "off"];
+
+impl BoolVariable {
+    pub const fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+impl RawVariable for BoolVariable {
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+impl TypedVariable for BoolVariable {
+    type Value = bool;
+    type Borrowed = bool;
+    fn parse(&self, value: &str) -> Result<Self::Value> {
+        let matches = |candidates: &[&str]| {
+            candidates.iter().any(|candidate| candidate.eq_ignore_ascii_case(value.trim()))
+        };
+        if matches(TRUTHY_VALUES) {
+            Ok(true)
+        } else if matches(FALSY_VALUES) {
+            Ok(false)
+        } else {
+            bail!(
+                "Invalid boolean value `{value}`. Expected one of: {} (for true) or {} (for false).",
+                TRUTHY_VALUES.join(", "),
+                FALSY_VALUES.join(", ")
+            )
+        }
+    }
+    fn generate(&self, value: &Self::Borrowed) -> Result<String> {
+        Ok(value.to_string())
+    }
+}
+
+/// Environment variable with a [`Duration`] value, like `30s`, `5m` or `1h30m`.
+///
+/// See [`parse_duration`] for the accepted format.
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, derive_more::Deref)]
+pub struct DurationVariable {
+    #[deref]
+    pub name: &'static str,
+}
+
+impl DurationVariable {
+    pub const fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+impl RawVariable for DurationVariable {
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+impl TypedVariable for DurationVariable {
+    type Value = Duration;
+    type Borrowed = Duration;
+    fn parse(&self, value: &str) -> Result<Self::Value> {
+        parse_duration(value)
+    }
+    fn generate(&self, value: &Self::Borrowed) -> Result<String> {
+        format_duration(*value)
+    }
+}
+
+/// Parse a human-readable duration.
+///
+/// The duration is a sequence of integer values with units, e.g. `1h30m`. Supported units are
+/// `ms`, `s`, `m`, `h` and `d`. A bare integer is interpreted as a number of seconds.
+pub fn parse_duration(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    ensure!(!text.is_empty(), "Duration cannot be empty.");
+    if let Ok(seconds) = text.parse::<u64>() {
+        return Ok(Duration::from_secs(seconds));
+    }
+    let mut total = Duration::ZERO;
+    let mut remaining = text;
+    while !remaining.is_empty() {
+        let number_end = remaining.find(|c: char| !c.is_ascii_digit()).unwrap_or(remaining.len());
+        let (number, tail) = remaining.split_at(number_end);
+        let unit_end = tail.find(|c: char| c.is_ascii_digit()).unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_end);
+        let number = number
+            .parse::<u64>()
+            .with_context(|| format!("Expected a number before `{unit}` in duration `{text}`."))?;
+        let part = match unit.trim() {
+            "ms" => Some(Duration::from_millis(number)),
+            "s" => Some(Duration::from_secs(number)),
+            "m" => number.checked_mul(60).map(Duration::from_secs),
+            "h" => number.checked_mul(60 * 60).map(Duration::from_secs),
+            "d" => number.checked_mul(24 * 60 * 60).map(Duration::from_secs),
+            other => bail!(
+                "Unknown unit `{other}` in duration `{text}`. Expected one of: ms, s, m, h, d."
+            ),
+        };
+        total = part
+            .and_then(|part| total.checked_add(part))
+            .with_context(|| format!("Duration `{text}` is too large."))?;
+        remaining = tail.trim_start();
+    }
+    Ok(total)
+}
+
+/// Pretty-print a duration in the format accepted by [`parse_duration`].
+///
+/// Fails if the duration has sub-millisecond precision.
+pub fn format_duration(duration: Duration) -> Result<String> {
+    ensure!(
+        duration.subsec_nanos() % 1_000_000 == 0,
+        "Duration {duration:?} cannot be represented with millisecond precision."
+    );
+    if duration.subsec_millis() == 0 {
+        Ok(format!("{}s", duration.as_secs()))
+    } else {
+        Ok(format!("{}ms", duration.as_millis()))
+    }
+}
+
+/// Environment variable with a value being one of enum variants.
+///
+/// Unlike [`SimpleVariable`], when the value is not recognized, the error lists all the accepted
+/// values.
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, derive_more::Deref)]
+pub struct EnumVariable<Value> {
+    #[deref]
+    pub name:         &'static str,
+    pub phantom_data: PhantomData<Value>,
+}
+
+impl<Value> EnumVariable<Value> {
+    pub const fn new(name: &'static str) -> Self {
+        Self { name, phantom_data: PhantomData }
+    }
+}
+
+impl<Value> RawVariable for EnumVariable<Value> {
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+impl<Value: FromStr + ToString + strum::VariantNames> TypedVariable for EnumVariable<Value> {
+    type Value = Value;
+    type Borrowed = Value;
+    fn parse(&self, value: &str) -> Result<Self::Value> {
+        Value::from_str(value).map_err(|_| {
+            anyhow!("Invalid value `{value}`. Expected one of: {}.", Value::VARIANTS.join(", "))
+        })
+    }
+    fn generate(&self, value: &Self::Borrowed) -> Result<String> {
+        Ok(value.to_string())
+    }
+}
+
+/// Environment variable with a secret value, like a password or an access token.
+///
+/// Whenever the value is read or set through this accessor, it is registered for masking (see
+/// [`crate::log::register_secret`]), so it does not leak into the build logs.
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, derive_more::Deref)]
+pub struct SecretVariable {
+    #[deref]
+    pub name: &'static str,
+}
+
+impl SecretVariable {
+    pub const fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+impl RawVariable for SecretVariable {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn get_raw(&self) -> Result<String> {
+        let value = expect_var(self.name())?;
+        crate::log::register_secret(&value);
+        Ok(value)
+    }
+
+    fn get_raw_os(&self) -> Result<OsString> {
+        let value = expect_var_os(self.name())?;
+        crate::log::register_secret(value.to_string_lossy());
+        Ok(value)
+    }
+
+    fn set_raw(&self, value: impl AsRef<OsStr>) {
+        crate::log::register_secret(value.as_ref().to_string_lossy());
+        crate::env::set_var(self.name(), value);
+    }
+}
+
+impl TypedVariable for SecretVariable {
+    type Value = String;
+    type Borrowed = str;
+    fn parse(&self, value: &str) -> Result<Self::Value> {
+        crate::log::register_secret(value);
+        Ok(value.to_string())
+    }
+    fn generate(&self, value: &Self::Borrowed) -> Result<String> {
+        crate::log::register_secret(value);
+        Ok(value.to_string())
+    }
+}
+
+/// Implement conversions and formatting that let the variable be used where its name is expected.
+macro_rules! impl_variable_name_conversions {
+    ($([$($generics:tt)*] $ty:ty),* $(,)?) => {$(
+        impl<$($generics)*> AsRef<str> for $ty {
+            fn as_ref(&self) -> &str {
+                self.name
+            }
+        }
+
+        impl<$($generics)*> AsRef<OsStr> for $ty {
+            fn as_ref(&self) -> &OsStr {
+                OsStr::new(self.name)
+            }
+        }
+
+        impl<$($generics)*> From<$ty> for String {
+            fn from(value: $ty) -> Self {
+                value.name.to_string()
+            }
+        }
+
+        impl<$($generics)*> From<&$ty> for String {
+            fn from(value: &$ty) -> Self {
+                value.name.to_string()
+            }
+        }
+
+        impl<$($generics)*> Display for $ty {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.name)
+            }
+        }
+    )*};
+}
+
+impl_variable_name_conversions!(
+    [] BoolVariable,
+    [] DurationVariable,
+    [Value] EnumVariable<Value>,
+    [] SecretVariable,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bool_parsing() -> Result {
+        const VAR: BoolVariable = BoolVariable::new("ENSO_TEST_BOOL");
+        for truthy in ["1", "true", "True", "YES", "on", " y "] {
+            assert!(VAR.parse(truthy)?, "{truthy} should be true");
+        }
+        for falsy in ["0", "false", "FALSE", "no", "off", "n"] {
+            assert!(!VAR.parse(falsy)?, "{falsy} should be false");
+        }
+        assert!(VAR.parse("maybe").is_err());
+        assert!(VAR.parse("").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn duration_parsing() -> Result {
+        assert_eq!(parse_duration("30")?, Duration::from_secs(30));
+        assert_eq!(parse_duration("30s")?, Duration::from_secs(30));
+        assert_eq!(parse_duration("250ms")?, Duration::from_millis(250));
+        assert_eq!(parse_duration("5m")?, Duration::from_secs(300));
+        assert_eq!(parse_duration("1h 30m")?, Duration::from_secs(90 * 60));
+        assert_eq!(parse_duration("2d")?, Duration::from_secs(2 * 24 * 60 * 60));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("5 minutes").is_err());
+        assert!(parse_duration("m").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn secret_is_masked() -> Result {
+        const VAR: SecretVariable = SecretVariable::new("ENSO_TEST_SECRET");
+        let secret = "ENSO_TEST_SECRET_VALUE_8d2f1";
+        assert_eq!(VAR.parse(secret)?, secret);
+        let message = format!("Authenticating with {secret}.");
+        assert_eq!(crate::log::mask_secrets(&message), "Authenticating with ***.");
+        Ok(())
+    }
+
+    #[test]
+    fn raw_secret_is_masked() -> Result {
+        const VAR: SecretVariable = SecretVariable::new("ENSO_TEST_RAW_SECRET");
+        let secret = "ENSO_TEST_RAW_SECRET_VALUE_3c9a7";
+        std::env::set_var(VAR.name, secret);
+        assert_eq!(VAR.get_raw()?, secret);
+        let message = format!("Signing with {secret}.");
+        assert_eq!(crate::log::mask_secrets(&message), "Signing with ***.");
+        Ok(())
+    }
+
+    #[test]
+    fn duration_round_trip() -> Result {
+        for duration in [Duration::from_secs(42), Duration::from_millis(1500), Duration::ZERO] {
+            assert_eq!(parse_duration(&format_duration(duration)?)?, duration);
+        }
+        assert!(format_duration(Duration::from_nanos(1)).is_err());
+        Ok(())
+    }
+}
@@ -57,6 +57,38 @@ pub mod win {
     }
 }
 
+/// macOS-specific environment variables.
+pub mod macos {
+    use super::*;
+
+    define_env_var! {
+        /// The user's home directory.
+        HOME, PathBuf;
+
+        /// Directory for temporary files, specific to the current user.
+        ///
+        /// Unlike on Linux, this is usually not `/tmp` but a per-user directory under
+        /// `/var/folders`.
+        TMPDIR, PathBuf;
+
+        /// List of directories searched for dynamic libraries before the standard locations.
+        ///
+        /// Note that this variable is stripped by System Integrity Protection when launching
+        /// protected binaries (like `/bin/sh`), so it might not get propagated to grandchildren.
+        DYLD_LIBRARY_PATH, Vec<PathBuf>;
+
+        /// The username of Apple developer account, used for notarization.
+        APPLEID, secret String;
+
+        /// The app-specific password (not Apple ID password), used for notarization. See:
+        /// https://support.apple.com/HT204397
+        APPLEIDPASS, secret String;
+
+        /// Apple Team ID, used for notarization.
+        APPLETEAMID, secret String;
+    }
+}
+
 define_env_var! {
     /// Overrides individual `LC_*` settings for consistent locale-specific behavior across programs.
     /// - [`LC_TIME`]: Defines formatting for dates and times.
@@ -0,0 +1,300 @@
+//! Declarative modifications of `PATH`-like environment variables.
+//!
+//! Instead of concatenating strings, the modification is described as a sequence of
+//! [operations](PathOperation) that can be previewed as a diff before being applied.
+
+use crate::prelude::*;
+
+use crate::env::accessor::RawVariable;
+
+
+
+// =====================
+// === PathOperation ===
+// =====================
+
+/// A single operation on the list of paths.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathOperation {
+    /// Put the paths at the beginning of the list, in the given order.
+    ///
+    /// If any of the paths is already present, it is moved rather than duplicated.
+    Prepend(Vec<PathBuf>),
+    /// Put the paths at the end of the list, in the given order.
+    ///
+    /// If any of the paths is already present, it is moved rather than duplicated.
+    Append(Vec<PathBuf>),
+    /// Remove all occurrences of the paths.
+    Remove(Vec<PathBuf>),
+    /// Remove all but the first occurrence of each path.
+    Dedup,
+}
+
+impl PathOperation {
+    /// Apply this operation to the given list of paths.
+    pub fn apply(&self, paths: &mut Vec<PathBuf>) {
+        let contains = |list: &[PathBuf], path: &Path| list.iter().any(|p| is_same_entry(p, path));
+        match self {
+            Self::Prepend(new_paths) => {
+                paths.retain(|path| !contains(new_paths, path));
+                paths.splice(0..0, new_paths.iter().cloned());
+            }
+            Self::Append(new_paths) => {
+                paths.retain(|path| !contains(new_paths, path));
+                paths.extend(new_paths.iter().cloned());
+            }
+            Self::Remove(removed) => paths.retain(|path| !contains(removed, path)),
+            Self::Dedup => {
+                let mut seen: Vec<PathBuf> = Vec::new();
+                paths.retain(|path| {
+                    let is_duplicate = contains(&seen, path);
+                    if !is_duplicate {
+                        seen.push(path.clone());
+                    }
+                    !is_duplicate
+                });
+            }
+        }
+    }
+}
+
+/// Check if two entries of a `PATH`-like variable denote the same path.
+///
+/// Trailing separators are ignored. On Windows the comparison is case-insensitive, like the
+/// file system.
+pub fn is_same_entry(lhs: &Path, rhs: &Path) -> bool {
+    fn normalize(path: &Path) -> String {
+        let text = path.as_os_str().to_string_lossy();
+        let trimmed = text.trim_end_matches(std::path::is_separator);
+        // Do not turn the root `/` into an empty string.
+        let text = if trimmed.is_empty() { &text } else { trimmed };
+        if TARGET_OS == OS::Windows {
+            text.to_lowercase()
+        } else {
+            text.to_string()
+        }
+    }
+    normalize(lhs) == normalize(rhs)
+}
+
+
+
+// ================
+// === PathEdit ===
+// ================
+
+/// Sequence of operations to be performed on a `PATH`-like environment variable.
+///
+/// The platform-specific separator is used for splitting and joining the entries.
+///
+/// ```
+/// use ide_ci::env::known::PATH;
+/// use ide_ci::env::path_edit::PathEdit;
+///
+/// let edit = PathEdit::new(&PATH).prepend("/opt/tool/bin").remove("/opt/old-tool/bin").dedup();
+/// println!("{}", edit.preview());
+/// ```
+#[derive(Clone, Debug)]
+pub struct PathEdit {
+    /// Name of the modified variable.
+    pub variable_name: String,
+    /// Operations to perform, in order.
+    pub operations:    Vec<PathOperation>,
+}
+
+impl PathEdit {
+    /// Start a new, empty edit of the given variable.
+    pub fn new(variable: &(impl RawVariable + ?Sized)) -> Self {
+        Self::new_by_name(variable.name())
+    }
+
+    /// Like [`PathEdit::new`], but takes the variable name.
+    pub fn new_by_name(variable_name: impl Into<String>) -> Self {
+        Self { variable_name: variable_name.into(), operations: default() }
+    }
+
+    /// Add an operation to the edit.
+    pub fn with(mut self, operation: PathOperation) -> Self {
+        self.operations.push(operation);
+        self
+    }
+
+    /// See [`PathOperation::Prepend`].
+    pub fn prepend(self, path: impl Into<PathBuf>) -> Self {
+        self.with(PathOperation::Prepend(vec![path.into()]))
+    }
+
+    /// See [`PathOperation::Prepend`].
+    pub fn prepend_all(self, paths: impl IntoIterator<Item: Into<PathBuf>>) -> Self {
+        self.with(PathOperation::Prepend(paths.into_iter().map_into().collect()))
+    }
+
+    /// See [`PathOperation::Append`].
+    pub fn append(self, path: impl Into<PathBuf>) -> Self {
+        self.with(PathOperation::Append(vec![path.into()]))
+    }
+
+    /// See [`PathOperation::Append`].
+    pub fn append_all(self, paths: impl IntoIterator<Item: Into<PathBuf>>) -> Self {
+        self.with(PathOperation::Append(paths.into_iter().map_into().collect()))
+    }
+
+    /// See [`PathOperation::Remove`].
+    pub fn remove(self, path: impl Into<PathBuf>) -> Self {
+        self.with(PathOperation::Remove(vec![path.into()]))
+    }
+
+    /// See [`PathOperation::Dedup`].
+    pub fn dedup(self) -> Self {
+        self.with(PathOperation::Dedup)
+    }
+
+    /// Compute the result of applying this edit to the given paths.
+    pub fn compute(&self, current: &[PathBuf]) -> Vec<PathBuf> {
+        let mut paths = current.to_vec();
+        for operation in &self.operations {
+            operation.apply(&mut paths);
+        }
+        paths
+    }
+
+    /// The current value of the variable, split into entries. Unset variable has no entries.
+    pub fn current(&self) -> Vec<PathBuf> {
+        match std::env::var_os(&self.variable_name) {
+            Some(value) => std::env::split_paths(&value).collect(),
+            None => default(),
+        }
+    }
+
+    /// Describe what applying this edit would do, without modifying the environment.
+    pub fn preview(&self) -> PathDiff {
+        let before = self.current();
+        let after = self.compute(&before);
+        PathDiff { variable_name: self.variable_name.clone(), before, after }
+    }
+
+    /// Apply this edit to the process environment.
+    #[context("Failed to modify the environment variable {}.", self.variable_name)]
+    pub fn apply(&self) -> Result<PathDiff> {
+        let diff = self.preview();
+        debug!("Modifying environment variable:\n{diff}");
+        let new_value = std::env::join_paths(&diff.after)?;
+        crate::env::set_var(&self.variable_name, new_value);
+        Ok(diff)
+    }
+}
+
+
+
+// ================
+// === PathDiff ===
+// ================
+
+/// Change of a `PATH`-like variable, as computed by [`PathEdit`].
+///
+/// When displayed, it shows a diff-style listing of entries: added ones are prefixed with `+`,
+/// removed ones with `-`.
+#[derive(Clone, Debug)]
+pub struct PathDiff {
+    /// Name of the modified variable.
+    pub variable_name: String,
+    /// Entries before the modification.
+    pub before:        Vec<PathBuf>,
+    /// Entries after the modification.
+    pub after:         Vec<PathBuf>,
+}
+
+/// Line of the [`PathDiff`] listing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Kept(&'a Path),
+    Added(&'a Path),
+    Removed(&'a Path),
+}
+
+impl PathDiff {
+    /// Check if the modification changes anything.
+    pub fn is_empty(&self) -> bool {
+        self.before == self.after
+    }
+
+    /// Compute the lines of the diff, using the longest common subsequence of the entries.
+    pub fn lines(&self) -> Vec<DiffLine> {
+        let (before, after) = (&self.before, &self.after);
+        // `common[i][j]` is the length of the longest common subsequence of `before[i..]` and
+        // `after[j..]`.
+        let mut common = vec![vec![0usize; after.len() + 1]; before.len() + 1];
+        for i in (0..before.len()).rev() {
+            for j in (0..after.len()).rev() {
+                common[i][j] = if before[i] == after[j] {
+                    common[i + 1][j + 1] + 1
+                } else {
+                    common[i + 1][j].max(common[i][j + 1])
+                };
+            }
+        }
+        let mut ret = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < before.len() || j < after.len() {
+            if i < before.len() && j < after.len() && before[i] == after[j] {
+                ret.push(DiffLine::Kept(&before[i]));
+                i += 1;
+                j += 1;
+            } else if j < after.len() && (i == before.len() || common[i][j + 1] >= common[i + 1][j])
+            {
+                ret.push(DiffLine::Added(&after[j]));
+                j += 1;
+            } else {
+                ret.push(DiffLine::Removed(&before[i]));
+                i += 1;
+            }
+        }
+        ret
+    }
+}
+
+impl Display for PathDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:", self.variable_name)?;
+        for line in self.lines() {
+            let (marker, path) = match line {
+                DiffLine::Kept(path) => (' ', path),
+                DiffLine::Added(path) => ('+', path),
+                DiffLine::Removed(path) => ('-', path),
+            };
+            write!(f, "\n{marker} {}", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn operations() {
+        let current = paths(&["/a", "/b", "/c", "/b/"]);
+        let edit = PathEdit::new_by_name("TEST").prepend("/c").append("/a").remove("/x");
+        assert_eq!(edit.compute(&current), paths(&["/c", "/b", "/b/", "/a"]));
+        let edit = edit.dedup();
+        assert_eq!(edit.compute(&current), paths(&["/c", "/b", "/a"]));
+        let edit = PathEdit::new_by_name("TEST").remove("/b");
+        assert_eq!(edit.compute(&current), paths(&["/a", "/c"]));
+    }
+
+    #[test]
+    fn diff_display() {
+        let diff = PathDiff {
+            variable_name: "PATH".into(),
+            before:        paths(&["/a", "/b", "/c"]),
+            after:         paths(&["/new", "/a", "/c"]),
+        };
+        assert_eq!(diff.to_string(), "PATH:\n+ /new\n  /a\n- /b\n  /c");
+    }
+}
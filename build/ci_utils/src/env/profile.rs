@@ -0,0 +1,293 @@
+//! Loading of `.env`-style files with environment variables.
+//!
+//! The files are layered: `.env` provides defaults, then the files of the active profiles (like
+//! `.env.ci`) are applied, and finally `.env.local` provides the local overrides. Variables that
+//! are already set in the process environment always take precedence over the files.
+//!
+//! This allows local runs of the build script to mirror the CI configuration without exporting
+//! all the variables by hand.
+
+use crate::prelude::*;
+
+use crate::env::accessor::RawVariable;
+use crate::env::accessor::Separated;
+use crate::env::accessor::TypedVariable;
+
+
+
+/// Name of the file with default values.
+pub const DEFAULT_FILE: &str = ".env";
+
+/// Name of the file with local overrides. It should not be committed to the repository.
+pub const LOCAL_FILE: &str = ".env.local";
+
+/// Name of the profile that is always active when running in CI.
+pub const CI_PROFILE: &str = "ci";
+
+/// Names of the additional profiles to load, separated by commas.
+///
+/// E.g. `ci` will load `.env.ci` file, so local run mirrors the CI configuration.
+pub const ENSO_BUILD_ENV_PROFILE: Separated =
+    Separated { name: "ENSO_BUILD_ENV_PROFILE", separator: "," };
+
+
+
+// =============
+// === Parse ===
+// =============
+
+/// Parse the contents of a `.env` file into a list of name-value pairs.
+///
+/// Supported syntax:
+/// * `NAME=value` lines, optionally prefixed with `export`;
+/// * values in single quotes (taken verbatim) or double quotes (supporting `\n`, `\"` and `\\`
+///   escapes);
+/// * full-line comments and comments after unquoted values, starting with `#`;
+/// * empty lines.
+pub fn parse(text: &str) -> Result<Vec<(String, String)>> {
+    let mut ret = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (name, value) = line
+            .split_once('=')
+            .with_context(|| format!("Line {line_number}: expected `NAME=value`, got `{line}`."))?;
+        let name = name.trim();
+        ensure!(
+            !name.is_empty() && !name.contains(char::is_whitespace),
+            "Line {line_number}: invalid variable name `{name}`."
+        );
+        let value = parse_value(value.trim())
+            .with_context(|| format!("Line {line_number}: invalid value of {name}."))?;
+        ret.push((name.to_string(), value));
+    }
+    Ok(ret)
+}
+
+fn parse_value(value: &str) -> Result<String> {
+    if let Some(quoted) = value.strip_prefix('\'') {
+        let end = quoted.find('\'').context("Missing closing single quote.")?;
+        ensure_only_comment(&quoted[end + 1..])?;
+        Ok(quoted[..end].to_string())
+    } else if let Some(quoted) = value.strip_prefix('"') {
+        let mut ret = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((index, character)) = chars.next() {
+            match character {
+                '"' => {
+                    ensure_only_comment(&quoted[index + 1..])?;
+                    return Ok(ret);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => ret.push('\n'),
+                    Some((_, escaped @ ('"' | '\\'))) => ret.push(escaped),
+                    Some((_, other)) => bail!("Unsupported escape sequence `\\{other}`."),
+                    None => break,
+                },
+                _ => ret.push(character),
+            }
+        }
+        bail!("Missing closing double quote.")
+    } else {
+        let value = match value.find(" #") {
+            Some(comment_start) => &value[..comment_start],
+            None => value,
+        };
+        Ok(value.trim_end().to_string())
+    }
+}
+
+fn ensure_only_comment(text: &str) -> Result {
+    let text = text.trim_start();
+    ensure!(
+        text.is_empty() || text.starts_with('#'),
+        "Unexpected text after quoted value: `{text}`."
+    );
+    Ok(())
+}
+
+
+
+// ======================
+// === KnownVariables ===
+// ======================
+
+/// Variables that the program expects to be set through the `.env` files.
+///
+/// Variables from the files that are not known trigger a warning, as they are likely misspelled.
+/// Typed variables have their values validated when loaded.
+#[derive(Default)]
+#[derive_where(Debug)]
+pub struct KnownVariables {
+    names:      BTreeSet<String>,
+    #[derive_where(skip)]
+    validators: HashMap<String, Box<dyn Fn(&str) -> Result + Send + Sync>>,
+}
+
+impl KnownVariables {
+    /// Mark variable of the given name as known, without validating its value.
+    pub fn add_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.names.insert(name.into());
+        self
+    }
+
+    /// Mark the variable as known. Its value will be validated by parsing it.
+    pub fn add<V: TypedVariable + Send + Sync + 'static>(&mut self, variable: V) -> &mut Self {
+        let name = variable.name().to_string();
+        let validator = move |value: &str| variable.parse(value).map(drop);
+        self.validators.insert(name.clone(), Box::new(validator));
+        self.add_name(name)
+    }
+
+    /// Check if the variable of the given name is known.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Validate the value of the variable, if it is known and typed.
+    pub fn validate(&self, name: &str, value: &str) -> Result {
+        match self.validators.get(name) {
+            Some(validator) => validator(value),
+            None => Ok(()),
+        }
+    }
+}
+
+
+
+// ===============
+// === Profile ===
+// ===============
+
+/// A value loaded from one of the profile files.
+#[derive(Clone, Debug)]
+pub struct LoadedValue {
+    /// The value of the variable.
+    pub value:  String,
+    /// The file that defined the value.
+    pub source: PathBuf,
+}
+
+/// Ordered set of `.env` files to be loaded.
+#[derive(Clone, Debug)]
+pub struct Profile {
+    /// Files to load, from the lowest to the highest priority. Missing files are skipped.
+    pub layers: Vec<PathBuf>,
+}
+
+impl Profile {
+    /// The standard layering of files in the given directory: `.env`, then `.env.<profile>` for
+    /// each of the active profiles, then `.env.local`.
+    ///
+    /// The [`CI_PROFILE`] is active when running in CI. Additional profiles can be activated using
+    /// [`ENSO_BUILD_ENV_PROFILE`].
+    pub fn standard(directory: impl AsRef<Path>) -> Result<Self> {
+        let directory = directory.as_ref();
+        let mut profiles = Vec::new();
+        if crate::ci::run_in_ci() {
+            profiles.push(CI_PROFILE.to_string());
+        }
+        if let Some(requested) = ENSO_BUILD_ENV_PROFILE.get_opt()? {
+            let requested =
+                requested.iter().map(|name| name.trim()).filter(|name| !name.is_empty());
+            for name in requested {
+                if !profiles.iter().any(|profile| profile == name) {
+                    profiles.push(name.to_string());
+                }
+            }
+        }
+        let profile_files = profiles.iter().map(|name| format!("{DEFAULT_FILE}.{name}"));
+        let layers = once(DEFAULT_FILE.to_string())
+            .chain(profile_files)
+            .chain(once(LOCAL_FILE.to_string()))
+            .map(|file| directory.join(file))
+            .collect();
+        Ok(Self { layers })
+    }
+
+    /// Read all the existing layers and merge them. Later layers override the earlier ones.
+    pub fn read(&self) -> Result<BTreeMap<String, LoadedValue>> {
+        let mut ret = BTreeMap::new();
+        for layer in &self.layers {
+            if !layer.exists() {
+                trace!("Skipping missing environment file {}.", layer.display());
+                continue;
+            }
+            debug!("Reading environment file {}.", layer.display());
+            let text = crate::fs::read_to_string(layer)?;
+            let variables =
+                parse(&text).with_context(|| format!("Failed to parse {}.", layer.display()))?;
+            for (name, value) in variables {
+                ret.insert(name, LoadedValue { value, source: layer.clone() });
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Set the variables from the profile files in the process environment.
+    ///
+    /// Variables already set in the environment are not overridden. Values of the known typed
+    /// variables are validated, and unknown variables are reported with a warning.
+    pub fn apply(&self, known: &KnownVariables) -> Result {
+        for (name, LoadedValue { value, source }) in self.read()? {
+            known.validate(&name, &value).with_context(|| {
+                format!("Invalid value of {name} in environment file {}.", source.display())
+            })?;
+            if !known.contains(&name) {
+                warn!(
+                    "Environment file {} sets {name} which is not known to be used. Is it \
+                    misspelled?",
+                    source.display()
+                );
+            }
+            if std::env::var_os(&name).is_some() {
+                debug!(
+                    "Not applying {name} from {}, as it is already set in the environment.",
+                    source.display()
+                );
+            } else {
+                crate::env::set_var(&name, &value);
+            }
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing() -> Result {
+        let text = r#"
+# Comment.
+PLAIN=value
+export EXPORTED=1
+SPACED = some value # trailing comment
+SINGLE='verbatim \n # not a comment'
+DOUBLE="line\nbreak \"quoted\""
+EMPTY=
+"#;
+        let expected = [
+            ("PLAIN", "value"),
+            ("EXPORTED", "1"),
+            ("SPACED", "some value"),
+            ("SINGLE", r"verbatim \n # not a comment"),
+            ("DOUBLE", "line\nbreak \"quoted\""),
+            ("EMPTY", ""),
+        ];
+        let expected =
+            expected.into_iter().map(|(name, value)| (name.into(), value.into())).collect_vec();
+        assert_eq!(parse(text)?, expected);
+
+        assert!(parse("NO_EQUALS_SIGN").is_err());
+        assert!(parse("UNTERMINATED=\"value").is_err());
+        assert!(parse("TRAILING='value' garbage").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+//! Snapshots of the process environment, used to diagnose differences between build environments.
+//!
+//! A snapshot is captured at the start of a build step. Comparing it against a snapshot from
+//! another run (e.g. the CI one) tells which variables differ.
+
+use crate::prelude::*;
+
+use crate::env::accessor::PathBufVariable;
+
+
+
+/// Path to a snapshot (as written by [`Snapshot::write`]) to compare the current environment
+/// against.
+pub const ENSO_BUILD_ENV_SNAPSHOT_BASELINE: PathBufVariable =
+    PathBufVariable("ENSO_BUILD_ENV_SNAPSHOT_BASELINE");
+
+/// Fragments of variable names that suggest the value is a credential.
+///
+/// Values of such variables are masked in the snapshot, even if they were not registered as
+/// secrets.
+pub const SENSITIVE_NAME_FRAGMENTS: [&str; 6] =
+    ["TOKEN", "SECRET", "PASSWORD", "PASS", "CREDENTIAL", "KEY"];
+
+/// Check if the variable name suggests that its value is a credential.
+pub fn is_sensitive_name(name: &str) -> bool {
+    let name = name.to_uppercase();
+    SENSITIVE_NAME_FRAGMENTS.iter().any(|fragment| name.contains(fragment))
+}
+
+
+
+// ================
+// === Snapshot ===
+// ================
+
+/// Environment variables of the process, with secrets masked.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Variable names mapped to their (possibly masked) values.
+    pub variables: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+    /// Capture all the variables of the current process.
+    pub fn capture() -> Self {
+        Self::capture_filtered(|_| true)
+    }
+
+    /// Capture the variables of the current process whose names satisfy the predicate.
+    ///
+    /// Values of registered secrets (see [`crate::log::register_secret`]), of variables declared
+    /// as secrets (see [`crate::env::register_declared_secrets`]) and of variables with
+    /// [sensitive names](is_sensitive_name) are masked. Variables that are not valid Unicode are
+    /// skipped.
+    pub fn capture_filtered(mut filter: impl FnMut(&str) -> bool) -> Self {
+        crate::env::register_declared_secrets();
+        let variables = std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .filter(|(name, _)| filter(name))
+            .map(|(name, value)| {
+                let value = if is_sensitive_name(&name) && !value.is_empty() {
+                    crate::log::MASK.to_string()
+                } else {
+                    crate::log::mask_secrets(&value).into_owned()
+                };
+                (name, value)
+            })
+            .collect();
+        Self { variables }
+    }
+
+    /// Compute the changes from the `previous` snapshot to this one.
+    pub fn diff(&self, previous: &Snapshot) -> Diff {
+        let mut diff = Diff::default();
+        for (name, value) in &self.variables {
+            match previous.variables.get(name) {
+                None => {
+                    diff.added.insert(name.clone(), value.clone());
+                }
+                Some(previous_value) if previous_value != value => {
+                    diff.changed.insert(name.clone(), (previous_value.clone(), value.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, value) in &previous.variables {
+            if !self.variables.contains_key(name) {
+                diff.removed.insert(name.clone(), value.clone());
+            }
+        }
+        diff
+    }
+
+    /// Read a snapshot from a JSON file.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        crate::fs::read_json(path)
+    }
+
+    /// Write the snapshot to a JSON file.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result {
+        crate::fs::write_json(path, self)
+    }
+}
+
+
+
+// ============
+// === Diff ===
+// ============
+
+/// Differences between two [snapshots](Snapshot).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diff {
+    /// Variables that were not present in the previous snapshot.
+    pub added:   BTreeMap<String, String>,
+    /// Variables that are not present anymore.
+    pub removed: BTreeMap<String, String>,
+    /// Variables with different values, mapped to the previous and the current value.
+    pub changed: BTreeMap<String, (String, String)>,
+}
+
+impl Diff {
+    /// Check if the snapshots are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No differences in the environment.");
+        }
+        let added = self.added.iter().map(|(name, value)| format!("+ {name}={value}"));
+        let removed = self.removed.iter().map(|(name, value)| format!("- {name}={value}"));
+        let changed = self
+            .changed
+            .iter()
+            .map(|(name, (previous, current))| format!("~ {name}: {previous} -> {current}"));
+        write!(f, "{}", added.chain(removed).chain(changed).join("\n"))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(variables: &[(&str, &str)]) -> Snapshot {
+        let variables = variables.iter().map(|(name, value)| (name.to_string(), value.to_string()));
+        Snapshot { variables: variables.collect() }
+    }
+
+    #[test]
+    fn diff() {
+        let previous = snapshot(&[("KEPT", "1"), ("CHANGED", "old"), ("REMOVED", "x")]);
+        let current = snapshot(&[("KEPT", "1"), ("CHANGED", "new"), ("ADDED", "y")]);
+        let diff = current.diff(&previous);
+        assert_eq!(diff.to_string(), "+ ADDED=y\n- REMOVED=x\n~ CHANGED: old -> new");
+        assert!(current.diff(&current).is_empty());
+    }
+
+    #[test]
+    fn sensitive_values_are_masked() {
+        const NAME: &str = "ENSO_TEST_SNAPSHOT_API_TOKEN";
+        let _guard = crate::env::scoped_set(NAME, "hunter2");
+        let snapshot = Snapshot::capture_filtered(|name| name == NAME);
+        assert_eq!(snapshot.variables.get(NAME).map(String::as_str), Some(crate::log::MASK));
+    }
+
+    #[test]
+    fn declared_secrets_are_masked() {
+        crate::define_env_var! {
+            ENSO_TEST_SNAPSHOT_CERTIFICATE, secret String;
+        }
+        let _guard = crate::env::scoped_set(ENSO_TEST_SNAPSHOT_CERTIFICATE, "certificate-data");
+        let snapshot = Snapshot::capture_filtered(|name| name == *ENSO_TEST_SNAPSHOT_CERTIFICATE);
+        let value = snapshot.variables.get(*ENSO_TEST_SNAPSHOT_CERTIFICATE);
+        assert_eq!(value.map(String::as_str), Some(crate::log::MASK));
+    }
+}
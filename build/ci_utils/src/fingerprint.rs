@@ -0,0 +1,336 @@
+//! Fingerprinting of build step inputs, so the steps whose inputs have not changed can be skipped.
+//!
+//! A [`Fingerprint`] describes the inputs of a step: contents of files, values of environment
+//! variables, versions of tools, etc. After a step succeeds, its fingerprint is recorded in the
+//! [`StateDatabase`]. On the next run, the step is skipped if its fingerprint is unchanged. Every
+//! decision is recorded together with its reasons, so [`Report::take`] can explain why each step
+//! was or was not rebuilt.
+//!
+//! Setting [`ENSO_BUILD_FORCE_REBUILD`] disables the skipping.
+
+use crate::prelude::*;
+
+use crate::cache::lock::EntryLock;
+
+use sha2::Digest;
+use std::sync::Mutex;
+
+
+
+define_env_var! {
+    /// Run all the build steps, even if their inputs have not changed since their last success.
+    ENSO_BUILD_FORCE_REBUILD, flag;
+}
+
+/// Maximum number of changes listed as the reasons for a rebuild.
+pub const MAX_LISTED_CHANGES: usize = 10;
+
+/// Directories that are never included when fingerprinting a directory tree.
+pub const IGNORED_DIRECTORIES: [&str; 3] = [".git", "node_modules", "target"];
+
+/// Decisions made since the last [`Report::take`].
+static DECISIONS: Mutex<Vec<(String, Decision)>> = Mutex::new(Vec::new());
+
+/// Check if the rebuild of all the steps was requested.
+pub fn is_forced() -> bool {
+    ENSO_BUILD_FORCE_REBUILD.get().unwrap_or(false)
+}
+
+fn sha256_hex(data: impl AsRef<[u8]>) -> String {
+    data_encoding::HEXLOWER.encode(&sha2::Sha256::digest(data))
+}
+
+
+
+// ===================
+// === Fingerprint ===
+// ===================
+
+/// Description of the build step inputs. Consists of named components, each with a value or hash.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    pub components: BTreeMap<String, String>,
+}
+
+impl Fingerprint {
+    /// Create an empty fingerprint.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Include an arbitrary value, like a build option.
+    pub fn with_value(mut self, name: impl Into<String>, value: impl Display) -> Self {
+        self.components.insert(name.into(), value.to_string());
+        self
+    }
+
+    /// Include the version of a tool.
+    pub fn with_tool_version(self, tool: impl Display, version: impl Display) -> Self {
+        self.with_value(format!("tool:{tool}"), version)
+    }
+
+    /// Include the value of an environment variable. Only its hash is stored, as it might be a
+    /// secret.
+    pub fn with_env(self, name: impl AsRef<str>) -> Self {
+        let name = name.as_ref();
+        let value = std::env::var_os(name)
+            .map_or_else(|| "<unset>".to_string(), |v| sha256_hex(v.as_encoded_bytes()));
+        self.with_value(format!("env:{name}"), value)
+    }
+
+    /// Include the contents of a file.
+    pub fn with_file(self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let hash = sha256_hex(crate::fs::read(path)?);
+        Ok(self.with_value(format!("file:{}", path.display()), hash))
+    }
+
+    /// Include the contents of a file that might not exist. Its absence is also recorded, so
+    /// creating the file changes the fingerprint.
+    pub fn with_optional_file(self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            self.with_file(path)
+        } else {
+            Ok(self.with_value(format!("file:{}", path.display()), "<missing>"))
+        }
+    }
+
+    /// Include the contents of all files in the directory tree that pass the filter.
+    ///
+    /// The files are identified by their paths relative to the `root`. The
+    /// [ignored directories](IGNORED_DIRECTORIES) are not traversed.
+    #[context("Failed to fingerprint the directory tree {}.", root.as_ref().display())]
+    pub fn with_tree(
+        mut self,
+        root: impl AsRef<Path>,
+        filter: impl Fn(&Path) -> bool,
+    ) -> Result<Self> {
+        let root = root.as_ref();
+        let is_ignored = |entry: &walkdir::DirEntry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.file_type().is_dir() && IGNORED_DIRECTORIES.contains(&name.as_ref())
+        };
+        let walker = walkdir::WalkDir::new(root).into_iter().filter_entry(|e| !is_ignored(e));
+        for entry in walker {
+            let entry = entry?;
+            if entry.file_type().is_file() && filter(entry.path()) {
+                let relative = entry.path().strip_prefix(root)?;
+                let relative = path_slash::PathExt::to_slash_lossy(relative);
+                let hash = sha256_hex(crate::fs::read(entry.path())?);
+                self.components.insert(format!("file:{relative}"), hash);
+            }
+        }
+        Ok(self)
+    }
+
+    /// The changes of this fingerprint's components relative to the previous one.
+    pub fn changes_since(&self, previous: &Fingerprint) -> Vec<Change> {
+        let removed =
+            previous.components.keys().filter(|name| !self.components.contains_key(*name));
+        let removed = removed.map(|name| Change::Removed(name.clone()));
+        let others = self.components.iter().filter_map(|(name, value)| {
+            match previous.components.get(name) {
+                None => Some(Change::Added(name.clone())),
+                Some(previous) if previous != value => Some(Change::Modified(name.clone())),
+                Some(_) => None,
+            }
+        });
+        others.chain(removed).collect()
+    }
+}
+
+/// A change of a single fingerprint component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    Added(String),
+    Removed(String),
+    Modified(String),
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Added(name) => write!(f, "{name} added"),
+            Change::Removed(name) => write!(f, "{name} removed"),
+            Change::Modified(name) => write!(f, "{name} changed"),
+        }
+    }
+}
+
+
+
+// ================
+// === Decision ===
+// ================
+
+/// Whether a build step should be run, and why.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// The inputs have not changed since the last successful run.
+    Skip,
+    /// The step must be run for the given reasons.
+    Run { reasons: Vec<String> },
+}
+
+impl Decision {
+    /// Whether the step should be run.
+    pub fn should_run(&self) -> bool {
+        matches!(self, Decision::Run { .. })
+    }
+}
+
+impl Display for Decision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Decision::Skip => write!(f, "skipped, inputs unchanged"),
+            Decision::Run { reasons } => write!(f, "run, {}", reasons.join("; ")),
+        }
+    }
+}
+
+
+
+// =====================
+// === StateDatabase ===
+// =====================
+
+/// Stored state of the database.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct State {
+    /// Fingerprints of the inputs of the last successful run of each step.
+    steps: BTreeMap<String, Fingerprint>,
+}
+
+/// Local database of the fingerprints of the last successful run of each build step.
+#[derive(Clone, Debug)]
+pub struct StateDatabase {
+    /// The JSON file with the state.
+    pub path: PathBuf,
+}
+
+impl StateDatabase {
+    /// Use the database stored in the given file. The file is created if necessary.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn read(&self) -> Result<State> {
+        if self.path.exists() {
+            crate::fs::read_json(&self.path)
+        } else {
+            Ok(default())
+        }
+    }
+
+    /// The fingerprint of the last successful run of the step, if there was one.
+    pub fn last_success(&self, step: &str) -> Result<Option<Fingerprint>> {
+        Ok(self.read()?.steps.remove(step))
+    }
+
+    /// Decide whether the step with the given inputs should be run, and record the decision for
+    /// the [`Report`].
+    pub fn decide(&self, step: &str, fingerprint: &Fingerprint) -> Decision {
+        let decision = if is_forced() {
+            Decision::Run { reasons: vec!["rebuild forced".into()] }
+        } else {
+            match self.last_success(step) {
+                Ok(Some(previous)) => {
+                    let changes = fingerprint.changes_since(&previous);
+                    if changes.is_empty() {
+                        Decision::Skip
+                    } else {
+                        let mut reasons = changes
+                            .iter()
+                            .take(MAX_LISTED_CHANGES)
+                            .map(ToString::to_string)
+                            .collect_vec();
+                        if changes.len() > MAX_LISTED_CHANGES {
+                            let more = changes.len() - MAX_LISTED_CHANGES;
+                            reasons.push(format!("{more} more changes"));
+                        }
+                        Decision::Run { reasons }
+                    }
+                }
+                Ok(None) => Decision::Run { reasons: vec!["no previous successful run".into()] },
+                Err(e) => Decision::Run { reasons: vec![format!("failed to read state: {e}")] },
+            }
+        };
+        info!("Step {step}: {decision}.");
+        DECISIONS.lock().unwrap().push((step.to_string(), decision.clone()));
+        decision
+    }
+
+    /// Record the successful run of the step with the given inputs.
+    pub async fn record_success(&self, step: &str, fingerprint: Fingerprint) -> Result {
+        crate::fs::create_parent_dir_if_missing(&self.path)?;
+        let _lock = EntryLock::acquire(self.path.with_appended_extension("lock")).await?;
+        let mut state = self.read().unwrap_or_else(|e| {
+            warn!("Discarding unreadable build state {}: {e:?}", self.path.display());
+            default()
+        });
+        state.steps.insert(step.to_string(), fingerprint);
+        crate::fs::write_json(&self.path, &state)
+    }
+}
+
+
+
+// ==============
+// === Report ===
+// ==============
+
+/// Decisions about running the fingerprinted build steps.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub decisions: Vec<(String, Decision)>,
+}
+
+impl Report {
+    /// Take all the decisions made so far into a report.
+    pub fn take() -> Self {
+        Self { decisions: std::mem::take(&mut *DECISIONS.lock().unwrap()) }
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (step, decision) in &self.decisions {
+            writeln!(f, "{step}: {decision}")?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn skipping_unchanged_steps() -> Result {
+        let temp = tempfile::tempdir()?;
+        let sources = temp.path().join("sources");
+        crate::fs::create_dir_if_missing(sources.join("target"))?;
+        crate::fs::write(sources.join("main.rs"), "fn main() {}")?;
+        crate::fs::write(sources.join("target").join("ignored.rs"), "")?;
+        let is_rust = |path: &Path| path.extension().is_some_and(|e| e == "rs");
+        let fingerprint = || Fingerprint::new().with_tool_version("rustc", "1.80.0");
+
+        let db = StateDatabase::new(temp.path().join("state.json"));
+        let first = fingerprint().with_tree(&sources, is_rust)?;
+        assert_eq!(first.components.len(), 2);
+        assert!(db.decide("build", &first).should_run());
+        db.record_success("build", first.clone()).await?;
+        assert_eq!(db.decide("build", &first), Decision::Skip);
+
+        crate::fs::write(sources.join("main.rs"), "fn main() { panic!() }")?;
+        let second = fingerprint().with_tree(&sources, is_rust)?;
+        let expected = Decision::Run { reasons: vec!["file:main.rs changed".into()] };
+        assert_eq!(db.decide("build", &second), expected);
+
+        let report = Report::take().to_string();
+        assert!(report.contains("build: skipped, inputs unchanged"));
+        Ok(())
+    }
+}
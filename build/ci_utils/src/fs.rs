@@ -11,6 +11,8 @@ use fs_extra::error::ErrorKind;
 // === Export ===
 // ==============
 
+pub mod atomic;
+pub mod clean;
 pub mod tokio;
 pub mod wrappers;
 
@@ -0,0 +1,145 @@
+//! File writes that never leave partially written files behind.
+//!
+//! An interrupted build step (e.g. a cancelled CI job) must not leave a half-written artifact, as
+//! the subsequent runs would take it for a complete one. The contents are written to a temporary
+//! file next to the destination and renamed into place only once complete, so the destination
+//! holds either the previous or the new contents. With [`Durability::Durable`], the data is also
+//! synced to the storage device before returning, so the change survives a system crash.
+
+use crate::prelude::*;
+
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+
+
+/// Guarantees given by the operations in this module.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Durability {
+    /// Other processes never observe a partial change, but the change might be lost if the
+    /// system crashes.
+    #[default]
+    Atomic,
+    /// Like [`Durability::Atomic`], and the change is synced to the storage device before the
+    /// operation returns.
+    Durable,
+}
+
+/// Create a temporary file in the destination's directory, removed when dropped.
+///
+/// Once written, it can be moved into place with [`persist`]. Being in the same directory, it is
+/// on the same filesystem as the destination, so the move is a simple rename.
+#[context("Failed to create a temporary file for {}.", destination.as_ref().display())]
+pub fn staging_file(destination: impl AsRef<Path>) -> Result<NamedTempFile> {
+    let destination = destination.as_ref();
+    let parent = crate::fs::create_parent_dir_if_missing(destination)?;
+    let name = destination.try_file_name()?.to_string_lossy();
+    Ok(tempfile::Builder::new().prefix(&format!(".{name}.")).suffix(".tmp").tempfile_in(parent)?)
+}
+
+/// Move the complete temporary file to the destination, replacing any existing file.
+#[context("Failed to move the written file into {}.", destination.as_ref().display())]
+pub fn persist(
+    staged: NamedTempFile,
+    destination: impl AsRef<Path>,
+    durability: Durability,
+) -> Result {
+    let destination = destination.as_ref();
+    // The file must be synced through a writable handle, as Windows cannot flush a read-only one.
+    if durability == Durability::Durable {
+        staged.as_file().sync_all()?;
+    }
+    staged.persist(destination).map_err(|e| e.error)?;
+    if durability == Durability::Durable {
+        sync_parent_dir(destination)?;
+    }
+    Ok(())
+}
+
+/// Write the contents to the file, creating the missing parent directories.
+///
+/// Unlike [`crate::fs::write`], the file is never left partially written.
+#[context("Failed to write path: {}", path.as_ref().display())]
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>, durability: Durability) -> Result {
+    let mut staged = staging_file(&path)?;
+    staged.write_all(contents.as_ref())?;
+    persist(staged, &path, durability)
+}
+
+/// Serialize the data to JSON text and [write](write) it to the file.
+pub fn write_json(
+    path: impl AsRef<Path>,
+    contents: &impl Serialize,
+    durability: Durability,
+) -> Result {
+    write(path, serde_json::to_string(contents)?, durability)
+}
+
+/// Rename the file or directory, replacing the destination file if it exists.
+///
+/// With [`Durability::Durable`], the renamed file and the affected directories are synced.
+#[context("Failed to rename {} to {}.", from.as_ref().display(), to.as_ref().display())]
+pub fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>, durability: Durability) -> Result {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    if durability == Durability::Durable && from.is_file() {
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(from)
+            .and_then(|file| file.sync_all())
+            .with_context(|| format!("Failed to sync the file {}.", from.display()))?;
+    }
+    crate::fs::create_parent_dir_if_missing(to)?;
+    crate::fs::wrappers::rename(from, to)?;
+    if durability == Durability::Durable {
+        sync_parent_dir(to)?;
+        if from.parent() != to.parent() {
+            sync_parent_dir(from)?;
+        }
+    }
+    Ok(())
+}
+
+/// Sync the directory containing the path, so the changes of its entries (like creating or
+/// renaming a file) are stored.
+///
+/// On Windows, the directory entries are synced together with the files, so this does nothing.
+pub fn sync_parent_dir(path: impl AsRef<Path>) -> Result {
+    let Some(parent) = path.as_ref().parent().filter(|parent| !parent.as_os_str().is_empty())
+    else {
+        return Ok(());
+    };
+    #[cfg(unix)]
+    crate::fs::open(parent)?
+        .sync_all()
+        .with_context(|| format!("Failed to sync the directory {}.", parent.display()))?;
+    #[cfg(not(unix))]
+    let _ = parent;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staged_writes() -> Result {
+        let temp = tempfile::tempdir()?;
+        let path = temp.path().join("nested").join("artifact.json");
+        write_json(&path, &vec![1, 2, 3], Durability::Durable)?;
+        assert_eq!(crate::fs::read_to_string(&path)?, "[1,2,3]");
+
+        // An interrupted write leaves the previous contents and no temporary files.
+        let staged = staging_file(&path)?;
+        crate::fs::write(staged.path(), "[1,")?;
+        drop(staged);
+        assert_eq!(crate::fs::read_to_string(&path)?, "[1,2,3]");
+        assert_eq!(crate::fs::read_dir(path.try_parent()?)?.count(), 1);
+
+        let renamed = temp.path().join("renamed.json");
+        rename(&path, &renamed, Durability::Durable)?;
+        assert!(!path.exists());
+        assert_eq!(crate::fs::read_to_string(&renamed)?, "[1,2,3]");
+        Ok(())
+    }
+}
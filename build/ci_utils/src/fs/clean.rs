@@ -0,0 +1,140 @@
+//! Removal of the build outputs, guarded against removing the paths that must never be removed.
+//!
+//! A misconfigured path (like an empty environment variable resolving to the current directory)
+//! must not make a cleaning step wipe the repository or the user's home. All the functions here
+//! [refuse](ensure_not_protected) to remove the filesystem roots, the home directory, the
+//! repository roots and the current directory (or its ancestors).
+//!
+//! On Windows, removal often fails transiently, because an antivirus or an indexer holds the file
+//! open. Such failures are [retried](RetryPolicy) a few times before giving up.
+
+use crate::prelude::*;
+
+use crate::program::command::retry::RetryPolicy;
+
+use std::time::Duration;
+
+
+
+/// How many times the removal is attempted on Windows before giving up.
+pub const ATTEMPTS: usize = 5;
+
+/// Delay before the first retry of the removal. It is doubled after each failed attempt.
+pub const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Upper bound for the delay between the removal attempts.
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Windows error codes of the failures caused by other processes holding the file.
+const TRANSIENT_WINDOWS_ERRORS: [i32; 3] = [
+    32,  // ERROR_SHARING_VIOLATION
+    33,  // ERROR_LOCK_VIOLATION
+    145, // ERROR_DIR_NOT_EMPTY, when the removed files are still pending deletion.
+];
+
+/// Fail if the path is one of the paths that must never be removed.
+///
+/// The path not existing is not an error, as there is nothing to remove.
+pub fn ensure_not_protected(path: impl AsRef<Path>) -> Result {
+    let path = path.as_ref();
+    let Ok(canonical) = path.canonicalize() else {
+        return Ok(());
+    };
+    let reason = if canonical.parent().is_none() {
+        Some("it is a filesystem root")
+    } else if dirs::home_dir().is_some_and(|home| home.starts_with(&canonical)) {
+        Some("it contains the home directory")
+    } else if crate::env::current_dir().is_ok_and(|current| current.starts_with(&canonical)) {
+        Some("it contains the current directory")
+    } else if canonical.join(".git").exists() {
+        Some("it is a repository root")
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => bail!("Refusing to remove {}, as {reason}.", path.display()),
+        None => Ok(()),
+    }
+}
+
+/// Whether the failed removal should be retried.
+fn is_transient(error: &std::io::Error) -> bool {
+    TARGET_OS == OS::Windows
+        && (error.kind() == std::io::ErrorKind::PermissionDenied
+            || error.raw_os_error().is_some_and(|code| TRANSIENT_WINDOWS_ERRORS.contains(&code)))
+}
+
+/// Remove the file or directory, if it exists.
+///
+/// The path is [checked](ensure_not_protected) first, and the transient failures are retried.
+#[context("Failed to remove {}.", path.as_ref().display())]
+pub fn remove_if_exists(path: impl AsRef<Path>) -> Result {
+    let path = path.as_ref();
+    ensure_not_protected(path)?;
+    let remove = || match path.symlink_metadata() {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(path),
+        Ok(_) => std::fs::remove_file(path),
+        Err(e) => Err(e),
+    };
+    let policy = RetryPolicy::new(ATTEMPTS).with_backoff(INITIAL_RETRY_DELAY, 2.0, MAX_RETRY_DELAY);
+    match policy.run_blocking(remove, is_transient) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Recreate the directory, so it exists and is empty. See [`remove_if_exists`].
+pub fn reset_dir(path: impl AsRef<Path>) -> Result {
+    remove_if_exists(&path)?;
+    crate::fs::create_dir_if_missing(&path)
+}
+
+/// Asynchronous [`remove_if_exists`]. The removal runs on the blocking threads, as it may take a
+/// while for large directories and the retries sleep between the attempts.
+pub async fn remove_if_exists_async(path: impl AsRef<Path>) -> Result {
+    let path = path.as_ref().to_path_buf();
+    tokio::task::spawn_blocking(move || remove_if_exists(path)).await?
+}
+
+/// Asynchronous [`reset_dir`]. See [`remove_if_exists_async`].
+pub async fn reset_dir_async(path: impl AsRef<Path>) -> Result {
+    let path = path.as_ref().to_path_buf();
+    tokio::task::spawn_blocking(move || reset_dir(path)).await?
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protected_paths() -> Result {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path().join("repo");
+        let output = repo.join("dist");
+        crate::fs::write(output.join("artifact.txt"), "artifact")?;
+        crate::fs::create_dir_if_missing(repo.join(".git"))?;
+
+        assert!(remove_if_exists(&repo).is_err());
+        assert!(remove_if_exists("/").is_err());
+        assert!(remove_if_exists(".").is_err());
+        reset_dir(&output)?;
+        assert!(output.is_dir() && crate::fs::read_dir(&output)?.next().is_none());
+        remove_if_exists(&output)?;
+        remove_if_exists(&output)?;
+        assert!(!output.exists() && repo.exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn async_removal() -> Result {
+        let temp = tempfile::tempdir()?;
+        let output = temp.path().join("dist");
+        crate::fs::write(output.join("artifact.txt"), "artifact")?;
+        reset_dir_async(&output).await?;
+        assert!(output.is_dir() && crate::fs::read_dir(&output)?.next().is_none());
+        remove_if_exists_async(&output).await?;
+        assert!(!output.exists());
+        Ok(())
+    }
+}
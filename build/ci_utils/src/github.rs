@@ -10,7 +10,9 @@ use octocrab::models::repos::Release;
 // === Export ===
 // ==============
 
+pub mod conditional;
 pub mod model;
+pub mod rate_limit;
 pub mod release;
 pub mod repo;
 pub mod workflow;
@@ -27,7 +29,7 @@ define_env_var! {
     /// GitHub Personal Access Token, used for authentication in GutHub API.
     ///
     /// Can be [created using GitHub web UI](https://docs.github.com/en/authentication/keeping-your-account-and-data-secure/creating-a-personal-access-token).
-    GITHUB_TOKEN, String;
+    GITHUB_TOKEN, secret String;
 }
 
 /// Tries to retrieve the GitHub Personal Access Token from the environment.
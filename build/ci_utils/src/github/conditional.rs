@@ -0,0 +1,128 @@
+//! Conditional GitHub API requests, using the `ETag` of the previous response.
+//!
+//! GitHub does not count `304 Not Modified` responses against the rate limit of authenticated
+//! users, so repeatedly listing the same releases or assets is nearly free.
+
+use crate::prelude::*;
+
+use crate::github::rate_limit;
+
+use reqwest::header::HeaderMap;
+use reqwest::header::ETAG;
+use reqwest::header::IF_NONE_MATCH;
+use reqwest::header::LINK;
+use reqwest::Client;
+use reqwest::StatusCode;
+use serde_json::Value;
+
+
+
+/// A stored response, together with its `ETag`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Entry {
+    etag: String,
+    body: Value,
+    next: Option<Url>,
+}
+
+/// On-disk store of the GitHub API responses, used to make conditional requests.
+#[derive(Clone, Debug)]
+pub struct ConditionalCache {
+    root: PathBuf,
+}
+
+impl ConditionalCache {
+    /// Store the responses in the given directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Store the responses under the [default cache location](crate::cache::default_path).
+    pub fn new_default() -> Result<Self> {
+        Ok(Self::new(crate::cache::default_path()?.join("github-etags")))
+    }
+
+    /// Get a JSON document, reusing the stored one if it was not modified.
+    pub async fn get_json<T: DeserializeOwned>(&self, client: &Client, url: Url) -> Result<T> {
+        let (body, _) = self.get_page(client, url).await?;
+        Ok(serde_json::from_value(body)?)
+    }
+
+    /// Get all the pages of a paginated JSON list, following the `Link` headers.
+    pub async fn get_all_pages<T: DeserializeOwned>(
+        &self,
+        client: &Client,
+        url: Url,
+    ) -> Result<Vec<T>> {
+        let mut ret = Vec::new();
+        let mut next = Some(url);
+        while let Some(url) = next {
+            let (body, next_url) = self.get_page(client, url).await?;
+            ret.extend(serde_json::from_value::<Vec<T>>(body)?);
+            next = next_url;
+        }
+        Ok(ret)
+    }
+
+    #[context("Failed to get {url}.")]
+    async fn get_page(&self, client: &Client, url: Url) -> Result<(Value, Option<Url>)> {
+        let entry_path = self.entry_path(&url);
+        let stored = crate::fs::read_json::<Entry>(&entry_path).ok();
+        let mut request = client.get(url.clone());
+        if let Some(stored) = &stored {
+            request = request.header(IF_NONE_MATCH, &stored.etag);
+        }
+        let response = rate_limit::send(request).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            let stored = stored.context("Got `304 Not Modified` for a request without ETag.")?;
+            trace!("Not modified since the last request, reusing the stored response.");
+            return Ok((stored.body, stored.next));
+        }
+        let next = next_page_url(response.headers());
+        let etag = response.headers().get(ETAG).and_then(|etag| etag.to_str().ok());
+        let etag = etag.map(ToString::to_string);
+        let body: Value = response.json().await?;
+        if let Some(etag) = etag {
+            let entry = Entry { etag, body: body.clone(), next: next.clone() };
+            let stored = crate::fs::create_parent_dir_if_missing(&entry_path)
+                .and_then(|_| crate::fs::write_json(&entry_path, &entry));
+            if let Err(e) = stored {
+                debug!("Failed to store the response for conditional requests: {e:?}");
+            }
+        }
+        Ok((body, next))
+    }
+
+    fn entry_path(&self, url: &Url) -> PathBuf {
+        use sha2::Digest;
+        let digest = sha2::Sha224::digest(url.as_str().as_bytes());
+        self.root.join(data_encoding::BASE64URL_NOPAD.encode(&digest)).with_extension("json")
+    }
+}
+
+/// Get the URL of the next page from the `Link` header of a paginated response.
+pub fn next_page_url(headers: &HeaderMap) -> Option<Url> {
+    let link = headers.get(LINK)?.to_str().ok()?;
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        let is_next = params.split(';').any(|param| param.trim() == r#"rel="next""#);
+        let url = url.trim().strip_prefix('<')?.strip_suffix('>')?;
+        is_next.then(|| Url::parse(url).ok()).flatten()
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn link_header() {
+        let link = r#"<https://api.github.com/repos/o/r/releases?page=2>; rel="next", <https://api.github.com/repos/o/r/releases?page=5>; rel="last""#;
+        let headers = HeaderMap::from_iter([(LINK, HeaderValue::from_static(link))]);
+        let next = next_page_url(&headers).map(String::from);
+        assert_eq!(next.as_deref(), Some("https://api.github.com/repos/o/r/releases?page=2"));
+        assert_eq!(next_page_url(&HeaderMap::new()), None);
+    }
+}
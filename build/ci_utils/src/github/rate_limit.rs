@@ -0,0 +1,199 @@
+//! Handling of the GitHub API rate limits.
+//!
+//! When the limit is exceeded, GitHub responds with an error that tells when the limit resets.
+//! If the wait is short enough, the request is retried after the reset. Otherwise, the typed
+//! [`RateLimited`] error is returned, so the callers can tell it apart from other failures.
+
+use crate::prelude::*;
+
+use chrono::DateTime;
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::RequestBuilder;
+use reqwest::Response;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+
+
+define_env_var! {
+    /// Maximum time to wait for the rate limit reset before giving up with [`RateLimited`] error.
+    ENSO_BUILD_GITHUB_RATE_LIMIT_MAX_WAIT, Duration;
+}
+
+/// Used when [`ENSO_BUILD_GITHUB_RATE_LIMIT_MAX_WAIT`] is not set.
+pub const DEFAULT_MAX_WAIT: Duration = Duration::from_secs(15 * 60);
+
+/// Margin added to the reset time, to account for clock differences.
+const RESET_MARGIN: Duration = Duration::from_secs(1);
+
+/// The maximum time to wait for the rate limit reset.
+pub fn max_wait() -> Result<Duration> {
+    Ok(ENSO_BUILD_GITHUB_RATE_LIMIT_MAX_WAIT.get_opt()?.unwrap_or(DEFAULT_MAX_WAIT))
+}
+
+
+
+// =====================
+// === RateLimitInfo ===
+// =====================
+
+/// Rate limit information from the GitHub API response headers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// Maximum number of requests in the current window.
+    pub limit:       Option<u64>,
+    /// Number of requests remaining in the current window.
+    pub remaining:   Option<u64>,
+    /// When the current window resets.
+    pub reset:       Option<DateTime<Utc>>,
+    /// How long to wait before retrying, sent when a secondary rate limit is hit.
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimitInfo {
+    /// Read the `x-ratelimit-*` and `retry-after` headers.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let number = |name: &str| -> Option<u64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+        Self {
+            limit:       number("x-ratelimit-limit"),
+            remaining:   number("x-ratelimit-remaining"),
+            reset:       number("x-ratelimit-reset")
+                .and_then(|seconds| DateTime::from_timestamp(seconds.try_into().ok()?, 0)),
+            retry_after: number(reqwest::header::RETRY_AFTER.as_str()).map(Duration::from_secs),
+        }
+    }
+
+    /// Check if the response was rejected because of the exceeded rate limit.
+    pub fn is_exceeded(&self, status: StatusCode) -> bool {
+        let limit_reached = self.remaining == Some(0) || self.retry_after.is_some();
+        status == StatusCode::TOO_MANY_REQUESTS
+            || (status == StatusCode::FORBIDDEN && limit_reached)
+    }
+
+    /// How long to wait, starting at `now`, before the request can be retried.
+    pub fn wait_time(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let until_reset = || (self.reset? - now).to_std().ok();
+        self.retry_after.or_else(until_reset).map(|wait| wait + RESET_MARGIN)
+    }
+}
+
+
+
+// ===================
+// === RateLimited ===
+// ===================
+
+/// Error returned when the GitHub API rate limit is exceeded and the reset is too far away to
+/// wait for it.
+///
+/// Can be detected using [`anyhow::Error::downcast_ref`].
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimited {
+    /// When the rate limit resets, if known.
+    pub reset: Option<DateTime<Utc>>,
+}
+
+impl Display for RateLimited {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GitHub API rate limit exceeded")?;
+        match self.reset {
+            Some(reset) => write!(f, ", it resets at {reset}."),
+            None => write!(f, "."),
+        }
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+
+
+// =================
+// === Functions ===
+// =================
+
+/// Send the request, waiting for the rate limit reset and retrying if necessary.
+///
+/// Error responses, other than exceeded rate limit, are turned into errors.
+pub async fn send(request: RequestBuilder) -> Result<Response> {
+    let max_wait = max_wait()?;
+    loop {
+        let attempt = request.try_clone().context("Request cannot be retried.")?;
+        let response = attempt.send().await?;
+        let info = RateLimitInfo::from_headers(response.headers());
+        if !info.is_exceeded(response.status()) {
+            warn_if_running_low(&info);
+            return crate::io::web::handle_error_response(response).await;
+        }
+        wait_for_reset(&info, max_wait).await?;
+    }
+}
+
+/// Run the octocrab call, waiting for the rate limit reset and retrying if necessary.
+///
+/// Octocrab does not expose the response headers, so the reset time is queried from the API.
+pub async fn retry_octocrab<F, Fut, T>(octocrab: &Octocrab, mut action: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = octocrab::Result<T>>, {
+    let max_wait = max_wait()?;
+    loop {
+        match action().await {
+            Err(octocrab::Error::GitHub { source, .. })
+                if source.message.to_lowercase().contains("rate limit") =>
+            {
+                let rate = octocrab.ratelimit().get().await?;
+                let reset = DateTime::from_timestamp(rate.resources.core.reset.try_into()?, 0);
+                let info = RateLimitInfo { reset, ..default() };
+                wait_for_reset(&info, max_wait).await?;
+            }
+            result => return Ok(result?),
+        }
+    }
+}
+
+/// Sleep until the rate limit resets, or fail with [`RateLimited`] if it would take too long.
+async fn wait_for_reset(info: &RateLimitInfo, max_wait: Duration) -> Result {
+    let rate_limited = RateLimited { reset: info.reset };
+    match info.wait_time(Utc::now()) {
+        Some(wait) if wait <= max_wait => {
+            warn!("{rate_limited} Waiting {wait:?} before retrying.");
+            tokio::time::sleep(wait).await;
+            Ok(())
+        }
+        _ => Err(rate_limited.into()),
+    }
+}
+
+fn warn_if_running_low(info: &RateLimitInfo) {
+    if let (Some(limit), Some(remaining)) = (info.limit, info.remaining) {
+        if remaining * 10 < limit {
+            warn!("GitHub API rate limit is running low: {remaining}/{limit} requests remaining.");
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn parse_headers() {
+        let headers = HeaderMap::from_iter([
+            ("x-ratelimit-limit".try_into().unwrap(), HeaderValue::from_static("5000")),
+            ("x-ratelimit-remaining".try_into().unwrap(), HeaderValue::from_static("0")),
+            ("x-ratelimit-reset".try_into().unwrap(), HeaderValue::from_static("1700000060")),
+        ]);
+        let info = RateLimitInfo::from_headers(&headers);
+        assert_eq!(info.limit, Some(5000));
+        assert!(info.is_exceeded(StatusCode::FORBIDDEN));
+        assert!(!info.is_exceeded(StatusCode::NOT_FOUND));
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(info.wait_time(now), Some(Duration::from_secs(61)));
+
+        let error = anyhow::Error::from(RateLimited { reset: info.reset });
+        assert!(error.downcast_ref::<RateLimited>().is_some());
+    }
+}
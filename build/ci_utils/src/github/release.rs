@@ -201,10 +201,46 @@ pub trait IsReleaseExt: IsRelease + Sync {
         .context(error_msg)
     }
 
+    /// Upload a new asset to the release from a given file, along with its checksum manifest
+    /// fragment.
+    ///
+    /// The checksum is computed from the local file. The fragments are merged into the release's
+    /// checksum manifest when it is published, see [`crate::checksums`].
+    async fn upload_asset_file_with_checksum_as(
+        &self,
+        path: impl AsRef<Path> + Send,
+        asset_filename: impl AsRef<Path> + Send,
+    ) -> Result<Asset> {
+        let path = path.as_ref();
+        let asset = self.upload_asset_file_as(path, asset_filename).await?;
+        let mut fragment = crate::checksums::Manifest::new();
+        fragment.add(&asset.name, crate::checksums::sha256_file(path)?)?;
+        let temp_dir = tempfile::tempdir()?;
+        let fragment_path = temp_dir.path().join(crate::checksums::fragment_name(&asset.name));
+        crate::fs::write(&fragment_path, fragment.to_string())?;
+        self.upload_asset_file(&fragment_path).await?;
+        Ok(asset)
+    }
+
+    /// Remove the asset from the release.
+    #[instrument(skip_all, fields(asset = %asset.name), err)]
+    async fn delete_asset(&self, asset: &Asset) -> Result {
+        let repo = self.repo();
+        let url = self.octocrab().absolute_url(format!(
+            "/repos/{}/{}/releases/assets/{}",
+            repo.owner(),
+            repo.name(),
+            asset.id
+        ))?;
+        let response = self.octocrab()._delete(url, None::<&()>).await?;
+        crate::io::web::handle_error_response(response).await?;
+        Ok(())
+    }
+
     /// Upload given directory as a release asset.
     ///
     /// The given filename will be used, with appended [platform-specific
-    /// extension](ARCHIVE_EXTENSION).
+    /// extension](ARCHIVE_EXTENSION). The archive's checksum manifest fragment is uploaded as well.
     async fn upload_compressed_dir_as(
         &self,
         dir_to_upload: impl AsRef<Path> + Send,
@@ -215,7 +251,8 @@ pub trait IsReleaseExt: IsRelease + Sync {
         let archive_path =
             custom_name.with_parent(temp_dir.path()).with_appended_extension(archive_extension());
         crate::archive::create(&archive_path, [&dir_to_upload]).await?;
-        self.upload_asset_file(archive_path).await
+        let asset_filename = archive_path.try_file_name()?.to_owned();
+        self.upload_asset_file_with_checksum_as(&archive_path, asset_filename).await
     }
 
     /// Upload given directory as a release asset.
@@ -228,12 +265,11 @@ pub trait IsReleaseExt: IsRelease + Sync {
 
     /// Get the information about the release.
     async fn get(&self) -> Result<Release> {
-        Ok(self
-            .octocrab()
-            .repos(self.repo().owner(), self.repo().name())
-            .releases()
-            .get_by_id(self.id())
-            .await?)
+        let repo = self.repo();
+        let get = || async {
+            self.octocrab().repos(repo.owner(), repo.name()).releases().get_by_id(self.id()).await
+        };
+        crate::github::rate_limit::retry_octocrab(self.octocrab(), get).await
     }
 
     async fn publish(&self) -> Result<Release> {
@@ -213,13 +213,17 @@ impl<R: IsRepo> Handle<R> {
     ///
     /// While this behavior is not documented anywhere, it seems that the GitHub API returns the
     /// releases in the reverse chronological order (i.e. the newest release first).
+    ///
+    /// Conditional requests are used, so listing unchanged releases does not count against the
+    /// rate limit.
     pub async fn all_releases(&self) -> Result<Vec<Release>> {
-        github::get_all(
-            &self.octocrab,
-            self.repos().releases().list().per_page(MAX_PER_PAGE).send(),
-        )
-        .await
-        .with_context(|| format!("Failed to list all releases in the {self} repository."))
+        let path = format!("/repos/{}/{}/releases", self.owner(), self.name());
+        let mut url = self.octocrab.absolute_url(path)?;
+        url.query_pairs_mut().append_pair("per_page", &MAX_PER_PAGE.to_string());
+        github::conditional::ConditionalCache::new_default()?
+            .get_all_pages(&self.octocrab.client, url)
+            .await
+            .with_context(|| format!("Failed to list all releases in the {self} repository."))
     }
 
     /// Get the latest release of this repository.
@@ -228,19 +232,18 @@ impl<R: IsRepo> Handle<R> {
     /// `created_at` attribute. The `created_at` attribute is the date of the commit used for the
     /// release, and not the date when the release was drafted or published.
     pub async fn latest_release(&self) -> Result<Release> {
-        self.repos()
-            .releases()
-            .get_latest()
+        let get_latest = || async { self.repos().releases().get_latest().await };
+        github::rate_limit::retry_octocrab(&self.octocrab, get_latest)
             .await
             .with_context(|| format!("Failed to get the latest release in the {self} repository."))
     }
 
     /// Get the information about release with the given id.
     pub async fn find_release_by_id(&self, release_id: ReleaseId) -> Result<Release> {
-        let repo_handler = self.repos();
-        let releases_handler = repo_handler.releases();
-        releases_handler
-            .get_by_id(release_id)
+        let path = format!("/repos/{}/{}/releases/{release_id}", self.owner(), self.name());
+        let url = self.octocrab.absolute_url(path)?;
+        github::conditional::ConditionalCache::new_default()?
+            .get_json(&self.octocrab.client, url)
             .await
             .with_context(|| format!("Failed to find release by id `{release_id}` in `{self}`."))
     }
@@ -349,6 +352,7 @@ impl<R: IsRepo> Handle<R> {
                     reqwest::header::ACCEPT,
                     HeaderValue::from_static(mime::APPLICATION_OCTET_STREAM.as_ref()),
                 )]),
+                sha256: None,
             },
         }
     }
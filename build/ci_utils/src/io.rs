@@ -75,31 +75,13 @@ pub async fn download_and_extract(
 }
 
 /// Retry a given action until it succeeds or the maximum number of attempts is reached.
-pub async fn retry<Fn, Fut, Ret>(mut action: Fn) -> Result<Ret>
+///
+/// Uses the [default retry policy](crate::program::command::retry::RetryPolicy::default).
+pub async fn retry<Fn, Fut, Ret>(action: Fn) -> Result<Ret>
 where
     Fn: FnMut() -> Fut,
     Fut: Future<Output = Result<Ret>>, {
-    let growth_factor = 1.5;
-    let mut attempts = 5;
-    let mut delay = std::time::Duration::from_millis(500);
-    let max_delay = std::time::Duration::from_secs(10);
-
-    loop {
-        match action().await {
-            Ok(result) => return Ok(result),
-            Err(err) => {
-                let warning = format!("Failed to execute action: {err:?}");
-                crate::actions::workflow::warn(&warning);
-                warn!("{warning}");
-                if attempts == 0 {
-                    return Err(err);
-                }
-                attempts -= 1;
-                tokio::time::sleep(delay).await;
-                delay = delay.mul_f32(growth_factor).min(max_delay);
-            }
-        }
-    }
+    crate::program::command::retry::RetryPolicy::default().run(action).await
 }
 
 
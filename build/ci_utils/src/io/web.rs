@@ -1,5 +1,6 @@
 use crate::prelude::*;
 
+use crate::fs::atomic::Durability;
 use crate::fs::tokio::copy_to_file;
 use crate::fs::tokio::create_parent_dir_if_missing;
 use crate::global::progress_bar;
@@ -115,22 +116,20 @@ pub fn filename_from_response(response: &Response) -> Result<&Path> {
     filename_from_content_disposition(disposition)
 }
 
+/// Stream the download to the file.
+///
+/// The file appears only once the download is complete, so an interrupted download does not
+/// leave a truncated file behind.
 pub async fn stream_to_file(
     stream: impl Stream<Item = reqwest::Result<Bytes>>,
     output_path: impl AsRef<Path>,
 ) -> Result {
     debug!("Streaming download to file {}. ", output_path.as_ref().display());
     create_parent_dir_if_missing(&output_path).await?;
-    let output = tokio::fs::OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&output_path)
-        .await
-        .with_context(|| {
-            format!("Failed to open file for writing: {}", output_path.as_ref().display())
-        })?;
-    stream
+    let staged = crate::fs::atomic::staging_file(&output_path)?;
+    let (output, staged) = staged.into_parts();
+    let output = tokio::fs::File::from_std(output);
+    let mut output = stream
         .map_err(anyhow::Error::from)
         // We must use fold (rather than foreach) to properly keep `output` alive long enough.
         .try_fold(output, |mut output, chunk| async move {
@@ -138,7 +137,9 @@ pub async fn stream_to_file(
             Ok(output)
         })
         .await?;
-    Ok(())
+    output.flush().await?;
+    let staged = tempfile::NamedTempFile::from_parts(output.into_std().await, staged);
+    crate::fs::atomic::persist(staged, output_path, Durability::Atomic)
 }
 
 #[cfg(test)]
@@ -8,12 +8,16 @@
 
 pub mod actions;
 pub mod archive;
+pub mod artifact_store;
 pub mod cache;
+pub mod checksums;
 pub mod ci;
 pub mod convert_case;
+pub mod dag;
 pub mod deploy;
 pub mod env;
 pub mod extensions;
+pub mod fingerprint;
 pub mod fmt;
 pub mod fs;
 pub mod future;
@@ -26,11 +30,14 @@ pub mod os;
 pub mod packaging;
 pub mod path;
 pub mod platform;
+pub mod prerequisites;
 pub mod process;
 pub mod program;
 pub mod programs;
+pub mod release_notes;
 pub mod reqwest;
 pub mod serde;
+pub mod timing;
 
 
 
@@ -4,6 +4,8 @@ use tracing_subscriber::prelude::*;
 use crate::global;
 
 use std::io;
+use std::sync::LazyLock;
+use std::sync::RwLock;
 use tracing::subscriber::Interest;
 use tracing::Metadata;
 use tracing::Subscriber;
@@ -48,7 +50,7 @@ where S: Subscriber + for<'a> LookupSpan<'a> + Debug + Send + Sync + 'static {
         .without_time()
         .with_ansi(false)
         .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
-        .with_writer(progress_bar_writer)
+        .with_writer(MaskingWriter(progress_bar_writer))
         .with_filter(filter)
 }
 
@@ -57,7 +59,7 @@ where S: Subscriber + for<'a> LookupSpan<'a> + Debug + Send + Sync + 'static {
     tracing_subscriber::fmt::layer()
         .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
         .with_thread_names(true)
-        .with_writer(file)
+        .with_writer(MaskingWriter(file))
 }
 
 /// Install global `tracing` subscriber that logs to stderr.
@@ -117,3 +119,79 @@ impl tracing_subscriber::fmt::MakeWriter<'_> for IndicatifWriter {
         self.clone()
     }
 }
+
+
+
+// ===============
+// === Secrets ===
+// ===============
+
+/// Text that replaces the secret values in the logs.
+pub const MASK: &str = "***";
+
+/// Values that must not appear in the logs.
+static SECRETS: LazyLock<RwLock<BTreeSet<String>>> = LazyLock::new(default);
+
+/// Register a value that must not appear in the logs.
+///
+/// The value is masked in all the logs written through the layers defined in this module. When
+/// running in GitHub Actions, the value is also masked in the workflow log. Each line of a
+/// multi-line value is masked separately, as this is how the GitHub Actions masking works.
+pub fn register_secret(value: impl AsRef<str>) {
+    for line in value.as_ref().lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let is_new = SECRETS.write().unwrap().insert(line.to_string());
+        if is_new {
+            crate::actions::workflow::mask_value(line);
+        }
+    }
+}
+
+/// Replace all the registered secret values in the given text with [`MASK`].
+pub fn mask_secrets(text: &str) -> Cow<str> {
+    let secrets = SECRETS.read().unwrap();
+    let mut present = secrets.iter().filter(|secret| text.contains(secret.as_str())).collect_vec();
+    if present.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    // Longer secrets go first, so a secret being a part of another one does not leave the rest
+    // of the longer one visible.
+    present.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+    let mut masked = text.to_string();
+    for secret in present {
+        masked = masked.replace(secret.as_str(), MASK);
+    }
+    Cow::Owned(masked)
+}
+
+/// A writer that masks the [registered secrets](register_secret) before passing the text on.
+///
+/// Can be used both as a [`io::Write`] and as a [`MakeWriter`](tracing_subscriber::fmt::MakeWriter)
+/// of masking writers.
+#[derive(Clone, Debug)]
+struct MaskingWriter<W>(W);
+
+impl<W: io::Write> io::Write for MaskingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match std::str::from_utf8(buf).map(mask_secrets) {
+            Ok(Cow::Owned(masked)) => {
+                self.0.write_all(masked.as_bytes())?;
+                Ok(buf.len())
+            }
+            _ => self.0.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<'a, W: tracing_subscriber::fmt::MakeWriter<'a>> tracing_subscriber::fmt::MakeWriter<'a>
+    for MaskingWriter<W>
+{
+    type Writer = MaskingWriter<W::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        MaskingWriter(self.0.make_writer())
+    }
+}
@@ -0,0 +1,269 @@
+//! Declarative description of the tools required by the build, and their up-front verification.
+//!
+//! All the [requirements](Requirement) are checked together and the results are gathered into a
+//! single [`Report`]. For each unfulfilled requirement, the report includes a hint on how to
+//! install the tool on the current platform, so the build does not fail midway with a cryptic
+//! "command not found" error.
+
+use crate::prelude::*;
+
+use crate::program;
+use semver::VersionReq;
+
+
+
+/// Retrieves the version of an installed tool.
+pub type VersionGetter = Arc<dyn Fn() -> BoxFuture<'static, Result<Version>> + Send + Sync>;
+
+
+
+// ===================
+// === Requirement ===
+// ===================
+
+/// A tool required by the build.
+#[derive(Clone)]
+#[derive_where(Debug)]
+pub struct Requirement {
+    /// Name of the tool, as presented to the user.
+    pub name:           String,
+    /// Executable that must be found in `PATH`, if any.
+    pub executable:     Option<String>,
+    /// Required version. If not set, it is enough for the tool to be present.
+    pub version:        Option<VersionReq>,
+    #[derive_where(skip)]
+    pub version_getter: VersionGetter,
+    /// Installation hints. The hints with `None` platform apply to all the platforms.
+    pub hints:          Vec<(Option<OS>, String)>,
+}
+
+impl Requirement {
+    /// Require the executable of the given name. Its version is read from `--version` output.
+    pub fn new(executable: impl Into<String>) -> Self {
+        let executable = executable.into();
+        let program = program::Unknown(executable.clone());
+        let version_getter: VersionGetter = Arc::new(move || {
+            let program = program.clone();
+            async move { program.version().await }.boxed()
+        });
+        Self {
+            name: executable.clone(),
+            executable: Some(executable),
+            version: None,
+            version_getter,
+            hints: default(),
+        }
+    }
+
+    /// Require the tool to be present in a version matching the given requirement.
+    pub fn with_version(mut self, version: VersionReq) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Use a custom way of retrieving the tool version.
+    ///
+    /// If the getter is able to find the tool on its own, [`Self::without_executable_lookup`]
+    /// should be used as well.
+    pub fn with_version_getter<F, Fut>(mut self, getter: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Version>> + Send + 'static, {
+        self.version_getter = Arc::new(move || getter().boxed());
+        self
+    }
+
+    /// Do not require the executable to be present in `PATH`.
+    pub fn without_executable_lookup(mut self) -> Self {
+        self.executable = None;
+        self
+    }
+
+    /// Add an installation hint that applies to all the platforms.
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hints.push((None, hint.into()));
+        self
+    }
+
+    /// Add an installation hint for the given platform. It takes precedence over the generic hint.
+    pub fn with_hint_for(mut self, os: OS, hint: impl Into<String>) -> Self {
+        self.hints.push((Some(os), hint.into()));
+        self
+    }
+
+    /// The installation hint for the given platform.
+    pub fn hint(&self, os: OS) -> Option<&str> {
+        let for_os = self.hints.iter().find(|(hint_os, _)| *hint_os == Some(os));
+        let generic = || self.hints.iter().find(|(hint_os, _)| hint_os.is_none());
+        for_os.or_else(generic).map(|(_, hint)| hint.as_str())
+    }
+
+    /// Check if the requirement is fulfilled in the current environment.
+    pub async fn check(&self) -> Outcome {
+        let status = self.status().await;
+        Outcome {
+            name: self.name.clone(),
+            required: self.version.clone(),
+            status,
+            hint: self.hint(TARGET_OS).map(ToString::to_string),
+        }
+    }
+
+    async fn status(&self) -> Status {
+        if let Some(executable) = &self.executable {
+            if program::lookup(executable).is_err() {
+                return Status::Missing;
+            }
+        }
+        let Some(required) = &self.version else {
+            return Status::Satisfied { found: None };
+        };
+        match (self.version_getter)().await {
+            Ok(found) if required.matches(&found) => Status::Satisfied { found: Some(found) },
+            Ok(found) => Status::WrongVersion { found },
+            Err(error) => Status::Failed { error: format!("{error:#}") },
+        }
+    }
+}
+
+
+
+// ===============
+// === Outcome ===
+// ===============
+
+/// The result of checking a single requirement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The tool is present in a matching version (if a version was required).
+    Satisfied { found: Option<Version> },
+    /// The tool could not be found.
+    Missing,
+    /// The tool is present, but in a version not matching the requirement.
+    WrongVersion { found: Version },
+    /// The tool's version could not be determined.
+    Failed { error: String },
+}
+
+/// The result of checking a single requirement, together with the information for the user.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    /// Name of the tool.
+    pub name:     String,
+    /// Required version, if any.
+    pub required: Option<VersionReq>,
+    /// The result of the check.
+    pub status:   Status,
+    /// The installation hint for the current platform.
+    pub hint:     Option<String>,
+}
+
+impl Outcome {
+    /// Whether the requirement is fulfilled.
+    pub fn is_satisfied(&self) -> bool {
+        matches!(self.status, Status::Satisfied { .. })
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = &self.name;
+        let required = match &self.required {
+            Some(required) => format!(" (required {required})"),
+            None => String::new(),
+        };
+        match &self.status {
+            Status::Satisfied { found: Some(found) } => write!(f, "[ok] {name} {found}{required}"),
+            Status::Satisfied { found: None } => write!(f, "[ok] {name}"),
+            Status::Missing => write!(f, "[missing] {name}: not found{required}"),
+            Status::WrongVersion { found } =>
+                write!(f, "[wrong version] {name}: found {found}{required}"),
+            Status::Failed { error } =>
+                write!(f, "[error] {name}: failed to determine the version{required}: {error}"),
+        }?;
+        match &self.hint {
+            Some(hint) if !self.is_satisfied() => write!(f, "\n    Hint: {hint}"),
+            _ => Ok(()),
+        }
+    }
+}
+
+
+
+// ==============
+// === Report ===
+// ==============
+
+/// The results of checking all the requirements.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    /// Whether all the requirements are fulfilled.
+    pub fn is_satisfied(&self) -> bool {
+        self.outcomes.iter().all(Outcome::is_satisfied)
+    }
+
+    /// The outcomes of the unfulfilled requirements.
+    pub fn failures(&self) -> impl Iterator<Item = &Outcome> {
+        self.outcomes.iter().filter(|outcome| !outcome.is_satisfied())
+    }
+
+    /// Fail with an error listing all the unfulfilled requirements, if there are any.
+    pub fn ensure_satisfied(&self) -> Result {
+        if !self.is_satisfied() {
+            let failures = self.failures().join("\n");
+            bail!("Some required programs are not available or have wrong versions:\n{failures}");
+        }
+        Ok(())
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for outcome in &self.outcomes {
+            writeln!(f, "{outcome}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check all the requirements concurrently.
+pub async fn check(requirements: impl IntoIterator<Item = &Requirement>) -> Report {
+    let checks = requirements.into_iter().map(Requirement::check);
+    let mut outcomes = futures::future::join_all(checks).await;
+    outcomes.sort_by(|a, b| a.name.cmp(&b.name));
+    Report { outcomes }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn aggregated_report() -> Result {
+        let missing = Requirement::new("surely-not-an-installed-program")
+            .with_hint("Install it from the web.")
+            .with_hint_for(TARGET_OS, "Install it using the package manager.");
+        let outdated = Requirement::new("outdated")
+            .without_executable_lookup()
+            .with_version(VersionReq::parse("^2.0")?)
+            .with_version_getter(|| ready(Version::parse("1.5.0").map_err(Into::into)))
+            .with_hint("Update it.");
+        let fine = Requirement::new("fine")
+            .without_executable_lookup()
+            .with_version(VersionReq::parse("^1.2")?)
+            .with_version_getter(|| ready(Version::parse("1.5.0").map_err(Into::into)));
+
+        let report = check([&missing, &outdated, &fine]).await;
+        assert_eq!(report.failures().count(), 2);
+        let error = report.ensure_satisfied().unwrap_err().to_string();
+        assert!(error.contains("Hint: Install it using the package manager."));
+        assert!(error.contains("[wrong version] outdated: found 1.5.0 (required ^2.0)"));
+        assert!(!error.contains("fine"));
+        Ok(())
+    }
+}
@@ -8,6 +8,7 @@ use sysinfo::Pid;
 // ==============
 
 pub mod hierarchy;
+pub mod tree;
 
 
 
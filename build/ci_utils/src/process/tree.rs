@@ -0,0 +1,175 @@
+//! Termination of a whole process tree, i.e. a spawned process together with its descendants.
+//!
+//! Killing only the spawned process is not enough, as its children (e.g. the JVM spawned by a
+//! wrapper script) would keep running and hold the output pipes open. Therefore, the spawned
+//! process is made the root of a tree that can be terminated as a whole:
+//! * on Unix, the process is placed in a new process group, and the group is signalled;
+//! * on Windows, the process is assigned to a job object, and the job is terminated.
+//!
+//! As a fallback for processes that escaped the group or job, the descendants found through the
+//! [process hierarchy](crate::process::hierarchy) are killed as well.
+
+use crate::prelude::*;
+
+use tokio::process::Child;
+
+
+
+/// A spawned process and all its descendants.
+#[derive(Debug)]
+pub struct ProcessTree {
+    /// PID of the root process.
+    pid: u32,
+    #[cfg(windows)]
+    job: windows_job::JobObject,
+}
+
+impl ProcessTree {
+    /// Configure the command, so the process it spawns can be tracked as a tree.
+    ///
+    /// Must be called before the process is spawned. Note that on Unix this means the process
+    /// will not receive signals sent to the parent's process group, like the terminal's `Ctrl+C`.
+    pub fn prepare(command: &mut tokio::process::Command) {
+        #[cfg(unix)]
+        command.process_group(0);
+        #[cfg(not(unix))]
+        let _ = command;
+    }
+
+    /// Track the tree rooted in the spawned child, which must have been [prepared](Self::prepare).
+    pub fn new(child: &Child) -> Result<Self> {
+        let pid = child.id().context("The process has already exited.")?;
+        #[cfg(windows)]
+        let job = {
+            let handle = child.raw_handle().context("The process has already exited.")?;
+            let job = windows_job::JobObject::new()?;
+            job.assign(handle)?;
+            job
+        };
+        Ok(Self {
+            pid,
+            #[cfg(windows)]
+            job,
+        })
+    }
+
+    /// PID of the root process.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Forcefully terminate all processes in the tree.
+    ///
+    /// Failures are logged, so as many processes as possible are killed.
+    #[instrument]
+    pub fn kill(&self) {
+        // The descendants must be collected before the root dies, as its children would then be
+        // reparented and lost.
+        let descendants = self.descendants();
+
+        #[cfg(unix)]
+        {
+            use nix::sys::signal::killpg;
+            use nix::sys::signal::Signal;
+            use nix::unistd::Pid;
+            match i32::try_from(self.pid) {
+                Ok(pgid) =>
+                    if let Err(e) = killpg(Pid::from_raw(pgid), Signal::SIGKILL) {
+                        warn!("Failed to kill the process group {pgid}: {e}");
+                    },
+                Err(e) => warn!("Invalid process group ID {}: {e}", self.pid),
+            }
+        }
+        #[cfg(windows)]
+        if let Err(e) = self.job.terminate() {
+            warn!("Failed to terminate the job object: {e:?}");
+        }
+
+        // Kill the processes that escaped the group or job, e.g. by starting a new session.
+        let mut system = sysinfo::System::new();
+        for pid in descendants {
+            if system.refresh_process(pid) {
+                if let Some(process) = system.process(pid) {
+                    debug!(%pid, name = %process.name(), "Killing process outside of the tree.");
+                    if !process.kill() {
+                        warn!(%pid, name = %process.name(), "Failed to kill process.");
+                    }
+                }
+            }
+        }
+    }
+
+    /// PIDs of the processes descending from the root, including the root itself.
+    fn descendants(&self) -> Vec<sysinfo::Pid> {
+        let mut system = sysinfo::System::new();
+        let hierarchy = crate::process::hierarchy::Hierarchy::new(&mut system);
+        let mut ret = vec![sysinfo::Pid::from_u32(self.pid)];
+        let mut index = 0;
+        while let Some(&pid) = ret.get(index) {
+            ret.extend(hierarchy.children.get(&pid).into_iter().flatten().copied());
+            index += 1;
+        }
+        ret
+    }
+}
+
+
+// === Windows job objects ===
+
+#[cfg(windows)]
+mod windows_job {
+    use crate::prelude::*;
+
+    use std::os::windows::io::RawHandle;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::JobObjects::AssignProcessToJobObject;
+    use windows::Win32::System::JobObjects::CreateJobObjectW;
+    use windows::Win32::System::JobObjects::TerminateJobObject;
+
+    /// Exit code of the processes terminated through the job object.
+    const TERMINATED_EXIT_CODE: u32 = 1;
+
+    /// Owned handle to an anonymous job object.
+    #[derive(Debug)]
+    pub struct JobObject {
+        handle: HANDLE,
+    }
+
+    // The handle is just an identifier of the kernel object, so it can be used from any thread.
+    unsafe impl Send for JobObject {}
+    unsafe impl Sync for JobObject {}
+
+    impl JobObject {
+        pub fn new() -> Result<Self> {
+            // SAFETY: Creating an anonymous job object with default security attributes.
+            let handle = unsafe { CreateJobObjectW(None, PCWSTR::null()) }
+                .context("Failed to create a job object.")?;
+            Ok(Self { handle })
+        }
+
+        /// Assign the process to the job. Its children spawned from now on will belong to the job.
+        pub fn assign(&self, process: RawHandle) -> Result {
+            // SAFETY: Both handles are valid for the duration of the call.
+            unsafe { AssignProcessToJobObject(self.handle, HANDLE(process as isize)) }
+                .context("Failed to assign the process to the job object.")
+        }
+
+        /// Terminate all processes belonging to the job.
+        pub fn terminate(&self) -> Result {
+            // SAFETY: The handle is valid until the job object is dropped.
+            unsafe { TerminateJobObject(self.handle, TERMINATED_EXIT_CODE) }
+                .context("Failed to terminate the job object.")
+        }
+    }
+
+    impl Drop for JobObject {
+        fn drop(&mut self) {
+            // SAFETY: The handle is owned by this object and is not used after being closed.
+            if let Err(e) = unsafe { CloseHandle(self.handle) } {
+                warn!("Failed to close the job object handle: {e}");
+            }
+        }
+    }
+}
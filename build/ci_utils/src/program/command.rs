@@ -1,11 +1,18 @@
 use crate::prelude::*;
 
 use crate::env::accessor::TypedVariable;
+use crate::process::tree::ProcessTree;
+use crate::program::command::log::CommandLog;
+use crate::program::command::log::Failed;
+use crate::program::command::retry::RetryPolicy;
+use crate::program::command::timeout::TimedOut;
 
 use anyhow::Context;
+use std::collections::VecDeque;
 use std::process::ExitStatus;
 use std::process::Output;
 use std::process::Stdio;
+use std::time::Duration;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncRead;
 use tokio::io::BufReader;
@@ -18,7 +25,10 @@ use tracing::field;
 // === Export ===
 // ==============
 
+pub mod log;
 pub mod provider;
+pub mod retry;
+pub mod timeout;
 
 
 
@@ -318,24 +328,39 @@ impl Command {
         }
     }
 
+    /// Spawn the command, with its output logged and recorded in a [`CommandLog`].
     pub fn spawn_intercepting(&mut self) -> Result<Child> {
+        let (child, _log, _processors) = self.spawn_logged()?;
+        Ok(child)
+    }
+
+    /// Spawn the command, with its output logged and recorded in a [`CommandLog`].
+    ///
+    /// Returns the log (if it could be created) and the tasks processing the output.
+    fn spawn_logged(&mut self) -> Result<(Child, Option<CommandLog>, [JoinHandle<Result>; 2])> {
         self.stdout(Stdio::piped());
         self.stderr(Stdio::piped());
-
-        let program = self.pretty_name.clone().unwrap_or_else(|| {
-            let program = self.inner.as_std().get_program();
-            let program = Path::new(program).file_stem().unwrap_or_default().to_os_string();
-            program.to_string_lossy().to_string()
-        });
-
+        let program = self.program_name();
+        let log = self.create_log();
         let mut child = self.spawn()?;
+        let stdout = child.stdout.take().context("Failed to capture standard output.")?;
+        let stderr = child.stderr.take().context("Failed to capture standard error.")?;
+        let processors = [
+            spawn_log_processor(format!("{program} ℹ️"), stdout, log.clone()),
+            spawn_log_processor(format!("{program} ⚠️"), stderr, log.clone()),
+        ];
+        Ok((child, log, processors))
+    }
 
-        // FIXME unwraps
-        spawn_log_processor(format!("{program} ℹ️"), child.stdout.take().unwrap());
-        spawn_log_processor(format!("{program} ⚠️"), child.stderr.take().unwrap());
-        Ok(child)
+    /// Create the log file for this command. Failure to do so is not fatal, just reported.
+    fn create_log(&self) -> Option<CommandLog> {
+        let program = self.program_name();
+        CommandLog::create(&program, &self.describe())
+            .inspect_err(|e| warn!("Failed to create the log file for {program}: {e:?}"))
+            .ok()
     }
 
+    /// Run the command, failing if it does not exit successfully.
     pub fn run_ok(&mut self) -> BoxFuture<'static, Result<()>> {
         let pretty = self.describe();
         let span = info_span!(
@@ -345,22 +370,169 @@ impl Command {
             command = field::Empty,
         )
         .entered();
-        let child = self.spawn_intercepting();
+        let child = self.spawn_logged();
         let status_checker = self.status_checker.clone();
         async move {
-            let mut child = child?;
+            let (mut child, log, processors) = child?;
             let status = child
                 .wait()
                 .inspect_ok(|exit_status| {
                     tracing::Span::current().record("status", exit_status.code());
                 })
                 .await?;
-            status_checker(status).context(format!("Command failed: {pretty}"))
+            if let Err(error) = status_checker(status) {
+                log::drain(processors).await;
+                return Err(error.context(Failed::new(pretty, log.as_ref())));
+            }
+            Ok(())
         }
         .instrument(span.exit())
         .boxed()
     }
 
+    /// Run the command with the timeout configured by
+    /// [`ENSO_BUILD_COMMAND_TIMEOUT`](timeout::ENSO_BUILD_COMMAND_TIMEOUT), if it is set.
+    ///
+    /// Meant for the commands that are prone to hanging, like test runs. Without the timeout
+    /// configured, this is the same as [`Self::run_ok`].
+    pub fn run_ok_with_configured_timeout(&mut self) -> BoxFuture<'static, Result> {
+        match timeout::ENSO_BUILD_COMMAND_TIMEOUT.get_opt() {
+            Ok(Some(timeout)) => self.run_ok_with_timeout(timeout),
+            Ok(None) => self.run_ok(),
+            Err(e) => ready(Err(e)).boxed(),
+        }
+    }
+
+    /// Run the command, killing it together with all its descendants if it does not complete
+    /// within the timeout.
+    ///
+    /// On timeout, fails with the [`TimedOut`] error carrying the last lines of the output. Note
+    /// that on Unix the command is run in a new process group, so it does not receive the signals
+    /// sent to the terminal's process group, like `Ctrl+C`.
+    pub fn run_ok_with_timeout(&mut self, timeout: Duration) -> BoxFuture<'static, Result> {
+        let pretty = self.describe();
+        let span = info_span!(
+            "Running process with timeout.",
+            ?timeout,
+            status = field::Empty,
+            pid = field::Empty,
+            command = field::Empty,
+        )
+        .entered();
+        ProcessTree::prepare(self.borrow_mut_command());
+        self.stdout(Stdio::piped());
+        self.stderr(Stdio::piped());
+        let program = self.program_name();
+        let log = self.create_log();
+        let child = self.spawn();
+        span.record("command", field::display(&pretty));
+        if let Ok(child) = &child {
+            span.record("pid", child.id());
+        }
+        let status_checker = self.status_checker.clone();
+        async move {
+            let mut child = child?;
+            let tree = ProcessTree::new(&child)?;
+            let stdout = child.stdout.take().context("Failed to capture standard output.")?;
+            let stderr = child.stderr.take().context("Failed to capture standard error.")?;
+            let lines = timeout::OUTPUT_TAIL_LINES;
+            let stdout =
+                spawn_tail_capturing_log_processor(format!("{program} ℹ️"), stdout, &log, lines);
+            let stderr =
+                spawn_tail_capturing_log_processor(format!("{program} ⚠️"), stderr, &log, lines);
+            match tokio::time::timeout(timeout, child.wait()).await {
+                Ok(status) => {
+                    let status = status?;
+                    tracing::Span::current().record("status", status.code());
+                    match status_checker(status) {
+                        Ok(()) => Ok(()),
+                        Err(error) => {
+                            log::drain([stdout, stderr]).await;
+                            Err(error.context(Failed::new(pretty, log.as_ref())))
+                        }
+                    }
+                }
+                Err(_) => {
+                    warn!("Command timed out after {timeout:?}, killing it: {pretty}");
+                    tree.kill();
+                    // The root is also killed directly, in case killing its group or job failed.
+                    // Otherwise, waiting for it could hang forever.
+                    if let Err(e) = child.start_kill() {
+                        debug!("Failed to kill the process directly: {e}");
+                    }
+                    child.wait().await?;
+                    let drain = |output: JoinHandle<Result<String>>| async move {
+                        let output = tokio::time::timeout(timeout::OUTPUT_DRAIN_TIMEOUT, output);
+                        output.await.ok()?.ok()?.ok()
+                    };
+                    let (stdout, stderr) = futures::join!(drain(stdout), drain(stderr));
+                    Err(TimedOut { command: pretty, timeout, stdout, stderr }.into())
+                }
+            }
+        }
+        .instrument(span.exit())
+        .boxed()
+    }
+
+    /// Run the command, retrying the failed attempts according to the policy.
+    ///
+    /// The standard error output of each attempt is captured (while still being logged), so the
+    /// policy can decide whether the failure is worth retrying.
+    pub fn run_ok_with_retries(mut self, policy: RetryPolicy) -> BoxFuture<'static, Result> {
+        let pretty = self.describe();
+        async move {
+            let mut attempt = 1;
+            loop {
+                let (status, stderr, log) = self.run_capturing_stderr().await?;
+                let error = match (self.status_checker)(status) {
+                    Ok(()) => return Ok(()),
+                    Err(error) => error,
+                };
+                if attempt >= policy.max_attempts || !policy.should_retry(status, &stderr) {
+                    let command = format!("{pretty} (attempt {attempt}/{})", policy.max_attempts);
+                    return Err(error.context(Failed::new(command, log.as_ref())));
+                }
+                let delay = policy.delay(attempt);
+                warn!(
+                    "Command failed (attempt {attempt}/{}), retrying in {delay:?}: {pretty}\n\
+                    {error:?}",
+                    policy.max_attempts
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+        .boxed()
+    }
+
+    /// Run the command once, logging its output and returning its exit status together with
+    /// the captured standard error output and the log of the attempt.
+    async fn run_capturing_stderr(&mut self) -> Result<(ExitStatus, String, Option<CommandLog>)> {
+        self.stdout(Stdio::piped());
+        self.stderr(Stdio::piped());
+        let program = self.program_name();
+        let log = self.create_log();
+        let mut child = self.spawn()?;
+        let stdout = child.stdout.take().context("Failed to capture standard output.")?;
+        let stderr = child.stderr.take().context("Failed to capture standard error.")?;
+        let stdout = spawn_log_processor(format!("{program} ℹ️"), stdout, log.clone());
+        let stderr = spawn_capturing_log_processor(format!("{program} ⚠️"), stderr, log.clone());
+        let status = child.wait().await?;
+        // Errors while processing the output are already logged by the processors.
+        let _ = stdout.await;
+        let stderr = stderr.await??;
+        Ok((status, stderr, log))
+    }
+
+    /// Short name of the program, used as a prefix for its output in the logs.
+    fn program_name(&self) -> String {
+        self.pretty_name.clone().unwrap_or_else(|| {
+            let program = self.inner.as_std().get_program();
+            let program = Path::new(program).file_stem().unwrap_or_default().to_os_string();
+            program.to_string_lossy().to_string()
+        })
+    }
+
     pub fn output_ok(&mut self) -> BoxFuture<'static, Result<Output>> {
         let pretty = self.describe();
         let span = info_span!(
@@ -375,18 +547,24 @@ impl Command {
         self.stderr(Stdio::piped());
         let child = self.spawn();
         let status_checker = self.status_checker.clone();
+        let program = self.program_name();
+        let command = pretty.clone();
         async move {
             let child = child?;
             let output =
                 child.wait_with_output().await.context("Failed while waiting for output.")?;
             tracing::Span::current().record("status", output.status.code());
-            status_checker(output.status).with_context(|| {
-                format!(
-                    "Stdout:\n{}\n\nStderr:\n{}\n",
-                    String::from_utf8_lossy(&output.stdout),
-                    String::from_utf8_lossy(&output.stderr),
-                )
-            })?;
+            if let Err(error) = status_checker(output.status) {
+                // The output was not streamed, so it is recorded only if the command failed.
+                let log = CommandLog::create(&program, &command)
+                    .inspect_err(|e| warn!("Failed to create the log file for {program}: {e:?}"))
+                    .ok();
+                if let Some(log) = &log {
+                    log.record_output(&output.stdout);
+                    log.record_output(&output.stderr);
+                }
+                return Err(error.context(Failed::new(command, log.as_ref())));
+            }
             Result::Ok(output)
         }
         .map_err(move |e| e.context(format!("Failed to get output of the command: {pretty}")))
@@ -456,50 +634,115 @@ impl Command {
     }
 }
 
+/// Spawn a task logging the process output, and recording it in the command log, if given.
 pub fn spawn_log_processor(
     prefix: String,
     out: impl AsyncRead + Send + Unpin + 'static,
+    log: Option<CommandLog>,
 ) -> JoinHandle<Result> {
+    tokio::task::spawn(
+        process_output_lines(prefix, out, move |line| {
+            if let Some(log) = &log {
+                log.record(line);
+            }
+        })
+        .inspect_err(|e| error!("Fatal error while processing process output: {e}")),
+    )
+}
+
+/// Like [`spawn_log_processor`], but the task also collects the logged output and returns it.
+pub fn spawn_capturing_log_processor(
+    prefix: String,
+    out: impl AsyncRead + Send + Unpin + 'static,
+    log: Option<CommandLog>,
+) -> JoinHandle<Result<String>> {
     tokio::task::spawn(
         async move {
-            trace!("{prefix} <START>");
-            let bufread = BufReader::new(out);
-            let mut lines = bufread.split(b'\n');
-            while let Some(line_bytes) = lines.next_segment().await? {
-                match String::from_utf8(line_bytes) {
-                    Ok(line) => {
-                        let line = line.trim_end_matches('\r');
-                        let mut command = false;
-                        if let Some(command_at) = line.find("::") {
-                            if let Some(group_at) = line.find("group") {
-                                // we support: `::group` and `::endgroup` right now
-                                if command_at < group_at && group_at < command_at + 10 {
-                                    let line_without_prefix = &line[command_at..];
-                                    // intentionally using println to avoid info!'s prefix
-                                    println!("{line_without_prefix}");
-                                    command = true;
-                                }
-                            }
-                        }
-                        if !command {
-                            info!("{prefix} {line}");
+            let mut captured = String::new();
+            process_output_lines(prefix, out, |line| {
+                if let Some(log) = &log {
+                    log.record(line);
+                }
+                captured.push_str(line);
+                captured.push('\n');
+            })
+            .await?;
+            Result::Ok(captured)
+        }
+        .inspect_err(|e| error!("Fatal error while processing process output: {e}")),
+    )
+}
+
+/// Like [`spawn_capturing_log_processor`], but only the last `lines` lines of the output are
+/// collected, so the memory use does not grow with the output.
+pub fn spawn_tail_capturing_log_processor(
+    prefix: String,
+    out: impl AsyncRead + Send + Unpin + 'static,
+    log: &Option<CommandLog>,
+    lines: usize,
+) -> JoinHandle<Result<String>> {
+    let log = log.clone();
+    tokio::task::spawn(
+        async move {
+            let mut tail = VecDeque::with_capacity(lines);
+            process_output_lines(prefix, out, |line| {
+                if let Some(log) = &log {
+                    log.record(line);
+                }
+                if tail.len() == lines {
+                    tail.pop_front();
+                }
+                tail.push_back(format!("{line}\n"));
+            })
+            .await?;
+            Result::Ok(tail.into_iter().collect())
+        }
+        .inspect_err(|e| error!("Fatal error while processing process output: {e}")),
+    )
+}
+
+/// Log the output lines of a process, passing each decoded line to the given callback.
+async fn process_output_lines(
+    prefix: String,
+    out: impl AsyncRead + Send + Unpin + 'static,
+    mut on_line: impl FnMut(&str) + Send,
+) -> Result {
+    trace!("{prefix} <START>");
+    let bufread = BufReader::new(out);
+    let mut lines = bufread.split(b'\n');
+    while let Some(line_bytes) = lines.next_segment().await? {
+        match String::from_utf8(line_bytes) {
+            Ok(line) => {
+                let line = line.trim_end_matches('\r');
+                on_line(line);
+                let mut command = false;
+                if let Some(command_at) = line.find("::") {
+                    if let Some(group_at) = line.find("group") {
+                        // we support: `::group` and `::endgroup` right now
+                        if command_at < group_at && group_at < command_at + 10 {
+                            let line_without_prefix = &line[command_at..];
+                            // intentionally using println to avoid info!'s prefix
+                            println!("{line_without_prefix}");
+                            command = true;
                         }
                     }
-                    Err(e) => {
-                        error!("{prefix} Failed to decode a line from output: {e}");
-                        warn!(
-                            "{prefix} Raw buffer: {:?}. Decoded with placeholders: {}",
-                            e.as_bytes(),
-                            String::from_utf8_lossy(e.as_bytes())
-                        );
-                    }
                 }
+                if !command {
+                    info!("{prefix} {line}");
+                }
+            }
+            Err(e) => {
+                error!("{prefix} Failed to decode a line from output: {e}");
+                warn!(
+                    "{prefix} Raw buffer: {:?}. Decoded with placeholders: {}",
+                    e.as_bytes(),
+                    String::from_utf8_lossy(e.as_bytes())
+                );
             }
-            trace!("{prefix} <ENDUT>");
-            Result::Ok(())
         }
-        .inspect_err(|e| error!("Fatal error while processing process output: {e}")),
-    )
+    }
+    trace!("{prefix} <ENDUT>");
+    Ok(())
 }
 
 pub trait Manipulator {
@@ -0,0 +1,243 @@
+//! Per-command log files, so that failures can be reported with a short excerpt of the output.
+//!
+//! The output of the commands is still streamed to the build logs, but it is also recorded in a
+//! dedicated [`CommandLog`] file. When a command fails, its error ([`Failed`]) includes only the
+//! last lines of the output and the path to the full log, rather than megabytes of interleaved
+//! output of the whole build.
+
+use crate::prelude::*;
+
+use std::collections::VecDeque;
+use std::io::BufWriter;
+use std::io::Write;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use tokio::task::JoinHandle;
+
+
+
+define_env_var! {
+    /// Directory where the output of each command is stored. Defaults to a directory unique for
+    /// the build script process, see [`set_default_root`].
+    ENSO_BUILD_COMMAND_LOG_DIR, PathBuf;
+
+    /// Number of the last output lines of a failed command included in its error.
+    ENSO_BUILD_FAILURE_EXCERPT_LINES, usize;
+}
+
+/// Default number of the last output lines of a failed command included in its error.
+pub const DEFAULT_EXCERPT_LINES: usize = 50;
+
+/// Index of the next log file, so the files are ordered by the start of their commands.
+static NEXT_INDEX: AtomicUsize = AtomicUsize::new(1);
+
+/// Directory containing the default log directories of the build script processes.
+static DEFAULT_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Set the directory containing the default log directories, like the build's target directory.
+///
+/// If not set, the logs are stored in the system's temporary directory.
+pub fn set_default_root(root: impl Into<PathBuf>) {
+    if DEFAULT_ROOT.set(root.into()).is_err() {
+        warn!("The default root of the command logs was already set.");
+    }
+}
+
+/// The default log directory, unique for the build script process.
+fn default_log_directory() -> PathBuf {
+    let root = DEFAULT_ROOT.get().cloned();
+    let root = root.unwrap_or_else(|| std::env::temp_dir().join("enso-build-logs"));
+    root.join(std::process::id().to_string())
+}
+
+/// Directory where the command logs are stored, see [`ENSO_BUILD_COMMAND_LOG_DIR`].
+pub fn log_directory() -> Result<PathBuf> {
+    Ok(ENSO_BUILD_COMMAND_LOG_DIR.get_opt()?.unwrap_or_else(default_log_directory))
+}
+
+/// Remove the default log directory of this process.
+///
+/// Meant to be called when the build succeeded, as the logs are referenced by the errors of the
+/// failed commands. A directory set through [`ENSO_BUILD_COMMAND_LOG_DIR`] is kept.
+pub fn remove_default_log_directory() -> Result {
+    if ENSO_BUILD_COMMAND_LOG_DIR.get_opt()?.is_none() {
+        crate::fs::remove_dir_if_exists(default_log_directory())?;
+    }
+    Ok(())
+}
+
+/// Number of the output lines included in the errors, see [`ENSO_BUILD_FAILURE_EXCERPT_LINES`].
+pub fn excerpt_lines() -> usize {
+    ENSO_BUILD_FAILURE_EXCERPT_LINES.get_opt().ok().flatten().unwrap_or(DEFAULT_EXCERPT_LINES)
+}
+
+/// Wait until the output processors record the remaining output of the completed command.
+///
+/// The wait is bounded, as the output pipes might be kept open by orphaned descendant processes.
+pub async fn drain<R>(processors: impl IntoIterator<Item = JoinHandle<R>>) {
+    let processors = futures::future::join_all(processors);
+    let _ = tokio::time::timeout(super::timeout::OUTPUT_DRAIN_TIMEOUT, processors).await;
+}
+
+
+
+// ==================
+// === CommandLog ===
+// ==================
+
+/// The log file of a single command, together with the last lines of its output.
+#[derive(Clone, Debug)]
+pub struct CommandLog {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    path:     PathBuf,
+    /// `None` after a write fails, so the failure is reported only once.
+    file:     Mutex<Option<BufWriter<std::fs::File>>>,
+    tail:     Mutex<VecDeque<String>>,
+    capacity: usize,
+}
+
+impl CommandLog {
+    /// Create a new log file for the command run by the given program.
+    pub fn create(program: &str, command: &str) -> Result<Self> {
+        let directory = log_directory()?;
+        crate::fs::create_dir_if_missing(&directory)?;
+        let index = NEXT_INDEX.fetch_add(1, Ordering::Relaxed);
+        let program = program.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "_");
+        let path = directory.join(format!("{index:04}-{program}.log"));
+        let mut file = BufWriter::new(crate::fs::create(&path)?);
+        writeln!(file, "$ {command}")?;
+        let capacity = excerpt_lines();
+        let inner = Inner {
+            path,
+            file: Mutex::new(Some(file)),
+            tail: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        };
+        Ok(Self { inner: Arc::new(inner) })
+    }
+
+    /// Path to the log file.
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Record a line of the output.
+    pub fn record(&self, line: &str) {
+        let mut file = self.inner.file.lock().unwrap();
+        if let Some(writer) = file.as_mut() {
+            if let Err(e) = writeln!(writer, "{line}") {
+                warn!("Failed to write to the command log {}: {e}", self.path().display());
+                *file = None;
+            }
+        }
+        drop(file);
+        let mut tail = self.inner.tail.lock().unwrap();
+        if tail.len() == self.inner.capacity {
+            tail.pop_front();
+        }
+        if self.inner.capacity > 0 {
+            tail.push_back(line.to_owned());
+        }
+    }
+
+    /// Record all the lines of the captured output.
+    pub fn record_output(&self, output: &[u8]) {
+        for line in String::from_utf8_lossy(output).lines() {
+            self.record(line);
+        }
+    }
+
+    /// The last lines of the output.
+    pub fn excerpt(&self) -> String {
+        self.inner.tail.lock().unwrap().iter().map(|line| format!("{line}\n")).collect()
+    }
+
+    /// Write the buffered output to the file.
+    pub fn flush(&self) {
+        if let Some(writer) = self.inner.file.lock().unwrap().as_mut() {
+            if let Err(e) = writer.flush() {
+                warn!("Failed to write to the command log {}: {e}", self.path().display());
+            }
+        }
+    }
+}
+
+
+
+// ==============
+// === Failed ===
+// ==============
+
+/// Context of the error of a failed command.
+///
+/// Can be detected using [`anyhow::Error::downcast_ref`].
+#[derive(Clone, Debug)]
+pub struct Failed {
+    /// Description of the command.
+    pub command: String,
+    /// The last lines of the output, if it was recorded.
+    pub excerpt: Option<String>,
+    /// Path to the full log of the command, if it was recorded.
+    pub log:     Option<PathBuf>,
+}
+
+impl Failed {
+    pub fn new(command: impl Into<String>, log: Option<&CommandLog>) -> Self {
+        if let Some(log) = log {
+            log.flush();
+        }
+        Self {
+            command: command.into(),
+            excerpt: log.map(CommandLog::excerpt),
+            log:     log.map(|log| log.path().to_owned()),
+        }
+    }
+}
+
+impl Display for Failed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Command failed: {}", self.command)?;
+        match self.excerpt.as_deref() {
+            Some("") => write!(f, "\n\nThe command produced no output.")?,
+            Some(excerpt) => write!(f, "\n\nLast lines of the output:\n{excerpt}")?,
+            None => {}
+        }
+        if let Some(log) = &self.log {
+            write!(f, "\nFull log: {}", log.display())?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::command::Command;
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn failure_excerpt() -> Result {
+        let error = Command::new("sh")
+            .args(["-c", "for i in $(seq 1 100); do echo line $i; done; exit 3"])
+            .run_ok()
+            .await
+            .unwrap_err();
+        let failed = error.downcast_ref::<Failed>().context("Missing failure context.")?;
+        let excerpt = failed.excerpt.as_deref().context("Missing excerpt.")?;
+        assert_eq!(excerpt.lines().count(), DEFAULT_EXCERPT_LINES);
+        assert!(excerpt.starts_with("line 51\n") && excerpt.ends_with("line 100\n"));
+
+        let log = failed.log.as_ref().context("Missing log.")?;
+        let full_log = crate::fs::read_to_string(log)?;
+        assert_eq!(full_log.lines().count(), 101);
+        assert!(error.to_string().contains(&format!("Full log: {}", log.display())));
+        Ok(())
+    }
+}
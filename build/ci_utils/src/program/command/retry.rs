@@ -0,0 +1,203 @@
+//! Policies for retrying failed operations, like flaky network-dependent commands.
+
+use crate::prelude::*;
+
+use regex::Regex;
+use std::process::ExitStatus;
+use std::time::Duration;
+
+
+
+/// Describes how many times and under which conditions a failed operation should be retried.
+///
+/// By default, any failure is retried. The retries can be limited to failures with specific exit
+/// codes or with standard error output matching specific patterns. If both are given, it is
+/// enough for a failure to match either of them.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one.
+    pub max_attempts:    usize,
+    /// Delay before the first retry.
+    pub initial_delay:   Duration,
+    /// Factor by which the delay grows after each retry.
+    pub backoff_factor:  f64,
+    /// Upper bound for the delay between retries.
+    pub max_delay:       Duration,
+    /// Exit codes that allow retrying.
+    pub exit_codes:      BTreeSet<i32>,
+    /// Patterns of the standard error output that allow retrying.
+    pub stderr_patterns: Vec<Regex>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts:    6,
+            initial_delay:   Duration::from_millis(500),
+            backoff_factor:  1.5,
+            max_delay:       Duration::from_secs(10),
+            exit_codes:      default(),
+            stderr_patterns: default(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy with the default backoff that makes at most `max_attempts` attempts.
+    pub fn new(max_attempts: usize) -> Self {
+        Self { max_attempts, ..default() }
+    }
+
+    /// Set the exponential backoff parameters.
+    pub fn with_backoff(
+        mut self,
+        initial_delay: Duration,
+        backoff_factor: f64,
+        max_delay: Duration,
+    ) -> Self {
+        self.initial_delay = initial_delay;
+        self.backoff_factor = backoff_factor;
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Allow retrying failures with the given exit code.
+    pub fn retry_on_exit_code(mut self, code: i32) -> Self {
+        self.exit_codes.insert(code);
+        self
+    }
+
+    /// Allow retrying failures whose standard error output matches the given regular expression.
+    pub fn retry_on_stderr(mut self, pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern)
+            .with_context(|| format!("Invalid standard error pattern: `{pattern}`."))?;
+        self.stderr_patterns.push(regex);
+        Ok(self)
+    }
+
+    /// Check if a command that exited with the given status and error output should be retried.
+    ///
+    /// This does not take the number of attempts into account.
+    pub fn should_retry(&self, status: ExitStatus, stderr: &str) -> bool {
+        if self.exit_codes.is_empty() && self.stderr_patterns.is_empty() {
+            return true;
+        }
+        let code_matches = status.code().is_some_and(|code| self.exit_codes.contains(&code));
+        let stderr_matches = self.stderr_patterns.iter().any(|pattern| pattern.is_match(stderr));
+        code_matches || stderr_matches
+    }
+
+    /// Delay before the retry that follows the given failed attempt (counted from 1).
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as usize) as i32;
+        let delay = self.initial_delay.as_secs_f64() * self.backoff_factor.powi(exponent);
+        Duration::try_from_secs_f64(delay).unwrap_or(self.max_delay).min(self.max_delay)
+    }
+
+    /// Run the action until it succeeds or the attempts are exhausted.
+    ///
+    /// Every error is retried, as there is no exit status nor error output to classify.
+    pub async fn run<F, Fut, Ret>(&self, mut action: F) -> Result<Ret>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Ret>>, {
+        let mut attempt = 1;
+        loop {
+            match action().await {
+                Ok(result) => return Ok(result),
+                Err(err) if attempt >= self.max_attempts => return Err(err),
+                Err(err) => {
+                    let delay = self.delay(attempt);
+                    let warning = format!(
+                        "Attempt {attempt}/{} failed, retrying in {delay:?}: {err:?}",
+                        self.max_attempts
+                    );
+                    crate::actions::workflow::warn(&warning);
+                    warn!("{warning}");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Run the blocking action until it succeeds, fails with an error that should not be retried,
+    /// or the attempts are exhausted.
+    pub fn run_blocking<Ret, E: Display>(
+        &self,
+        mut action: impl FnMut() -> std::result::Result<Ret, E>,
+        should_retry: impl Fn(&E) -> bool,
+    ) -> std::result::Result<Ret, E> {
+        let mut attempt = 1;
+        loop {
+            match action() {
+                Err(err) if attempt < self.max_attempts && should_retry(&err) => {
+                    let delay = self.delay(attempt);
+                    debug!(
+                        "Attempt {attempt}/{} failed, retrying in {delay:?}: {err}",
+                        self.max_attempts
+                    );
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn status(code: i32) -> ExitStatus {
+        use std::os::unix::process::ExitStatusExt;
+        ExitStatus::from_raw(code << 8)
+    }
+
+    #[test]
+    fn backoff() {
+        let policy =
+            RetryPolicy::new(10).with_backoff(Duration::from_secs(1), 2.0, Duration::from_secs(5));
+        let delays = (1..=4).map(|attempt| policy.delay(attempt).as_secs()).collect_vec();
+        assert_eq!(delays, [1, 2, 4, 5]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn classification() -> Result {
+        assert!(RetryPolicy::default().should_retry(status(1), ""));
+        let policy = RetryPolicy::default()
+            .retry_on_exit_code(75)
+            .retry_on_stderr("Connection (reset|refused)")?;
+        assert!(policy.should_retry(status(75), ""));
+        assert!(policy.should_retry(status(1), "error: Connection reset by peer"));
+        assert!(!policy.should_retry(status(1), "error: file not found"));
+        Ok(())
+    }
+
+    #[test]
+    fn blocking() {
+        let policy = RetryPolicy::new(3).with_backoff(Duration::ZERO, 1.0, Duration::ZERO);
+        let mut attempts = 0;
+        let result = policy.run_blocking(
+            || {
+                attempts += 1;
+                Err::<(), _>(attempts)
+            },
+            |_| true,
+        );
+        assert_eq!((result, attempts), (Err(3), 3));
+        let mut attempts = 0;
+        let result = policy.run_blocking(
+            || {
+                attempts += 1;
+                Err::<(), _>(attempts)
+            },
+            |attempt| *attempt != 2,
+        );
+        assert_eq!((result, attempts), (Err(2), 2));
+    }
+}
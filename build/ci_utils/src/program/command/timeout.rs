@@ -0,0 +1,77 @@
+//! Hard timeouts for commands, so a hung process cannot wedge the whole build.
+//!
+//! See [`Command::run_ok_with_timeout`](crate::program::command::Command::run_ok_with_timeout).
+
+use crate::prelude::*;
+
+use std::time::Duration;
+
+
+
+define_env_var! {
+    /// Timeout applied to the commands run with
+    /// [`Command::run_ok_with_configured_timeout`](crate::program::command::Command::run_ok_with_configured_timeout).
+    /// When exceeded, the command's whole process tree is killed.
+    ENSO_BUILD_COMMAND_TIMEOUT, Duration;
+}
+
+/// Number of the last output lines included in the [`TimedOut`] error.
+pub const OUTPUT_TAIL_LINES: usize = 50;
+
+/// How long to wait for the output of the killed processes to be drained.
+pub const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Error returned when a command did not complete within its timeout and was killed.
+///
+/// Can be detected using [`anyhow::Error::downcast_ref`].
+#[derive(Clone, Debug)]
+pub struct TimedOut {
+    /// Description of the command.
+    pub command: String,
+    /// The exceeded timeout.
+    pub timeout: Duration,
+    /// The last lines of the standard output, if they could be collected.
+    pub stdout:  Option<String>,
+    /// The last lines of the standard error, if they could be collected.
+    pub stderr:  Option<String>,
+}
+
+impl Display for TimedOut {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Command timed out after {:?} and was killed: {}", self.timeout, self.command)?;
+        let outputs = [("standard output", &self.stdout), ("standard error", &self.stderr)];
+        for (name, output) in outputs {
+            match output {
+                Some(output) => write!(f, "\nLast lines of {name}:\n{output}")?,
+                None => write!(f, "\nFailed to collect {name}.\n")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::command::Command;
+    use std::time::Instant;
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn kills_process_tree() -> Result {
+        let started = Instant::now();
+        let result = Command::new("sh")
+            .args(["-c", "echo started; sleep 60 & sleep 60"])
+            .run_ok_with_timeout(Duration::from_secs(1))
+            .await;
+        let error = result.expect_err("The command should time out.");
+        let timed_out = error.downcast_ref::<TimedOut>().expect("Expected the timeout error.");
+        // The output is only drained once the background `sleep` holding the pipes is killed.
+        assert!(started.elapsed() < Duration::from_secs(30));
+        assert_eq!(timed_out.stdout.as_deref(), Some("started\n"));
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@ pub mod explorer;
 pub mod flatc;
 pub mod git;
 pub mod go;
+pub mod gpg;
 pub mod graalpy;
 pub mod java;
 pub mod javac;
@@ -21,6 +22,7 @@ pub mod rsync;
 pub mod rustc;
 pub mod rustup;
 pub mod sbt;
+pub mod sccache;
 pub mod seven_zip;
 pub mod sh;
 pub mod signtool;
@@ -38,6 +40,7 @@ pub use docker::Docker;
 pub use flatc::Flatc;
 pub use git::Git;
 pub use go::Go;
+pub use gpg::Gpg;
 pub use java::Java;
 pub use javac::Javac;
 pub use node::Node;
@@ -46,6 +49,7 @@ pub use node::Pnpm;
 pub use npx::Npx;
 pub use pwsh::PwSh;
 pub use sbt::Sbt;
+pub use sccache::Sccache;
 pub use seven_zip::SevenZip;
 pub use sh::Bash;
 pub use strip::Strip;
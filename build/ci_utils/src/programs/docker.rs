@@ -2,29 +2,14 @@ use crate::prelude::*;
 
 use crate::env::accessor::TypedVariable;
 use crate::extensions::child::ChildExt;
+use crate::program::command::retry::RetryPolicy;
 
 use std::process::Stdio;
 
 
 
-/// Get the docker image identifier from the `docker build` command output.
-///
-/// This assumes format compatible with `--quiet` flag.
-#[context("Failed to get the image identifier from the output: {output:?}.")]
-fn get_image_id_from_build_output(output: &std::process::Output) -> Result<ImageId> {
-    trace!("Output: {:?}", output);
-    let built_image_id = std::str::from_utf8(&output.stdout)?
-        .lines()
-        .inspect(|line| debug!("{}", line))
-        .filter(|line| !line.is_empty())
-        .last()
-        .with_context(|| "Docker provided no output.")?
-        .split(' ')
-        .last()
-        .with_context(|| "The last line has no space!")?;
-    debug!("Image {} successfully built!", built_image_id);
-    ImageId::from_str(built_image_id)
-}
+/// Key of the pushed image's manifest digest in the `--metadata-file` written by `buildx`.
+const METADATA_DIGEST_KEY: &str = "containerimage.digest";
 
 #[derive(Clone, Debug, PartialEq, Ord, PartialOrd, Eq, Hash)]
 pub enum NetworkDriver {
@@ -114,15 +99,44 @@ impl Program for Docker {
 
 impl Docker {
     pub async fn build(&self, options: BuildOptions) -> Result<ImageId> {
+        Ok(self.build_image(options).await?.image_id)
+    }
+
+    /// Build the image, describing the result by its identifiers rather than its tags.
+    ///
+    /// The identifiers are read from the files written by Docker (`--iidfile` and, for `buildx`,
+    /// `--metadata-file`), so the build output can be streamed to the logs as-is.
+    pub async fn build_image(&self, options: BuildOptions) -> Result<BuildOutput> {
+        let temp = tempfile::tempdir()?;
+        let iid_file = temp.path().join("iid");
+        let metadata_file = temp.path().join("metadata.json");
         let mut command = self.cmd()?;
         if options.buildx {
             command.arg("buildx");
         }
-        command.arg("build").args(options.args());
+        command.arg("build").args(options.args()).arg("--iidfile").arg(&iid_file);
+        if options.buildx {
+            command.arg("--metadata-file").arg(&metadata_file);
+        }
         debug!("{:?}", command);
-        let output = command.output_ok().await?;
-        let built_image_id = get_image_id_from_build_output(&output)?;
-        Ok(built_image_id)
+        command.run_ok().await?;
+        let output = BuildOutput::from_files(&iid_file, &metadata_file)?;
+        debug!("Image {} successfully built!", output.image_id);
+        Ok(output)
+    }
+
+    /// Create the `buildx` builder with the `docker-container` driver, unless it already exists.
+    ///
+    /// Unlike the default builder, it can export the cache to a registry or a local directory.
+    pub async fn ensure_container_builder(&self, name: &str) -> Result {
+        let inspect = self.cmd()?.args(["buildx", "inspect", name]).run_ok().await;
+        if inspect.is_err() {
+            self.cmd()?
+                .args(["buildx", "create", "--name", name, "--driver", "docker-container"])
+                .run_ok()
+                .await?;
+        }
+        Ok(())
     }
 
     pub fn run_cmd(&self, options: &RunOptions) -> Result<Command> {
@@ -267,7 +281,7 @@ impl Docker {
     pub async fn push(&self, image: &str) -> Result {
         let mut cmd = self.cmd()?;
         cmd.args(["push", image]);
-        cmd.run_ok().await
+        cmd.run_ok_with_retries(RetryPolicy::default()).await
     }
 
     /// Prune, i.e. remove unused data.
@@ -279,29 +293,95 @@ impl Docker {
     }
 }
 
+/// Location of the build cache, used as a source (`--cache-from`) or a destination
+/// (`--cache-to`) of the cached layers.
+///
+/// Exporting the cache to a registry or a local directory requires the `buildx` builder with the
+/// `docker-container` driver, as the default `docker` driver supports only the inline cache. See
+/// [`Docker::ensure_container_builder`] and [`BuildOptions::builder`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Cache {
+    /// Cache metadata embedded in the built image. Usable only as a destination; later builds
+    /// should use the image as a [registry](Cache::Registry) source.
+    Inline,
+    /// Cache stored as a separate image in the registry, like `registry/repository:buildcache`.
+    Registry(String),
+    /// Cache stored in a local directory.
+    Local(PathBuf),
+}
+
+impl Cache {
+    /// Value of the `--cache-from` argument.
+    pub fn source_arg(&self) -> Result<String> {
+        match self {
+            Cache::Inline => bail!("The inline cache cannot be used as a cache source."),
+            Cache::Registry(reference) => Ok(format!("type=registry,ref={reference}")),
+            Cache::Local(path) => Ok(format!("type=local,src={}", path.display())),
+        }
+    }
+
+    /// Value of the `--cache-to` argument.
+    ///
+    /// The layers of all the stages are exported (`mode=max`), not only of the final one. The
+    /// registry cache is stored as an OCI image manifest, as some registries (like ECR) do not
+    /// accept the cache manifest list.
+    pub fn destination_arg(&self) -> String {
+        match self {
+            Cache::Inline => "type=inline".into(),
+            Cache::Registry(reference) => format!(
+                "type=registry,ref={reference},mode=max,image-manifest=true,oci-mediatypes=true"
+            ),
+            Cache::Local(path) => format!("type=local,dest={},mode=max", path.display()),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct BuildOptions {
     /// Whether the `buildx` (extended build capabilities with BuildKit) should be used.
     pub buildx:        bool,
+    /// The `buildx` builder instance to use, instead of the current one. Available only for
+    /// buildx.
+    pub builder:       Option<String>,
     pub context:       PathBuf,
+    /// The stage of a multi-stage Dockerfile to build.
     pub target:        Option<OsString>,
     pub tags:          Vec<String>,
-    pub build_args:    HashMap<String, Option<String>>,
+    pub build_args:    BTreeMap<String, Option<String>>,
     ///Named build contexts. Available only for buildx.
-    pub build_context: HashMap<String, String>,
+    pub build_context: BTreeMap<String, String>,
     pub file:          Option<PathBuf>,
+    /// Metadata labels of the built image.
+    pub labels:        BTreeMap<String, String>,
+    /// Caches to import the layers from. Available only for buildx.
+    pub cache_from:    Vec<Cache>,
+    /// Caches to export the layers to. Available only for buildx.
+    pub cache_to:      Vec<Cache>,
+    /// Do not use the cached layers at all.
+    pub no_cache:      bool,
+    /// Push the image to the registry right after building. Available only for buildx.
+    ///
+    /// Unlike a separate `docker push`, this reports the [digest](BuildOutput::digest) of the
+    /// pushed image.
+    pub push:          bool,
 }
 
 impl BuildOptions {
     pub fn new(context_path: impl Into<PathBuf>) -> Self {
         Self {
             buildx:        false,
+            builder:       default(),
             context:       context_path.into(),
             target:        default(),
             tags:          default(),
             build_args:    default(),
             build_context: default(),
             file:          default(),
+            labels:        default(),
+            cache_from:    default(),
+            cache_to:      default(),
+            no_cache:      false,
+            push:          false,
         }
     }
 
@@ -355,12 +435,64 @@ impl BuildOptions {
         self.build_context.insert(name.into(), path.as_ref().as_str().to_string());
     }
 
+    /// Import the cached layers from the given cache.
+    pub fn add_cache_from(&mut self, cache: Cache) {
+        self.buildx = true;
+        self.cache_from.push(cache);
+    }
+
+    /// Export the cached layers to the given cache.
+    pub fn add_cache_to(&mut self, cache: Cache) {
+        self.buildx = true;
+        self.cache_to.push(cache);
+    }
+
+    /// Build using the given `buildx` builder instance.
+    pub fn use_builder(&mut self, name: impl Into<String>) {
+        self.buildx = true;
+        self.builder = Some(name.into());
+    }
+
+    /// Use the registry cache, both as a source and as a destination.
+    pub fn use_registry_cache(&mut self, reference: impl Into<String>) {
+        let cache = Cache::Registry(reference.into());
+        self.add_cache_from(cache.clone());
+        self.add_cache_to(cache);
+    }
+
+    /// Push the built image, see [`BuildOptions::push`].
+    pub fn push(&mut self) {
+        self.buildx = true;
+        self.push = true;
+    }
+
+    /// The arguments of the `docker build` command.
+    ///
+    /// The arguments are always in the same order, so the same options result in the same
+    /// command.
     pub fn args(&self) -> Vec<OsString> {
-        let Self { buildx: _, context, target, tags, build_args, build_context, file } = self;
+        let Self {
+            buildx: _,
+            builder,
+            context,
+            target,
+            tags,
+            build_args,
+            build_context,
+            file,
+            labels,
+            cache_from,
+            cache_to,
+            no_cache,
+            push,
+        } = self;
 
         let mut ret = Vec::new();
         ret.push(context.clone().into());
-        ret.push("--quiet".into());
+        if let Some(builder) = builder.as_ref() {
+            ret.push("--builder".into());
+            ret.push(builder.into());
+        }
         if let Some(target) = target.as_ref() {
             ret.push("--target".into());
             ret.push(target.clone());
@@ -381,6 +513,25 @@ impl BuildOptions {
             ret.push("--build-context".into());
             ret.push(format!("{name}={value}").into());
         }
+        for (name, value) in labels {
+            ret.push("--label".into());
+            ret.push(format!("{name}={value}").into());
+        }
+        // The inline cache cannot be imported, the image itself is used as a registry cache.
+        for cache in cache_from.iter().filter_map(|cache| cache.source_arg().ok()) {
+            ret.push("--cache-from".into());
+            ret.push(cache.into());
+        }
+        for cache in cache_to {
+            ret.push("--cache-to".into());
+            ret.push(cache.destination_arg().into());
+        }
+        if *no_cache {
+            ret.push("--no-cache".into());
+        }
+        if *push {
+            ret.push("--push".into());
+        }
         if let Some(file) = file.as_ref() {
             ret.push("--file".into());
             // Docker can't handle verbatim Dockerfile path. It would fail like:
@@ -587,6 +738,40 @@ impl FromStr for ImageId {
     }
 }
 
+/// Identifiers of the built image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildOutput {
+    /// The local image identifier, i.e. the digest of the image configuration.
+    pub image_id: ImageId,
+    /// The digest of the image manifest in the registry, like `sha256:...`. Known only if the
+    /// image was [pushed](BuildOptions::push) by the build.
+    ///
+    /// The image can be referred to as `repository@digest`, which, unlike the tags, always
+    /// denotes the same image.
+    pub digest:   Option<String>,
+}
+
+impl BuildOutput {
+    /// Read the identifiers from the `--iidfile` and the (optional) `--metadata-file`.
+    #[context("Failed to read the identifiers of the built image.")]
+    pub fn from_files(iid_file: impl AsRef<Path>, metadata_file: impl AsRef<Path>) -> Result<Self> {
+        let image_id = ImageId(crate::fs::read_to_string(iid_file)?.trim().to_owned());
+        ensure!(!image_id.0.is_empty(), "Docker provided no image identifier.");
+        let digest = if metadata_file.as_ref().exists() {
+            let metadata: serde_json::Value = crate::fs::read_json(metadata_file)?;
+            metadata.get(METADATA_DIGEST_KEY).and_then(|digest| digest.as_str()).map(Into::into)
+        } else {
+            None
+        };
+        Ok(Self { image_id, digest })
+    }
+
+    /// Reference to the pushed image in the given repository, pinned by its digest.
+    pub fn pinned_reference(&self, repository: &str) -> Option<String> {
+        self.digest.as_ref().map(|digest| format!("{repository}@{digest}"))
+    }
+}
+
 #[derive(Clone, Debug, Display, Deref, AsRef)]
 pub struct ContainerId(pub String);
 
@@ -673,6 +858,53 @@ mod tests {
         get_windows_image_tag(kernel_version)
     }
 
+    #[test]
+    fn build_arguments() -> Result {
+        let mut opts = BuildOptions::new("context");
+        opts.build_args.insert("B".into(), Some("2".into()));
+        opts.build_args.insert("A".into(), None);
+        opts.labels.insert("version".into(), "1.0".into());
+        opts.use_builder("container");
+        opts.use_registry_cache("registry/runtime:buildcache");
+        opts.add_cache_to(Cache::Inline);
+        opts.push();
+        assert!(opts.buildx);
+        let expected = [
+            "context",
+            "--builder",
+            "container",
+            "--build-arg",
+            "A",
+            "--build-arg",
+            "B=2",
+            "--label",
+            "version=1.0",
+            "--cache-from",
+            "type=registry,ref=registry/runtime:buildcache",
+            "--cache-to",
+            "type=registry,ref=registry/runtime:buildcache,mode=max,image-manifest=true,\
+            oci-mediatypes=true",
+            "--cache-to",
+            "type=inline",
+            "--push",
+        ];
+        assert_eq!(opts.args(), expected.map(OsString::from));
+        assert!(Cache::Inline.source_arg().is_err());
+
+        let temp = tempfile::tempdir()?;
+        let iid_file = temp.path().join("iid");
+        let metadata_file = temp.path().join("metadata.json");
+        crate::fs::write(&iid_file, "sha256:1234\n")?;
+        let output = BuildOutput::from_files(&iid_file, &metadata_file)?;
+        assert_eq!(output.image_id, ImageId("sha256:1234".into()));
+        assert_eq!(output.pinned_reference("registry/runtime"), None);
+        crate::fs::write(&metadata_file, r#"{"containerimage.digest": "sha256:abcd"}"#)?;
+        let output = BuildOutput::from_files(&iid_file, &metadata_file)?;
+        let reference = output.pinned_reference("registry/runtime");
+        assert_eq!(reference.as_deref(), Some("registry/runtime@sha256:abcd"));
+        Ok(())
+    }
+
     #[tokio::test]
     #[ignore]
     async fn network() -> Result {
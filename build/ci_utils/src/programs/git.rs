@@ -7,6 +7,7 @@
 use crate::prelude::*;
 
 use crate::new_command_type;
+use crate::program::command::retry::RetryPolicy;
 use crate::programs::git::pretty_format::refs_from_decoration;
 use crate::RECORD_SEPARATOR;
 
@@ -62,7 +63,9 @@ impl Git {
     pub async fn clone(&self, path: impl AsRef<Path>, url: &Url) -> Result<Context> {
         let path = path.as_ref();
         crate::fs::tokio::create_dir_if_missing(path).await?;
-        self.cmd()?.arg(Command::Clone).arg(url.as_str()).arg(path).run_ok().await?;
+        let mut clone = self.cmd()?;
+        clone.arg(Command::Clone).arg(url.as_str()).arg(path);
+        clone.into_inner().run_ok_with_retries(RetryPolicy::default()).await?;
         Context::new(path).await
     }
 }
@@ -138,7 +141,9 @@ impl Context {
     /// Fetch a branch from a remote repository.
     #[context("Failed to fetch branch {} from remote {}.", branch, remote)]
     pub async fn fetch_branch(&self, remote: &str, branch: &str) -> Result {
-        self.cmd()?.arg(Command::Fetch).args([remote, branch]).run_ok().await
+        let mut fetch = self.cmd()?;
+        fetch.arg(Command::Fetch).args([remote, branch]);
+        fetch.into_inner().run_ok_with_retries(RetryPolicy::default()).await
     }
 
     /// List of files that are different than the compared commit.
@@ -239,6 +244,44 @@ impl Context {
             })
             .try_collect()
     }
+
+    /// Get the commits in the given revision range (like `v1.0.0..HEAD`), newest first.
+    pub async fn commits(&self, range: &str) -> Result<Vec<Commit>> {
+        use pretty_format::Placeholder::Body;
+        use pretty_format::Placeholder::Hash;
+        use pretty_format::Placeholder::Subject;
+        let format = [Hash, Subject, Body].iter().map(ToString::to_string).join(RECORD_SEPARATOR);
+        // The body may span multiple lines, so the commits are separated by NUL characters.
+        let output = self
+            .cmd()?
+            .arg(Command::Log)
+            .args(["-z", &format!("--pretty=format:{format}"), range])
+            .output_ok()
+            .await?
+            .into_stdout_string()?;
+        output
+            .split('\0')
+            .filter(|record| !record.is_empty())
+            .map(|record| {
+                let mut fields = record.splitn(3, RECORD_SEPARATOR);
+                let mut next = |name| fields.next().with_context(|| format!("Missing {name}."));
+                let hash = next("hash")?.to_owned();
+                let subject = next("subject")?.to_owned();
+                let body = next("body")?.trim().to_owned();
+                Ok(Commit { hash, subject, body })
+            })
+            .try_collect()
+    }
+}
+
+/// A commit from the history, as returned by [`Context::commits`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Commit {
+    pub hash:    String,
+    /// The first line of the commit message.
+    pub subject: String,
+    /// The commit message without the subject.
+    pub body:    String,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -0,0 +1,47 @@
+//! Wrapper over [GnuPG](https://gnupg.org), used to sign and verify files.
+
+use crate::prelude::*;
+
+
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Gpg;
+
+impl Program for Gpg {
+    type Command = Command;
+    type Version = Version;
+
+    fn executable_name(&self) -> &'static str {
+        "gpg"
+    }
+}
+
+impl Gpg {
+    /// Create an ASCII-armored detached signature of the file, using the given key.
+    ///
+    /// The key can be identified by anything `gpg --local-user` accepts, like its fingerprint.
+    pub async fn detach_sign(
+        &self,
+        key: &str,
+        file: impl AsRef<Path>,
+        signature: impl AsRef<Path>,
+    ) -> Result {
+        self.cmd()?
+            .args(["--batch", "--yes", "--armor", "--local-user", key, "--output"])
+            .arg(signature.as_ref())
+            .arg("--detach-sign")
+            .arg(file.as_ref())
+            .run_ok()
+            .await
+    }
+
+    /// Verify the detached signature of the file against the keys in the keyring.
+    pub async fn verify(&self, signature: impl AsRef<Path>, file: impl AsRef<Path>) -> Result {
+        self.cmd()?
+            .args(["--batch", "--verify"])
+            .arg(signature.as_ref())
+            .arg(file.as_ref())
+            .run_ok()
+            .await
+    }
+}
@@ -8,7 +8,7 @@ use crate::program::command::Manipulator;
 define_env_var! {
     /// Force the SBT server to start, avoiding `ServerAlreadyBootingException`.
     /// See: https://github.com/sbt/sbt/issues/6777#issuecomment-1613316167
-    SBT_SERVER_FORCESTART, bool;
+    SBT_SERVER_FORCESTART, flag;
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -0,0 +1,298 @@
+//! Wrapper over [sccache](https://github.com/mozilla/sccache), the shared compiler cache.
+//!
+//! When [started](start), sccache is set as the `RUSTC_WRAPPER`, so all the Cargo invocations of
+//! the build share the cache. The cache statistics are captured before the build, so the
+//! [report] can tell how well the cache performed for this particular build.
+
+use crate::prelude::*;
+
+use crate::prerequisites;
+use crate::prerequisites::Requirement;
+use std::sync::Mutex;
+
+
+
+pub mod env {
+    crate::define_env_var! {
+        /// Wrapper that Cargo invokes `rustc` through.
+        RUSTC_WRAPPER, PathBuf;
+
+        /// S3 bucket storing the cache.
+        SCCACHE_BUCKET, String;
+
+        /// Region of the S3 bucket.
+        SCCACHE_REGION, String;
+
+        /// Endpoint of the S3-compatible storage, if it is not the AWS S3.
+        SCCACHE_ENDPOINT, String;
+
+        /// Set to `true` or `on` to store the cache in the GitHub Actions cache.
+        SCCACHE_GHA_ENABLED, String;
+
+        /// Local directory storing the cache, if no remote storage is configured.
+        SCCACHE_DIR, PathBuf;
+    }
+}
+
+/// Statistics captured when the cache was started for this build.
+static BASELINE: Mutex<Option<Stats>> = Mutex::new(None);
+
+
+
+// ===============
+// === Storage ===
+// ===============
+
+/// Where sccache stores the cached compilation results.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Storage {
+    /// An S3 (or S3-compatible) bucket, shared between the CI runners and developers.
+    S3 { bucket: String, region: Option<String>, endpoint: Option<String> },
+    /// The GitHub Actions cache.
+    GitHubActions,
+    /// A local directory. If not set, sccache uses its default location.
+    Local { directory: Option<PathBuf> },
+}
+
+impl Storage {
+    /// Read the storage configuration from the environment, failing if it is inconsistent.
+    pub fn from_env() -> Result<Self> {
+        let bucket = env::SCCACHE_BUCKET.get_opt()?;
+        let region = env::SCCACHE_REGION.get_opt()?;
+        let endpoint = env::SCCACHE_ENDPOINT.get_opt()?;
+        let gha_enabled = env::SCCACHE_GHA_ENABLED.get_opt()?;
+        let gha_enabled = gha_enabled.is_some_and(|value| matches!(value.as_str(), "true" | "on"));
+        match bucket {
+            Some(bucket) => {
+                ensure!(!gha_enabled, "Both the S3 bucket and GitHub Actions cache are enabled.");
+                ensure!(
+                    region.is_some() || endpoint.is_some(),
+                    "The S3 bucket `{bucket}` requires {} or {} to be set.",
+                    env::SCCACHE_REGION.name(),
+                    env::SCCACHE_ENDPOINT.name()
+                );
+                Ok(Storage::S3 { bucket, region, endpoint })
+            }
+            None => {
+                ensure!(
+                    endpoint.is_none(),
+                    "{} is set without {}.",
+                    env::SCCACHE_ENDPOINT.name(),
+                    env::SCCACHE_BUCKET.name()
+                );
+                if gha_enabled {
+                    Ok(Storage::GitHubActions)
+                } else {
+                    Ok(Storage::Local { directory: env::SCCACHE_DIR.get_opt()? })
+                }
+            }
+        }
+    }
+}
+
+impl Display for Storage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Storage::S3 { bucket, endpoint: Some(endpoint), .. } =>
+                write!(f, "S3 bucket {bucket} at {endpoint}"),
+            Storage::S3 { bucket, .. } => write!(f, "S3 bucket {bucket}"),
+            Storage::GitHubActions => write!(f, "GitHub Actions cache"),
+            Storage::Local { directory: Some(directory) } =>
+                write!(f, "local directory {}", directory.display()),
+            Storage::Local { directory: None } => write!(f, "default local directory"),
+        }
+    }
+}
+
+
+
+// =============
+// === Stats ===
+// =============
+
+/// Counters from the `sccache --show-stats --stats-format json` output.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawStats {
+    compile_requests:           u64,
+    requests_not_cacheable:     u64,
+    cache_hits:                 RawCounts,
+    cache_misses:               RawCounts,
+    cache_errors:               RawCounts,
+    non_cacheable_compilations: u64,
+}
+
+/// Counts per language.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawCounts {
+    counts: BTreeMap<String, u64>,
+}
+
+impl RawCounts {
+    fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawOutput {
+    stats: RawStats,
+}
+
+/// Statistics of the compiler cache.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Compilations requested through the wrapper.
+    pub compile_requests: u64,
+    /// Compilations served from the cache.
+    pub hits:             u64,
+    /// Cacheable compilations that were not found in the cache.
+    pub misses:           u64,
+    /// Failures to read from or write to the cache.
+    pub errors:           u64,
+    /// Compilations that could not be cached at all (e.g. of binaries or proc-macros).
+    pub not_cacheable:    u64,
+}
+
+impl Stats {
+    /// Parse the JSON output of `sccache --show-stats --stats-format json`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let RawOutput { stats } = serde_json::from_str(json)?;
+        Ok(Self {
+            compile_requests: stats.compile_requests,
+            hits:             stats.cache_hits.total(),
+            misses:           stats.cache_misses.total(),
+            errors:           stats.cache_errors.total(),
+            not_cacheable:    stats.requests_not_cacheable + stats.non_cacheable_compilations,
+        })
+    }
+
+    /// The statistics accumulated since the `baseline` was captured.
+    pub fn since(&self, baseline: &Stats) -> Self {
+        Self {
+            compile_requests: self.compile_requests.saturating_sub(baseline.compile_requests),
+            hits:             self.hits.saturating_sub(baseline.hits),
+            misses:           self.misses.saturating_sub(baseline.misses),
+            errors:           self.errors.saturating_sub(baseline.errors),
+            not_cacheable:    self.not_cacheable.saturating_sub(baseline.not_cacheable),
+        }
+    }
+
+    /// Share of the cacheable compilations that were served from the cache.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let cacheable = self.hits + self.misses;
+        (cacheable > 0).then(|| self.hits as f64 / cacheable as f64)
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Stats { compile_requests, hits, misses, errors, not_cacheable } = self;
+        write!(f, "{compile_requests} compile requests, {hits} hits, {misses} misses")?;
+        if let Some(hit_rate) = self.hit_rate() {
+            write!(f, " ({:.1}% hit rate)", hit_rate * 100.0)?;
+        }
+        write!(f, ", {not_cacheable} not cacheable, {errors} cache errors")
+    }
+}
+
+
+
+// ===============
+// === Sccache ===
+// ===============
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sccache;
+
+impl Program for Sccache {
+    type Command = Command;
+    type Version = Version;
+
+    fn executable_name(&self) -> &'static str {
+        "sccache"
+    }
+}
+
+impl Sccache {
+    /// Start the server, which picks up the storage configuration from the environment.
+    ///
+    /// A server that is already running is stopped first, as it might use a different storage.
+    pub async fn restart_server(&self) -> Result {
+        if let Err(e) = self.cmd()?.arg("--stop-server").run_ok().await {
+            debug!("No sccache server was stopped: {e:?}");
+        }
+        self.cmd()?.arg("--start-server").run_ok().await
+    }
+
+    /// Get the current statistics from the server.
+    pub async fn stats(&self) -> Result<Stats> {
+        let output =
+            self.cmd()?.args(["--show-stats", "--stats-format", "json"]).run_stdout().await?;
+        Stats::from_json(&output).context("Failed to parse the sccache statistics.")
+    }
+}
+
+/// The sccache installation required by [`start`], with the hints on how to install it.
+pub fn requirement() -> Requirement {
+    Requirement::new(Sccache.executable_name())
+        .with_hint("Run `cargo install sccache --locked`.")
+        .with_hint_for(OS::MacOS, "Run `brew install sccache` or `cargo install sccache --locked`.")
+        .with_hint_for(
+            OS::Windows,
+            "Run `scoop install sccache` or `cargo install sccache --locked`.",
+        )
+}
+
+/// Set up sccache as the Rust compiler wrapper for all the Cargo invocations of this build.
+///
+/// Fails if sccache is not available or the storage is misconfigured.
+#[context("Failed to set up sccache as the compiler cache.")]
+pub async fn start() -> Result {
+    let storage = Storage::from_env()?;
+    prerequisites::check([&requirement()]).await.ensure_satisfied()?;
+    let executable = Sccache.lookup()?;
+    Sccache.restart_server().await?;
+    let baseline = Sccache.stats().await?;
+    info!("Using sccache with {storage} as the compiler cache.");
+    env::RUSTC_WRAPPER.set(&executable)?;
+    *BASELINE.lock().unwrap() = Some(baseline);
+    Ok(())
+}
+
+/// The cache statistics of this build, if sccache was [started](start).
+pub async fn report() -> Result<Option<Stats>> {
+    let Some(baseline) = BASELINE.lock().unwrap().take() else { return Ok(None) };
+    let stats = Sccache.stats().await?;
+    Ok(Some(stats.since(&baseline)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_since_baseline() -> Result {
+        let json = r#"{
+            "stats": {
+                "compile_requests": 40,
+                "requests_not_cacheable": 3,
+                "cache_hits": { "counts": { "Rust": 30, "C/C++": 2 }, "adv_counts": {} },
+                "cache_misses": { "counts": { "Rust": 4 }, "adv_counts": {} },
+                "cache_errors": { "counts": {}, "adv_counts": {} },
+                "non_cacheable_compilations": 1
+            },
+            "cache_location": "Local disk: \"/tmp/sccache\""
+        }"#;
+        let stats = Stats::from_json(json)?;
+        let baseline = Stats { compile_requests: 10, hits: 8, misses: 2, ..default() };
+        let build = stats.since(&baseline);
+        assert_eq!(build.hits, 24);
+        assert_eq!(build.misses, 2);
+        assert_eq!(build.not_cacheable, 4);
+        assert!(build.to_string().contains("24 hits, 2 misses (92.3% hit rate)"));
+        Ok(())
+    }
+}
@@ -59,7 +59,7 @@ define_env_var! {
     VCToolsRedistDir, PathBuf;
 
     /// The telemetry introduces undesired dependency on Power Shell.
-    VSCMD_SKIP_SENDTELEMETRY, bool;
+    VSCMD_SKIP_SENDTELEMETRY, flag;
 }
 
 /// Microsoft C/C++ Optimizing compiler.
@@ -1,6 +1,7 @@
 use crate::prelude::*;
 
 use crate::new_command_type;
+use crate::program::command::retry::RetryPolicy;
 use crate::program::command::Manipulator;
 use crate::programs::Cargo;
 
@@ -114,7 +115,9 @@ pub async fn install_if_missing() -> Result {
     //
     // Note that this will install the tool to the default system-wide location, not temp.
     if WasmPack.lookup().is_err() {
-        Cargo.cmd()?.args(["install", "wasm-pack"]).current_dir(temp.path()).run_ok().await?;
+        let mut install = Cargo.cmd()?;
+        install.args(["install", "wasm-pack"]).current_dir(temp.path());
+        install.run_ok_with_retries(RetryPolicy::default()).await?;
         // TODO
         //  this kind of function likely could use some generalization, that should also cover how
         //  PATH updates are handled
@@ -0,0 +1,321 @@
+//! Release notes generated from the commit history following the
+//! [Conventional Commits](https://www.conventionalcommits.org) convention.
+//!
+//! Commit subjects like `feat(gui)!: New node browser (#1234)` are parsed into [entries](Entry),
+//! grouped by their [kind](Kind) and component (the scope). The [`Changelog`] can be rendered as
+//! Markdown release notes, with the pull requests and commits linked, or stored as JSON, so the
+//! version bump step can use its [suggested bump](Changelog::bump).
+//!
+//! Commits not following the convention are listed as [other changes](Kind::Other).
+
+use crate::prelude::*;
+
+use crate::github::IsRepo;
+use crate::programs::git;
+use regex::Regex;
+use std::sync::LazyLock;
+
+
+
+/// Component of the entries without a scope.
+pub const GENERAL_COMPONENT: &str = "general";
+
+const CONVENTIONAL_SUBJECT_REGEX_CODE: &str =
+    r"^(?P<kind>[a-zA-Z]+)(?:\((?P<scope>[^)]*)\))?(?P<breaking>!)?:\s*(?P<description>.+)$";
+
+/// Subject of a conventional commit: `type(scope)!: description`.
+static CONVENTIONAL_SUBJECT: LazyLock<Regex> = LazyLock::new(||
+    // unwrap safe, as this is covered by test `parsing_entries`.
+    Regex::new(CONVENTIONAL_SUBJECT_REGEX_CODE).unwrap());
+
+/// Pull request reference added by GitHub to the subjects of squash-merged commits: `(#1234)`.
+static PULL_REQUEST_REFERENCE: LazyLock<Regex> = LazyLock::new(||
+    // unwrap safe, as this is covered by test `parsing_entries`.
+    Regex::new(r"\s*\(#(?P<number>\d+)\)").unwrap());
+
+
+
+// ============
+// === Kind ===
+// ============
+
+/// Type of a change, determining the section of the release notes it is listed in.
+///
+/// The variants are ordered as the sections.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    Feature,
+    Fix,
+    Performance,
+    Refactor,
+    Documentation,
+    Tests,
+    Build,
+    Chore,
+    Revert,
+    Other,
+}
+
+impl Kind {
+    /// The kind designated by the conventional commit type, like `feat` or `fix`.
+    pub fn from_commit_type(commit_type: &str) -> Self {
+        match commit_type.to_lowercase().as_str() {
+            "feat" | "feature" => Kind::Feature,
+            "fix" | "bugfix" => Kind::Fix,
+            "perf" => Kind::Performance,
+            "refactor" => Kind::Refactor,
+            "docs" | "doc" => Kind::Documentation,
+            "test" | "tests" => Kind::Tests,
+            "build" | "ci" => Kind::Build,
+            "chore" | "style" => Kind::Chore,
+            "revert" => Kind::Revert,
+            _ => Kind::Other,
+        }
+    }
+
+    /// Title of the release notes section.
+    pub fn title(self) -> &'static str {
+        match self {
+            Kind::Feature => "Features",
+            Kind::Fix => "Bug fixes",
+            Kind::Performance => "Performance improvements",
+            Kind::Refactor => "Refactoring",
+            Kind::Documentation => "Documentation",
+            Kind::Tests => "Tests",
+            Kind::Build => "Build and CI",
+            Kind::Chore => "Chores",
+            Kind::Revert => "Reverts",
+            Kind::Other => "Other changes",
+        }
+    }
+}
+
+
+
+// =============
+// === Entry ===
+// =============
+
+/// A single change, parsed from a commit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub kind:          Kind,
+    /// The component affected by the change, i.e. the conventional commit scope.
+    pub component:     Option<String>,
+    pub description:   String,
+    /// Whether the change is marked as breaking, by `!` or a `BREAKING CHANGE` footer.
+    pub breaking:      bool,
+    pub commit:        String,
+    /// Numbers of the pull requests referenced in the subject.
+    pub pull_requests: Vec<u64>,
+}
+
+impl Entry {
+    /// Parse the commit. Commits not following the convention are of the [`Kind::Other`].
+    pub fn from_commit(commit: &git::Commit) -> Self {
+        let pull_requests = PULL_REQUEST_REFERENCE
+            .captures_iter(&commit.subject)
+            .filter_map(|captures| captures["number"].parse().ok())
+            .collect();
+        let subject = PULL_REQUEST_REFERENCE.replace_all(&commit.subject, "");
+        let subject = subject.trim();
+        let has_breaking_footer = commit.body.lines().any(|line| {
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        });
+        let (kind, component, description, breaking) = match CONVENTIONAL_SUBJECT.captures(subject)
+        {
+            Some(captures) => (
+                Kind::from_commit_type(&captures["kind"]),
+                captures.name("scope").map(|scope| scope.as_str().trim().to_owned()),
+                captures["description"].trim().to_owned(),
+                captures.name("breaking").is_some(),
+            ),
+            None => (Kind::Other, None, subject.to_owned(), false),
+        };
+        let component = component.filter(|component| !component.is_empty());
+        Self {
+            kind,
+            component,
+            description,
+            breaking: breaking || has_breaking_footer,
+            commit: commit.hash.clone(),
+            pull_requests,
+        }
+    }
+
+    /// The component, or [`GENERAL_COMPONENT`] if the change has no scope.
+    pub fn component(&self) -> &str {
+        self.component.as_deref().unwrap_or(GENERAL_COMPONENT)
+    }
+
+    /// Markdown list item describing the change, with the pull requests or the commit linked.
+    pub fn to_markdown(&self, repo_url: &Url) -> String {
+        let description = uppercase_first(&self.description);
+        let links = if self.pull_requests.is_empty() {
+            let short_hash = self.commit.get(..7).unwrap_or(&self.commit);
+            format!("[{short_hash}]({repo_url}commit/{})", self.commit)
+        } else {
+            self.pull_requests.iter().map(|pr| format!("[#{pr}]({repo_url}pull/{pr})")).join(", ")
+        };
+        format!("- **{}:** {description} ({links})", self.component())
+    }
+}
+
+fn uppercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+
+
+// =================
+// === Changelog ===
+// =================
+
+/// Version bump suggested by the changes, following the semantic versioning.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Bump {
+    /// Only fixes and internal changes.
+    Patch,
+    /// New features.
+    Minor,
+    /// Breaking changes.
+    Major,
+}
+
+/// Machine-readable list of changes between two revisions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Changelog {
+    /// The revision the changes are listed since, if any.
+    pub from:    Option<String>,
+    /// The revision the changes are listed up to.
+    pub to:      String,
+    /// The version bump suggested by the changes.
+    pub bump:    Bump,
+    /// The changes, newest first.
+    pub entries: Vec<Entry>,
+}
+
+impl Changelog {
+    /// Describe the changes in the given commits.
+    pub fn new(from: Option<String>, to: String, commits: &[git::Commit]) -> Self {
+        let entries = commits.iter().map(Entry::from_commit).collect_vec();
+        let bump = suggested_bump(&entries);
+        Self { from, to, bump, entries }
+    }
+
+    /// Describe the changes between the revisions. If `from` is not given, the whole history up
+    /// to the `to` revision is used.
+    pub async fn from_history(git: &git::Context, from: Option<&str>, to: &str) -> Result<Self> {
+        let range = match from {
+            Some(from) => format!("{from}..{to}"),
+            None => to.to_owned(),
+        };
+        let commits = git.commits(&range).await?;
+        Ok(Self::new(from.map(Into::into), to.into(), &commits))
+    }
+
+    /// Render the release notes.
+    ///
+    /// Breaking changes are listed first, then the other changes grouped by their kind. Within
+    /// each section, the entries are ordered by their component.
+    pub fn to_markdown(&self, repo: &impl IsRepo) -> Result<String> {
+        let repo_url = repo.url()?;
+        let mut sections = BTreeMap::<Option<Kind>, Vec<&Entry>>::new();
+        for entry in &self.entries {
+            let section = (!entry.breaking).then_some(entry.kind);
+            sections.entry(section).or_default().push(entry);
+        }
+        let mut markdown = String::new();
+        for (section, entries) in sections {
+            let title = section.map_or("Breaking changes", Kind::title);
+            markdown.push_str(&format!("## {title}\n\n"));
+            for entry in entries.into_iter().sorted_by(|a, b| a.component().cmp(b.component())) {
+                markdown.push_str(&entry.to_markdown(&repo_url));
+                markdown.push('\n');
+            }
+            markdown.push('\n');
+        }
+        Ok(markdown)
+    }
+
+    /// Write the release notes as `release-notes.md` and the changelog as `changelog.json` into the
+    /// directory.
+    pub fn write(&self, repo: &impl IsRepo, directory: impl AsRef<Path>) -> Result {
+        let directory = directory.as_ref();
+        crate::fs::create_dir_if_missing(directory)?;
+        crate::fs::write(directory.join("release-notes.md"), self.to_markdown(repo)?)?;
+        crate::fs::write_json(directory.join("changelog.json"), self)
+    }
+}
+
+/// The most significant bump required by any of the changes.
+fn suggested_bump(entries: &[Entry]) -> Bump {
+    let bump_for = |entry: &Entry| match entry.kind {
+        _ if entry.breaking => Bump::Major,
+        Kind::Feature => Bump::Minor,
+        _ => Bump::Patch,
+    };
+    entries.iter().map(bump_for).max().unwrap_or(Bump::Patch)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::Repo;
+
+    fn commit(hash: &str, subject: &str, body: &str) -> git::Commit {
+        git::Commit { hash: hash.into(), subject: subject.into(), body: body.into() }
+    }
+
+    #[test]
+    fn parsing_entries() {
+        let entry = Entry::from_commit(&commit("a1", "feat(gui)!: new node browser (#1234)", ""));
+        assert_eq!(entry.kind, Kind::Feature);
+        assert_eq!(entry.component.as_deref(), Some("gui"));
+        assert_eq!(entry.description, "new node browser");
+        assert!(entry.breaking);
+        assert_eq!(entry.pull_requests, [1234]);
+
+        let entry = Entry::from_commit(&commit("a2", "fix: crash", "BREAKING CHANGE: removed."));
+        assert_eq!((entry.kind, entry.component(), entry.breaking), (Kind::Fix, "general", true));
+
+        let entry = Entry::from_commit(&commit("a3", "Update the README", ""));
+        assert_eq!((entry.kind, entry.description.as_str()), (Kind::Other, "Update the README"));
+    }
+
+    #[test]
+    fn release_notes() -> Result {
+        let repo = Repo::new("enso-org", "enso");
+        let commits = [
+            commit("0123456789", "fix(engine): wrong result (#12)", ""),
+            commit("abcdef0123", "feat(gui): dark theme", ""),
+            commit("fedcba9876", "chore: bump dependencies (#10) (#11)", ""),
+        ];
+        let changelog = Changelog::new(Some("v1".into()), "HEAD".into(), &commits);
+        assert_eq!(changelog.bump, Bump::Minor);
+        let expected = "\
+## Features
+
+- **gui:** Dark theme ([abcdef0](https://github.com/enso-org/enso/commit/abcdef0123))
+
+## Bug fixes
+
+- **engine:** Wrong result ([#12](https://github.com/enso-org/enso/pull/12))
+
+## Chores
+
+- **general:** Bump dependencies ([#10](https://github.com/enso-org/enso/pull/10), \
+[#11](https://github.com/enso-org/enso/pull/11))
+
+";
+        assert_eq!(changelog.to_markdown(&repo)?, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+//! Timing of the build steps.
+//!
+//! Steps are marked using the [`step!`](crate::step) macro (or the [`step`] function). Steps can be
+//! nested: a step run within another step's future is recorded as its child. Note that the
+//! nesting is tracked per task, so steps of a spawned task are recorded as top-level ones.
+//!
+//! At the end of a run, [`Report::take`] gathers the recorded steps. The report can be displayed
+//! as a summary table or serialized to JSON.
+
+use crate::prelude::*;
+
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+
+
+define_env_var! {
+    /// Path where the JSON timing report is written at the end of the run.
+    ENSO_BUILD_TIMING_REPORT, PathBuf;
+}
+
+tokio::task_local! {
+    /// Names of the steps enclosing the currently run future, from the outermost one.
+    static CURRENT_STEP: Vec<String>;
+}
+
+/// The moment the timing started, used as a reference point for the steps' start times.
+static START: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+/// Steps that have been completed and not yet taken into a report.
+static RECORDS: Mutex<Vec<StepRecord>> = Mutex::new(Vec::new());
+
+/// Run the future as a named build step, recording its timing.
+///
+/// The [`step!`](crate::step) macro is a more concise way of calling this function.
+pub async fn step<T>(
+    name: impl Into<String>,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let name = name.into();
+    let mut path = CURRENT_STEP.try_with(Clone::clone).unwrap_or_default();
+    path.push(name.clone());
+    let started = Instant::now();
+    let start = started.saturating_duration_since(*START);
+    let span = info_span!("Step", %name);
+    let result = CURRENT_STEP.scope(path.clone(), future.instrument(span)).await;
+    let duration = started.elapsed();
+    debug!("Step {name} took {duration:.1?}.");
+    let record = StepRecord { path, start, duration, succeeded: result.is_ok() };
+    RECORDS.lock().unwrap().push(record);
+    result
+}
+
+/// Run the future as a named build step, recording its timing.
+///
+/// The name can be a format string literal with inline arguments or any expression convertible
+/// into a `String`.
+///
+/// ```ignore
+/// step!("Build {target}", build(target)).await?;
+/// ```
+#[macro_export]
+macro_rules! step {
+    ($name:literal, $future:expr $(,)?) => {
+        $crate::timing::step(format!($name), $future)
+    };
+    ($name:expr, $future:expr $(,)?) => {
+        $crate::timing::step($name, $future)
+    };
+}
+
+
+
+// ==================
+// === StepRecord ===
+// ==================
+
+/// Timing of a single completed step.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StepRecord {
+    /// Names of the enclosing steps, followed by the name of this step.
+    pub path:      Vec<String>,
+    /// When the step started, relative to the start of the timing.
+    #[serde(with = "seconds")]
+    pub start:     Duration,
+    /// How long the step took.
+    #[serde(with = "seconds")]
+    pub duration:  Duration,
+    /// Whether the step completed successfully.
+    pub succeeded: bool,
+}
+
+impl StepRecord {
+    /// Name of the step.
+    pub fn name(&self) -> &str {
+        self.path.last().map_or("", String::as_str)
+    }
+
+    /// Nesting level of the step, 0 for the top-level steps.
+    pub fn depth(&self) -> usize {
+        self.path.len().saturating_sub(1)
+    }
+}
+
+/// Serialization of [`Duration`] as a floating-point number of seconds.
+mod seconds {
+    use super::*;
+
+    pub fn serialize<S: serde::Serializer>(
+        value: &Duration,
+        ser: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        value.as_secs_f64().serialize(ser)
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        de: D,
+    ) -> std::result::Result<Duration, D::Error> {
+        let seconds = f64::deserialize(de)?;
+        Duration::try_from_secs_f64(seconds).map_err(serde::de::Error::custom)
+    }
+}
+
+
+
+// ==============
+// === Report ===
+// ==============
+
+/// Timings of all the steps recorded during a run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// Wall time from the start of the timing until the report was taken.
+    #[serde(with = "seconds")]
+    pub total: Duration,
+    /// Recorded steps, ordered by their start time. Children follow their parents.
+    pub steps: Vec<StepRecord>,
+}
+
+impl Report {
+    /// Take all the steps recorded so far into a report.
+    pub fn take() -> Self {
+        let total = START.elapsed();
+        let steps = std::mem::take(&mut *RECORDS.lock().unwrap());
+        Self::new(total, steps)
+    }
+
+    /// Create a report from the given steps, ordering them.
+    pub fn new(total: Duration, mut steps: Vec<StepRecord>) -> Self {
+        // A child never starts before its parent, so ordering by the start time (and then by the
+        // depth, for simultaneous starts) puts the children after their parents.
+        steps.sort_by_key(|step| (step.start, step.depth()));
+        Self { total, steps }
+    }
+
+    /// Write the report as JSON to the given file.
+    pub fn write_json(&self, path: impl AsRef<Path>) -> Result {
+        crate::fs::create_parent_dir_if_missing(&path)?;
+        crate::fs::write_json(path, self)
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let label = |step: &StepRecord| {
+            let status = if step.succeeded { "" } else { " (failed)" };
+            format!("{}{}{status}", "  ".repeat(step.depth()), step.name())
+        };
+        let labels = self.steps.iter().map(label).collect_vec();
+        let width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0).max(4);
+        writeln!(f, "{:width$}  {:>10}  {:>6}", "Step", "Duration", "Share")?;
+        for (label, step) in labels.iter().zip(&self.steps) {
+            let share = if self.total.is_zero() {
+                0.0
+            } else {
+                100.0 * step.duration.as_secs_f64() / self.total.as_secs_f64()
+            };
+            let duration = format!("{:.1?}", step.duration);
+            writeln!(f, "{label:width$}  {duration:>10}  {share:>5.1}%")?;
+        }
+        let total = format!("{:.1?}", self.total);
+        writeln!(f, "{:width$}  {total:>10}", "Total")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn nested_steps() -> Result {
+        let index = 1;
+        let outer = step!("Outer", async {
+            step!("Inner {index}", async { Result::Ok(()) }).await?;
+            let failed = step!("Failing", async { Result::<()>::Err(anyhow!("Failure.")) }).await;
+            assert!(failed.is_err());
+            Ok(5)
+        });
+        assert_eq!(outer.await?, 5);
+
+        let report = Report::take();
+        let ours = report.steps.iter().filter(|step| step.path[0] == "Outer").collect_vec();
+        let paths = ours.iter().map(|step| step.path.join("/")).collect_vec();
+        assert_eq!(paths, ["Outer", "Outer/Inner 1", "Outer/Failing"]);
+        assert!(!ours[2].succeeded);
+
+        let json = serde_json::to_string(&report)?;
+        assert_eq!(serde_json::from_str::<Report>(&json)?.steps.len(), report.steps.len());
+        assert!(report.to_string().contains("\n  Failing (failed)"));
+        Ok(())
+    }
+}
@@ -176,6 +176,20 @@ pub struct Cli {
     #[clap(long, global = true, enso_env())]
     pub skip_npm_install: bool,
 
+    /// Run all the build steps, even if their inputs have not changed since their last success.
+    #[clap(long = "force", global = true, enso_env())]
+    pub force_rebuild: bool,
+
+    /// Use sccache as the Rust compiler cache. The cache storage is configured through the
+    /// `SCCACHE_*` environment variables.
+    #[clap(long, global = true, enso_env())]
+    pub sccache: bool,
+
+    /// On CI, upload the snapshot of the environment variables as an artifact, so it can be used
+    /// as a baseline for a local run. Values of secrets are masked.
+    #[clap(long, global = true, enso_env())]
+    pub upload_env_snapshot: bool,
+
     #[clap(subcommand)]
     pub target: Target,
 }
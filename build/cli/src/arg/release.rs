@@ -12,11 +12,42 @@ pub struct DeployRuntime {
 }
 
 /// Structure that represents `promote` subcommand arguments.
-#[derive(Args, Clone, Copy, Debug)]
+#[derive(Args, Clone, Debug)]
 pub struct Promote {
     /// What kind of version is to be created.
-    #[clap(value_enum)]
-    pub designation: enso_build::version::promote::Designation,
+    #[clap(value_enum, required_unless_present = "changelog")]
+    pub designation: Option<enso_build::version::promote::Designation>,
+    /// Changelog generated by the `release-notes` subcommand. If the designation is not given,
+    /// it is determined by the version bump suggested in the changelog.
+    #[clap(long)]
+    pub changelog:   Option<PathBuf>,
+}
+
+/// Structure that represents `release-notes` subcommand arguments.
+#[derive(Args, Clone, Debug)]
+pub struct ReleaseNotes {
+    /// The revision to list the changes since. Defaults to the latest stable release.
+    #[clap(long)]
+    pub from:   Option<String>,
+    /// The revision to list the changes up to.
+    #[clap(long, default_value = "HEAD")]
+    pub to:     String,
+    /// Directory to write the `release-notes.md` and `changelog.json` files into.
+    #[clap(long)]
+    pub output: PathBuf,
+}
+
+/// Structure that represents `verify-checksums` subcommand arguments.
+#[derive(Args, Clone, Debug)]
+pub struct VerifyChecksums {
+    /// Directory with the downloaded release assets and their `SHA256SUMS` manifest.
+    pub directory:         PathBuf,
+    /// Do not fail for the assets listed in the manifest but not downloaded.
+    #[clap(long)]
+    pub ignore_missing:    bool,
+    /// Fail if the manifest is not signed, instead of only warning about it.
+    #[clap(long)]
+    pub require_signature: bool,
 }
 
 #[derive(Subcommand, Clone, Debug)]
@@ -27,6 +58,10 @@ pub enum Action {
     DeployRuntime(DeployRuntime),
     Publish,
     Promote(Promote),
+    /// Generate the release notes and the changelog from the conventional commits.
+    ReleaseNotes(ReleaseNotes),
+    /// Verify the downloaded release assets against the (signed) checksum manifest.
+    VerifyChecksums(VerifyChecksums),
 }
 
 #[derive(Args, Clone, Debug)]
@@ -28,6 +28,7 @@ use crate::arg::Target;
 use crate::arg::WatchJob;
 use anyhow::Context;
 use arg::BuildDescription;
+use clap::CommandFactory;
 use clap::Parser;
 use enso_build::config::Config;
 use enso_build::context::BuildContext;
@@ -62,7 +63,14 @@ use enso_build::version;
 use futures_util::future::try_join;
 use ide_ci::actions::workflow::is_in_env;
 use ide_ci::cache::Cache;
+use ide_ci::dag::Graph;
+use ide_ci::dag::Step;
 use ide_ci::define_env_var;
+use ide_ci::env::profile::KnownVariables;
+use ide_ci::env::profile::Profile;
+use ide_ci::env::snapshot::Snapshot;
+use ide_ci::fingerprint::Fingerprint;
+use ide_ci::fingerprint::StateDatabase;
 use ide_ci::fs::remove_if_exists;
 use ide_ci::github::release;
 use ide_ci::github::setup_octocrab;
@@ -72,6 +80,7 @@ use ide_ci::programs::cargo;
 use ide_ci::programs::git;
 use ide_ci::programs::git::clean;
 use ide_ci::programs::rustc;
+use ide_ci::programs::sccache;
 use ide_ci::programs::Cargo;
 use octocrab::models::ReleaseId;
 use std::time::Duration;
@@ -87,6 +96,9 @@ define_env_var! {
     ENSO_BUILD_KIND, version::Kind;
 }
 
+/// Name of the file in the build's target directory, storing the fingerprints of the build steps.
+pub const BUILD_STATE_FILENAME: &str = "enso-build-state.json";
+
 /// The basic, common information available in this application.
 #[derive(Clone, Debug)]
 pub struct Processor {
@@ -111,7 +123,7 @@ impl Processor {
         let release_provider = || version::promote::releases_on_remote(&git);
         let versions = version::deduce_or_generate(release_provider).await?;
         let mut triple = TargetTriple::new(versions);
-        triple.os = cli.target_os;
+        triple.target = enso_build::target::Target::for_platform(cli.target_os, triple.target.arch);
         triple.versions.publish().await?;
         let context = BuildContext {
             inner: project::Context {
@@ -486,28 +498,24 @@ impl Processor {
     }
 
     /// Upload IDE assets from the build job to the given release.
+    ///
+    /// The assets keep their filenames, as these are referenced by the auto-update manifest.
     pub fn upload_ide_assets(
         &self,
         build_job: BoxFuture<'static, Result<ide::Artifact>>,
         release_id: ReleaseId,
-        name_prefix: Option<String>,
     ) -> BoxFuture<'static, Result> {
         let release = self.release(release_id);
-        let add_prefix = move |name: String| {
-            if let Some(prefix) = name_prefix.clone() {
-                format!("{prefix}-{name}")
-            } else {
-                name
-            }
-        };
         async move {
             let artifacts = build_job.await?;
-            release
-                .upload_asset_file_with_custom_name(&artifacts.image, add_prefix.clone())
-                .await?;
-            release
-                .upload_asset_file_with_custom_name(&artifacts.image_checksum, add_prefix)
-                .await?;
+            let packages = std::iter::once(&artifacts.image).chain(&artifacts.update_packages);
+            // The image's `.sha256` file is kept for the users checking a single download.
+            let files = packages.chain(std::iter::once(&artifacts.image_checksum));
+            for file in files.unique() {
+                let name = file.try_file_name()?.to_owned();
+                release.upload_asset_file_with_checksum_as(file, name).await?;
+            }
+            release.upload_asset_file(&artifacts.update_manifest).await?;
             Ok(())
         }
         .boxed()
@@ -518,7 +526,7 @@ impl Processor {
             arg::ide::Command::Build { params } => self.build_ide(params).void_ok().boxed(),
             arg::ide::Command::Upload { params, release_id } => {
                 let build_job = self.build_ide(params);
-                self.upload_ide_assets(build_job, release_id, None)
+                self.upload_ide_assets(build_job, release_id)
             }
         }
     }
@@ -571,7 +579,7 @@ impl Processor {
             artifact_name: "ide".into(),
         };
 
-        let target = Ide { target_os: self.triple.os, target_arch: self.triple.arch };
+        let target = Ide { target: self.triple.target };
         let artifact_name_prefix = input.artifact_name.clone();
         let build_job = target.build(&self.context, input, output_path);
         async move {
@@ -627,7 +635,7 @@ impl Resolvable for Runtime {
 
 impl Resolvable for Backend {
     fn prepare_target(context: &Processor) -> Result<Self> {
-        Ok(Backend { target_os: context.triple.os })
+        Ok(Backend { target_os: context.triple.target.os })
     }
 
     fn resolve(
@@ -672,14 +680,37 @@ pub async fn main_internal(config: Option<Config>) -> Result {
     trace!("Creating the build context.");
     debug!("Initial configuration for the CLI driver: {config:#?}");
 
+    // This must happen before the CLI arguments are parsed, as most of them can be provided through
+    // environment variables.
+    if let Some(repo_root) = arg::default_repo_path() {
+        load_env_profile(&repo_root)?;
+    }
+
     let cli = Cli::parse();
 
     debug!("Parsed CLI arguments: {cli:#?}");
 
+    ide_ci::program::command::log::set_default_root(
+        cli.repo_path.join("target").join("command-logs"),
+    );
+
+    // The report is only a diagnostic aid, so failing to produce it should not fail the build.
+    if let Err(e) = report_environment(cli.upload_env_snapshot).await {
+        warn!("Failed to report the build environment: {e:?}");
+    }
+
     if cli.skip_npm_install {
         enso_build::web::assume_installed();
     }
 
+    if cli.force_rebuild {
+        ide_ci::fingerprint::ENSO_BUILD_FORCE_REBUILD.set(&true)?;
+    }
+
+    if cli.sccache {
+        sccache::start().await?;
+    }
+
     if !cli.skip_version_check {
         // Let's be helpful!
         let error_message = "Program requirements were not fulfilled. Please do one of the \
@@ -695,13 +726,17 @@ pub async fn main_internal(config: Option<Config>) -> Result {
         remove_if_exists(cli.repo_path.join("ci-build"))?;
     }
 
-    let ctx: Processor = Processor::new(&cli).instrument(info_span!("Building context.")).await?;
+    let ctx: Processor = ide_ci::step!(
+        "Prepare build context",
+        Processor::new(&cli).instrument(info_span!("Building context."))
+    )
+    .await?;
     match cli.target {
-        Target::Wasm(wasm) => ctx.handle_wasm(wasm).await?,
-        Target::Gui(gui) => ctx.handle_gui(gui).await?,
-        Target::Runtime(runtime) => ctx.handle_runtime(runtime).await?,
-        Target::Backend(backend) => ctx.handle_backend(backend).await?,
-        Target::Ide(ide) => ctx.handle_ide(ide).await?,
+        Target::Wasm(wasm) => ide_ci::step!("WASM", ctx.handle_wasm(wasm)).await?,
+        Target::Gui(gui) => ide_ci::step!("GUI", ctx.handle_gui(gui)).await?,
+        Target::Runtime(runtime) => ide_ci::step!("Runtime", ctx.handle_runtime(runtime)).await?,
+        Target::Backend(backend) => ide_ci::step!("Backend", ctx.handle_backend(backend)).await?,
+        Target::Ide(ide) => ide_ci::step!("IDE", ctx.handle_ide(ide)).await?,
         Target::GitClean(options) => {
             let arg::git_clean::Options { dry_run, cache, build_script } = options;
             let mut exclusions = vec![".idea"];
@@ -717,41 +752,59 @@ pub async fn main_internal(config: Option<Config>) -> Result {
                 // prevents this from happening.
                 //
                 // Related npm issue: https://github.com/npm/npm/issues/19091
-                ide_ci::fs::tokio::remove_dir_if_exists(ctx.repo_root.join("node_modules")).await?;
+                ide_ci::fs::clean::remove_if_exists_async(ctx.repo_root.join("node_modules"))
+                    .await?;
             }
 
             let git_clean = clean::clean_except_for(&ctx.repo_root, exclusions, dry_run);
             let clean_cache = async {
                 if cache && !dry_run {
-                    ide_ci::fs::tokio::remove_dir_if_exists(ctx.cache.path()).await?;
+                    ide_ci::fs::clean::remove_if_exists_async(ctx.cache.path()).await?;
                 }
                 Result::Ok(())
             };
             try_join(git_clean, clean_cache).await?;
         }
         Target::Lint => {
-            Cargo
-                .cmd()?
-                .current_dir(&ctx.repo_root)
-                .arg(cargo::clippy::COMMAND)
-                .apply(&cargo::Options::Workspace)
-                .apply(&cargo::Options::Package("enso-integration-test".into()))
-                .apply(&cargo::Options::AllTargets)
-                .apply(&cargo::Color::Always)
-                .arg("--")
-                .apply(&rustc::Option::Deny(rustc::Lint::Warnings))
-                .run_ok()
-                .await?;
-
-            Cargo
-                .cmd()?
-                .current_dir(&ctx.repo_root)
-                .arg("fmt")
-                .args(["--", "--check"])
-                .run_ok()
+            let clippy = async {
+                Cargo
+                    .cmd()?
+                    .current_dir(&ctx.repo_root)
+                    .arg(cargo::clippy::COMMAND)
+                    .apply(&cargo::Options::Workspace)
+                    .apply(&cargo::Options::Package("enso-integration-test".into()))
+                    .apply(&cargo::Options::AllTargets)
+                    .apply(&cargo::Color::Always)
+                    .arg("--")
+                    .apply(&rustc::Option::Deny(rustc::Lint::Warnings))
+                    .run_ok()
+                    .await
+            };
+            let fmt = async {
+                Cargo
+                    .cmd()?
+                    .current_dir(&ctx.repo_root)
+                    .arg("fmt")
+                    .args(["--", "--check"])
+                    .run_ok()
+                    .await
+            };
+            let enso_linter = enso_build::rust::enso_linter::lint_all(ctx.repo_root.clone());
+
+            // The checks are independent, so they are run concurrently. Each of them is skipped if
+            // its inputs have not changed since it last passed.
+            let state = StateDatabase::new(ctx.repo_root.target.join(BUILD_STATE_FILENAME));
+            let [clippy_inputs, fmt_inputs, enso_linter_inputs] = lint_fingerprints(&ctx).await?;
+            let step = |name: &str, action, inputs| {
+                Step::new(name, action).with_fingerprint(ok_ready_boxed(inputs))
+            };
+            Graph::new()?
+                .with_state(state)
+                .with(step("Clippy", clippy.boxed(), clippy_inputs))
+                .with(step("Rust formatting check", fmt.boxed(), fmt_inputs))
+                .with(step("Enso linter", enso_linter.boxed(), enso_linter_inputs))
+                .run()
                 .await?;
-
-            enso_build::rust::enso_linter::lint_all(ctx.repo_root.clone()).await?;
         }
         Target::Fmt => {
             enso_build::web::install(&ctx.repo_root).await?;
@@ -778,9 +831,29 @@ pub async fn main_internal(config: Option<Config>) -> Result {
                 enso_build::release::publish_release(&ctx).await?;
             }
             Action::Promote(args) => {
-                let arg::release::Promote { designation } = args;
+                let arg::release::Promote { designation, changelog } = args;
+                let designation = match (designation, changelog) {
+                    (Some(designation), _) => designation,
+                    (None, Some(changelog)) => {
+                        let changelog: ide_ci::release_notes::Changelog =
+                            ide_ci::fs::read_json(changelog)?;
+                        info!("Changelog suggests a {:?} version bump.", changelog.bump);
+                        version::promote::Designation::for_bump(changelog.bump)
+                    }
+                    (None, None) => bail!("Either the designation or the changelog is required."),
+                };
                 enso_build::release::promote_release(&ctx, designation).await?;
             }
+            Action::ReleaseNotes(args) => {
+                let arg::release::ReleaseNotes { from, to, output } = args;
+                enso_build::release::generate_release_notes(&ctx, from, &to, &output).await?;
+            }
+            Action::VerifyChecksums(args) => {
+                let arg::release::VerifyChecksums { directory, ignore_missing, require_signature } =
+                    args;
+                ide_ci::checksums::verify_directory(directory, ignore_missing, require_signature)
+                    .await?;
+            }
         },
         Target::JavaGen(command) => {
             let repo_root = ctx.repo_root.clone();
@@ -813,11 +886,195 @@ pub async fn main_internal(config: Option<Config>) -> Result {
     Ok(())
 }
 
+/// Load the `.env` files from the repository root into the environment.
+///
+/// See [`ide_ci::env::profile`] for the details on the files and their layering.
+pub fn load_env_profile(repo_root: &Path) -> Result {
+    fn add_cli_variables(command: &clap::Command, known: &mut KnownVariables) {
+        for variable in command.get_arguments().filter_map(|arg| arg.get_env()) {
+            known.add_name(variable.to_string_lossy());
+        }
+        for subcommand in command.get_subcommands() {
+            add_cli_variables(subcommand, known);
+        }
+    }
+
+    let mut known = KnownVariables::default();
+    add_cli_variables(&Cli::command(), &mut known);
+    for definition in ide_ci::env::definitions() {
+        known.add_name(definition.name);
+    }
+    known
+        .add(ide_ci::env::profile::ENSO_BUILD_ENV_PROFILE)
+        .add(ide_ci::github::GITHUB_TOKEN)
+        .add(enso_build::paths::ENSO_DATA_DIRECTORY)
+        .add(ide_ci::env::snapshot::ENSO_BUILD_ENV_SNAPSHOT_BASELINE)
+        .add(ide_ci::timing::ENSO_BUILD_TIMING_REPORT)
+        .add_name("ENSO_BUILD_LOG");
+    Profile::standard(repo_root)?.apply(&known)
+}
+
+/// Capture the environment of this build step and compare it against the baseline snapshot, if
+/// one was provided through [`ENSO_BUILD_ENV_SNAPSHOT_BASELINE`].
+///
+/// If `upload` is set and running on CI, the snapshot and the diff are uploaded as an artifact, so
+/// they can be used as a baseline for a local run.
+///
+/// [`ENSO_BUILD_ENV_SNAPSHOT_BASELINE`]: ide_ci::env::snapshot::ENSO_BUILD_ENV_SNAPSHOT_BASELINE
+pub async fn report_environment(upload: bool) -> Result {
+    let baseline = ide_ci::env::snapshot::ENSO_BUILD_ENV_SNAPSHOT_BASELINE.get_opt()?;
+    let upload = upload && is_in_env();
+    if baseline.is_none() && !upload {
+        return Ok(());
+    }
+    let snapshot = Snapshot::capture();
+    let diff = match baseline {
+        Some(baseline_path) => {
+            let baseline = Snapshot::read(&baseline_path)?;
+            let diff = snapshot.diff(&baseline);
+            info!("Environment compared to {}:\n{diff}", baseline_path.display());
+            Some(diff)
+        }
+        None => None,
+    };
+    if upload {
+        let job = ide_ci::actions::env::GITHUB_JOB.get()?;
+        let name = format!("environment-{job}-{TARGET_OS}-{}", std::process::id());
+        let directory = ide_ci::actions::env::RUNNER_TEMP.get()?.join(&name);
+        ide_ci::fs::create_dir_if_missing(&directory)?;
+        snapshot.write(directory.join("snapshot.json"))?;
+        if let Some(diff) = diff {
+            ide_ci::fs::write_json(directory.join("diff.json"), &diff)?;
+        }
+        ide_ci::actions::artifacts::upload_directory_if_exists(directory, name).await?;
+    }
+    Ok(())
+}
+
+/// Upload the logs of the commands run by this build script process as an artifact.
+pub async fn upload_command_logs() -> Result {
+    let directory = ide_ci::program::command::log::log_directory()?;
+    if !directory.exists() {
+        return Ok(());
+    }
+    let job = ide_ci::actions::env::GITHUB_JOB.get()?;
+    let name = format!("command-logs-{job}-{TARGET_OS}-{}", std::process::id());
+    ide_ci::actions::artifacts::upload_directory_if_exists(directory, name).await
+}
+
+/// Print the timings of the build steps and store them as JSON.
+///
+/// The JSON report is written to [`ENSO_BUILD_TIMING_REPORT`] if set. On CI, the table is also
+/// added to the job summary.
+///
+/// [`ENSO_BUILD_TIMING_REPORT`]: ide_ci::timing::ENSO_BUILD_TIMING_REPORT
+pub async fn report_timings() -> Result {
+    let report = ide_ci::timing::Report::take();
+    if report.steps.is_empty() {
+        return Ok(());
+    }
+    info!("Build step timings:\n{report}");
+    if let Some(path) = ide_ci::timing::ENSO_BUILD_TIMING_REPORT.get_opt()? {
+        report.write_json(path)?;
+    }
+    if is_in_env() {
+        let summary = format!("### Build step timings\n```\n{report}```");
+        ide_ci::actions::env_file::GITHUB_STEP_SUMMARY.append_line(summary).await?;
+    }
+    Ok(())
+}
+
+/// Print why the fingerprinted build steps were skipped or run.
+pub fn report_incremental_build() {
+    let report = ide_ci::fingerprint::Report::take();
+    if !report.decisions.is_empty() {
+        info!("Incremental build decisions:\n{report}");
+    }
+}
+
+/// Fingerprint of the Rust sources in the repository and of the Rust toolchain and its
+/// configuration.
+pub async fn rust_fingerprint(ctx: &Processor) -> Result<Fingerprint> {
+    const RUST_CONFIG_FILES: [&str; 3] = ["Cargo.toml", "Cargo.lock", "rust-toolchain.toml"];
+    let cargo_version = Cargo.version().await?;
+    let repo_root = ctx.repo_root.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let is_rust_input = |path: &Path| {
+            let name = path.file_name().and_then(|name| name.to_str());
+            let is_config = name.is_some_and(|name| RUST_CONFIG_FILES.contains(&name));
+            let is_source = path.extension().is_some_and(|extension| extension == "rs");
+            is_config || is_source
+        };
+        Fingerprint::new()
+            .with_tool_version("cargo", cargo_version)
+            .with_env("RUSTFLAGS")
+            .with_optional_file(repo_root.join(".cargo").join("config.toml"))?
+            .with_tree(&repo_root, is_rust_input)
+    })
+    .await?
+}
+
+/// Fingerprints of the inputs of the [`Target::Lint`] checks: Clippy, the Rust formatting check
+/// and the Enso linter, respectively.
+pub async fn lint_fingerprints(ctx: &Processor) -> Result<[Fingerprint; 3]> {
+    let rust = rust_fingerprint(ctx).await?;
+    let repo_root = ctx.repo_root.to_path_buf();
+    let enso_roots =
+        [ctx.repo_root.distribution.lib.to_path_buf(), ctx.repo_root.test.to_path_buf()];
+    tokio::task::spawn_blocking(move || {
+        let clippy = rust.clone().with_optional_file(repo_root.join("clippy.toml"))?;
+        let fmt = rust.clone().with_optional_file(repo_root.join("rustfmt.toml"))?;
+        let is_enso_source = |path: &Path| {
+            let is_linted = enso_roots.iter().any(|root| path.starts_with(root));
+            is_linted && path.extension().is_some_and(|extension| extension == "enso")
+        };
+        // The linter is built from the Rust sources, so they are its inputs too.
+        let enso_linter = rust.with_tree(&repo_root, is_enso_source)?;
+        Ok([clippy, fmt, enso_linter])
+    })
+    .await?
+}
+
+/// Print the compiler cache statistics of this build, if sccache was used.
+///
+/// On CI, the statistics are also added to the job summary.
+pub async fn report_compiler_cache() -> Result {
+    let Some(stats) = sccache::report().await? else { return Ok(()) };
+    info!("Compiler cache: {stats}.");
+    if is_in_env() {
+        let summary = format!("### Compiler cache\n{stats}.");
+        ide_ci::actions::env_file::GITHUB_STEP_SUMMARY.append_line(summary).await?;
+    }
+    Ok(())
+}
+
 pub fn lib_main(config: Option<Config>) -> Result {
     trace!("Starting the tokio runtime.");
     let rt = tokio::runtime::Runtime::new()?;
     trace!("Entering main.");
-    rt.block_on(async { main_internal(config).await })?;
+    rt.block_on(async {
+        let result = main_internal(config).await;
+        // The timings are especially useful when the build failed, so they are reported anyway.
+        if let Err(e) = report_timings().await {
+            warn!("Failed to report the build step timings: {e:?}");
+        }
+        report_incremental_build();
+        if let Err(e) = report_compiler_cache().await {
+            warn!("Failed to report the compiler cache statistics: {e:?}");
+        }
+        // The logs of the failed commands are kept, as they are referenced in the error. On CI,
+        // they would be lost with the runner, so they are uploaded.
+        if result.is_ok() {
+            if let Err(e) = ide_ci::program::command::log::remove_default_log_directory() {
+                warn!("Failed to remove the command logs: {e:?}");
+            }
+        } else if is_in_env() {
+            if let Err(e) = upload_command_logs().await {
+                warn!("Failed to upload the command logs: {e:?}");
+            }
+        }
+        result
+    })?;
     rt.shutdown_timeout(Duration::from_secs(60 * 30));
     info!("Successfully ending.");
     Ok(())
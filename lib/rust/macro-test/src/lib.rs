@@ -0,0 +1,265 @@
+//! Snapshot tests of the procedural macros.
+//!
+//! A fixture is a Rust file with the items a macro is applied to, stored in a directory like
+//! `tests/expand` (not directly in `tests`, so Cargo does not compile it as an integration test).
+//! [`check_derive`] expands the macro on every item of every fixture and compares the
+//! [pretty-printed](pretty_print) expansion with the snapshot stored next to the fixture, e.g.
+//! `tests/expand/enum.expanded.rs` for `tests/expand/enum.rs`. Missing snapshots are created,
+//! except on CI (when the [`CI`](CI_VAR) variable is set), where they fail the test.
+//!
+//! After an intended change of the expansion, run the tests with
+//! [`ENSO_MACRO_TEST_OVERWRITE`](OVERWRITE_VAR) set to accept the new expansions, and review the
+//! changed snapshots like any other change.
+//!
+//! The macro is tested as a function on [`TokenStream`]s, so the proc-macro crate should implement
+//! its macros as such functions, wrapped by the thin `#[proc_macro_derive]` entry points:
+//!
+//! ```ignore
+//! #[proc_macro_derive(Visitor)]
+//! pub fn derive_visitor(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+//!     derive(input.into()).into()
+//! }
+//!
+//! #[cfg(test)]
+//! mod tests {
+//!     #[test]
+//!     fn expansions() {
+//!         enso_macro_test::check_derive("tests/expand", super::derive);
+//!     }
+//! }
+//! ```
+
+// === Non-Standard Linter Configuration ===
+#![warn(missing_docs)]
+
+use proc_macro2::TokenStream;
+use std::fmt::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// Environment variable which, when set to a non-empty value, makes the tests overwrite the
+/// snapshots with the current expansions instead of comparing them.
+pub const OVERWRITE_VAR: &str = "ENSO_MACRO_TEST_OVERWRITE";
+
+/// Environment variable set on CI, where the missing snapshots fail the tests instead of being
+/// created, so a forgotten snapshot is not silently accepted.
+pub const CI_VAR: &str = "CI";
+
+/// Extension of the fixture files.
+pub const FIXTURE_EXTENSION: &str = "rs";
+
+/// Extension of the snapshot files, replacing the fixture's extension.
+pub const SNAPSHOT_EXTENSION: &str = "expanded.rs";
+
+/// Number of the unchanged lines shown around the first difference in a failure report.
+const DIFF_CONTEXT: usize = 3;
+
+
+
+// =======================
+// === Pretty Printing ===
+// =======================
+
+/// Format the tokens like `rustfmt` would.
+///
+/// The tokens that are not a valid Rust file are printed as they are, so a broken expansion still
+/// produces a readable snapshot.
+pub fn pretty_print(tokens: TokenStream) -> String {
+    match syn::parse2::<syn::File>(tokens.clone()) {
+        Ok(file) => prettyplease::unparse(&file),
+        Err(_) => format!("{tokens}\n"),
+    }
+}
+
+/// Expand the derive on every item of the fixture source, and pretty-print the expansions.
+pub fn expand_derive(source: &str, derive: impl Fn(TokenStream) -> TokenStream) -> String {
+    let file = match syn::parse_file(source) {
+        Ok(file) => file,
+        Err(error) => panic!("Invalid fixture: {error}."),
+    };
+    let expansions = file.items.into_iter().map(|item| derive(syn::parse_quote!(#item)));
+    pretty_print(expansions.collect())
+}
+
+
+
+// ================
+// === Fixtures ===
+// ================
+
+/// Paths of the fixtures in the directory, in alphabetical order.
+fn fixtures(dir: &Path) -> Vec<PathBuf> {
+    let entries = std::fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Failed to read the fixtures in {}: {e}", dir.display()));
+    let mut fixtures: Vec<PathBuf> = entries
+        .map(|entry| entry.expect("Failed to read a fixture directory entry.").path())
+        .filter(|path| is_fixture(path))
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+fn is_fixture(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let extension = format!(".{FIXTURE_EXTENSION}");
+    let snapshot_extension = format!(".{SNAPSHOT_EXTENSION}");
+    name.ends_with(&extension) && !name.ends_with(&snapshot_extension)
+}
+
+/// Path of the snapshot of the fixture.
+pub fn snapshot_path(fixture: &Path) -> PathBuf {
+    fixture.with_extension(SNAPSHOT_EXTENSION)
+}
+
+/// Resolve the fixture directory relative to the tested crate.
+fn fixture_dir(dir: &Path) -> PathBuf {
+    match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(manifest_dir) if dir.is_relative() => Path::new(&manifest_dir).join(dir),
+        _ => dir.to_owned(),
+    }
+}
+
+fn is_set(var: &str) -> bool {
+    std::env::var_os(var).is_some_and(|value| !value.is_empty())
+}
+
+
+
+// ============
+// === Mode ===
+// ============
+
+/// How the snapshots are treated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    /// Compare the expansions with the snapshots, creating the missing ones.
+    Compare,
+    /// Compare the expansions with the snapshots, failing on the missing ones.
+    Strict,
+    /// Overwrite the snapshots with the expansions.
+    Overwrite,
+}
+
+impl Mode {
+    /// The mode selected by the [`OVERWRITE_VAR`] and [`CI_VAR`] variables.
+    fn from_env() -> Self {
+        if is_set(OVERWRITE_VAR) {
+            Mode::Overwrite
+        } else if is_set(CI_VAR) {
+            Mode::Strict
+        } else {
+            Mode::Compare
+        }
+    }
+}
+
+
+
+// ================
+// === Checking ===
+// ================
+
+/// Compare the expansions of the derive on the fixtures in the directory with their snapshots.
+///
+/// A relative directory is resolved against the tested crate's manifest directory. All the
+/// fixtures are checked before failing, and the failure lists every mismatching snapshot.
+pub fn check_derive(dir: impl AsRef<Path>, derive: impl Fn(TokenStream) -> TokenStream) {
+    let dir = fixture_dir(dir.as_ref());
+    let fixtures = fixtures(&dir);
+    assert!(!fixtures.is_empty(), "No fixtures found in {}.", dir.display());
+    let mode = Mode::from_env();
+    let mut failures = String::new();
+    for fixture in fixtures {
+        let source = read(&fixture);
+        let expansion = expand_derive(&source, &derive);
+        if let Err(failure) = check_snapshot(&snapshot_path(&fixture), &expansion, mode) {
+            writeln!(failures, "{failure}").unwrap();
+        }
+    }
+    if !failures.is_empty() {
+        panic!("{failures}Run the tests with {OVERWRITE_VAR}=1 to accept the new expansions.");
+    }
+}
+
+/// Compare the expansion with the snapshot, creating or overwriting it as the mode allows.
+fn check_snapshot(snapshot: &Path, expansion: &str, mode: Mode) -> Result<(), String> {
+    let expected = snapshot.exists().then(|| read(snapshot));
+    match (expected, mode) {
+        (Some(expected), Mode::Compare | Mode::Strict) =>
+            match first_difference(&expected, expansion) {
+                None => Ok(()),
+                Some(diff) =>
+                    Err(format!("Expansion does not match {}:\n{diff}", snapshot.display())),
+            },
+        (None, Mode::Strict) => Err(format!("Missing snapshot {}.", snapshot.display())),
+        _ => {
+            std::fs::write(snapshot, expansion)
+                .unwrap_or_else(|e| panic!("Failed to write {}: {e}", snapshot.display()));
+            Ok(())
+        }
+    }
+}
+
+fn read(path: &Path) -> String {
+    std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()))
+        .replace("\r\n", "\n")
+}
+
+/// Describe the first differing line of the texts, with a few lines of context.
+fn first_difference(expected: &str, actual: &str) -> Option<String> {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    let index = (0..expected.len().max(actual.len()))
+        .find(|&index| expected.get(index) != actual.get(index))?;
+    let start = index.saturating_sub(DIFF_CONTEXT);
+    let mut diff = String::new();
+    for line in &expected[start.min(expected.len())..index.min(expected.len())] {
+        writeln!(diff, "   {line}").unwrap();
+    }
+    let end = |lines: &[&str]| (index + DIFF_CONTEXT + 1).min(lines.len());
+    for line in expected.get(index..end(&expected)).unwrap_or_default() {
+        writeln!(diff, " - {line}").unwrap();
+    }
+    for line in actual.get(index..end(&actual)).unwrap_or_default() {
+        writeln!(diff, " + {line}").unwrap();
+    }
+    Some(diff)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn printing_and_diffing() {
+        let tokens: TokenStream = syn::parse_quote!(impl X for Y { fn f(&self) { g(); } });
+        assert_eq!(
+            pretty_print(tokens),
+            "impl X for Y {\n    fn f(&self) {\n        g();\n    }\n}\n"
+        );
+        assert_eq!(pretty_print(syn::parse_quote!(not valid)), "not valid\n");
+
+        assert_eq!(first_difference("a\nb\n", "a\nb"), None);
+        assert_eq!(
+            first_difference("a\nb\nc", "a\nx\nc").unwrap(),
+            "   a\n - b\n - c\n + x\n + c\n"
+        );
+        assert_eq!(first_difference("a", "a\nb").unwrap(), "   a\n + b\n");
+    }
+
+    #[test]
+    fn missing_snapshot_on_ci() {
+        let snapshot = Path::new("missing.expanded.rs");
+        let failure = check_snapshot(snapshot, "struct S;\n", Mode::Strict).unwrap_err();
+        assert_eq!(failure, "Missing snapshot missing.expanded.rs.");
+        assert!(!snapshot.exists());
+    }
+}
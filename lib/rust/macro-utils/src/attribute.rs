@@ -0,0 +1,216 @@
+//! Parsing of the helper attribute arguments, like `#[visitor(skip, rename = "name")]`.
+//!
+//! The arguments are collected from all the attributes with the given name into [`Arguments`].
+//! The macro then extracts the arguments it supports as [flags](Arguments::flag) or typed
+//! [values](Arguments::value), and [finishes](Arguments::finish) the parsing, which reports the
+//! arguments it did not ask for. All the errors are spanned, so the compiler points at the
+//! offending argument.
+
+use proc_macro2::Span;
+use quote::ToTokens;
+use syn::parse::Parse;
+use syn::spanned::Spanned;
+
+
+
+// =================
+// === Arguments ===
+// =================
+
+/// A single `key` or `key = value` argument.
+#[derive(Debug)]
+struct Argument {
+    key:   syn::Ident,
+    value: Option<syn::Expr>,
+    used:  bool,
+}
+
+/// Arguments of the helper attributes with a given name.
+#[derive(Debug)]
+pub struct Arguments {
+    attribute: String,
+    /// Span of the first attribute, used by errors not related to any particular argument.
+    span:      Span,
+    arguments: Vec<Argument>,
+    /// The keys the macro asked for, listed when an unknown argument is reported.
+    known:     Vec<&'static str>,
+}
+
+impl Arguments {
+    /// Collect the arguments of all the `#[attribute(...)]` attributes. Other attributes are
+    /// ignored.
+    ///
+    /// A key given more than once is reported as an error.
+    pub fn parse(attribute: &str, attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let matching = attrs.iter().filter(|attr| attr.path().is_ident(attribute));
+        let mut arguments = Self {
+            attribute: attribute.to_owned(),
+            span:      Span::call_site(),
+            arguments: Vec::new(),
+            known:     Vec::new(),
+        };
+        for (index, attr) in matching.enumerate() {
+            if index == 0 {
+                arguments.span = attr.span();
+            }
+            match &attr.meta {
+                syn::Meta::Path(_) => {}
+                syn::Meta::List(list) => list.parse_nested_meta(|meta| arguments.push(meta))?,
+                syn::Meta::NameValue(_) => {
+                    let message = format!("Expected `#[{attribute}(...)]`.");
+                    return Err(syn::Error::new_spanned(attr, message));
+                }
+            }
+        }
+        Ok(arguments)
+    }
+
+    fn push(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        let key = meta.path.get_ident().cloned().ok_or_else(|| {
+            meta.error(format!("Expected an argument name of `#[{}]`.", self.attribute))
+        })?;
+        if self.arguments.iter().any(|argument| argument.key == key) {
+            return Err(syn::Error::new(key.span(), format!("Duplicate argument `{key}`.")));
+        }
+        let value = if meta.input.peek(syn::Token![=]) {
+            Some(meta.value()?.parse()?)
+        } else if meta.input.is_empty() || meta.input.peek(syn::Token![,]) {
+            None
+        } else {
+            return Err(meta.error(format!("Expected `{key}` or `{key} = value`.")));
+        };
+        self.arguments.push(Argument { key, value, used: false });
+        Ok(())
+    }
+
+    /// Span of the first attribute, or the call site if there is none.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    fn take(&mut self, key: &'static str) -> Option<&Argument> {
+        self.known.push(key);
+        let argument = self.arguments.iter_mut().find(|argument| argument.key == key)?;
+        argument.used = true;
+        Some(argument)
+    }
+
+    /// Whether the `key` argument is present. Fails if it is given a value.
+    pub fn flag(&mut self, key: &'static str) -> syn::Result<bool> {
+        match self.take(key) {
+            None => Ok(false),
+            Some(Argument { value: None, .. }) => Ok(true),
+            Some(Argument { value: Some(value), .. }) => {
+                let message = format!("Argument `{key}` does not take a value.");
+                Err(syn::Error::new_spanned(value, message))
+            }
+        }
+    }
+
+    /// Value of the `key = value` argument, if present.
+    ///
+    /// The value is parsed as `T`. If that fails and the value is a string literal, its contents
+    /// are parsed instead, so e.g. `bound = "T: Clone"` can be read as a [`syn::WherePredicate`].
+    pub fn value<T: Parse>(&mut self, key: &'static str) -> syn::Result<Option<T>> {
+        let Some(argument) = self.take(key) else { return Ok(None) };
+        let Some(value) = &argument.value else {
+            let message = format!("Argument `{key}` requires a value: `{key} = value`.");
+            return Err(syn::Error::new(argument.key.span(), message));
+        };
+        match syn::parse2(value.to_token_stream()) {
+            Ok(value) => Ok(Some(value)),
+            Err(error) => match value {
+                syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) =>
+                    lit.parse().map(Some),
+                _ => Err(error),
+            },
+        }
+    }
+
+    /// Value of the `key = value` argument. Fails if it is not present.
+    pub fn required<T: Parse>(&mut self, key: &'static str) -> syn::Result<T> {
+        let span = self.span;
+        let attribute = self.attribute.clone();
+        self.value(key)?.ok_or_else(|| {
+            let message = format!("Missing required argument `#[{attribute}({key} = ...)]`.");
+            syn::Error::new(span, message)
+        })
+    }
+
+    /// Finish the parsing, reporting the arguments that the macro did not ask for.
+    pub fn finish(self) -> syn::Result<()> {
+        let expected = match self.known.as_slice() {
+            [] => format!("`#[{}]` takes no arguments", self.attribute),
+            known => {
+                let known = known.iter().map(|key| format!("`{key}`")).collect::<Vec<_>>();
+                format!("expected one of: {}", known.join(", "))
+            }
+        };
+        let errors = self.arguments.iter().filter(|argument| !argument.used).map(|argument| {
+            let key = &argument.key;
+            let message =
+                format!("Unknown argument `{key}` of `#[{}]`, {expected}.", self.attribute);
+            syn::Error::new(key.span(), message)
+        });
+        errors
+            .reduce(|mut errors, error| {
+                errors.combine(error);
+                errors
+            })
+            .map_or(Ok(()), Err)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(input: syn::DeriveInput) -> Arguments {
+        Arguments::parse("visitor", &input.attrs).unwrap()
+    }
+
+    #[test]
+    fn typed_extraction() {
+        let mut args = arguments(syn::parse_quote! {
+            #[derive(Visitor)]
+            #[visitor(skip, rename = "Visit")]
+            #[visitor(bound = "T: Clone", depth = 3)]
+            struct Node<T>(T);
+        });
+        assert!(args.flag("skip").unwrap());
+        assert!(!args.flag("transparent").unwrap());
+        assert_eq!(args.required::<syn::LitStr>("rename").unwrap().value(), "Visit");
+        let bound: syn::WherePredicate = args.required("bound").unwrap();
+        assert_eq!(bound.to_token_stream().to_string(), "T : Clone");
+        assert_eq!(args.value::<syn::LitInt>("depth").unwrap().unwrap().base10_digits(), "3");
+        assert!(args.value::<syn::Ident>("trait_name").unwrap().is_none());
+        args.finish().unwrap();
+    }
+
+    #[test]
+    fn diagnostics() {
+        let mut args = arguments(syn::parse_quote! {
+            #[visitor(skip = true, unknown)]
+            struct Node;
+        });
+        assert!(args.flag("skip").is_err());
+        assert!(args.required::<syn::Ident>("name").is_err());
+        let error = args.finish().unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Unknown argument `unknown` of `#[visitor]`, expected one of: `skip`, `name`."
+        );
+
+        let input: syn::DeriveInput = syn::parse_quote! {
+            #[visitor(skip, skip)]
+            struct Node;
+        };
+        assert!(Arguments::parse("visitor", &input.attrs).is_err());
+        assert!(arguments(syn::parse_quote!(
+            struct Node;
+        ))
+        .finish()
+        .is_ok());
+    }
+}
@@ -0,0 +1,139 @@
+//! Generics of the impls generated by derive macros.
+//!
+//! A generated impl usually needs more than the generics of the type it is derived for, e.g. an
+//! extra lifetime of the implemented trait, or bounds on the type parameters. [`ImplGenerics`]
+//! merges them into valid generics, rather than splicing the token streams by hand.
+
+use syn::parse_quote;
+use syn::GenericParam;
+use syn::Generics;
+
+
+
+// ====================
+// === ImplGenerics ===
+// ====================
+
+/// Generics of a type, extended with the parameters and bounds required by a generated impl.
+///
+/// ```
+/// # use enso_macro_utils::generics::ImplGenerics;
+/// # use quote::quote;
+/// let input: syn::DeriveInput = syn::parse_quote!(struct Node<'s, T>(&'s T););
+/// let generics = ImplGenerics::new(&input.generics)
+///     .lifetime(syn::parse_quote!('s))
+///     .lifetime(syn::parse_quote!('a))
+///     .bound_type_params(syn::parse_quote!(Visitable<'s, 'a>));
+/// let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+/// let output = quote!(impl #impl_generics Visitable<'s, 'a> for Node #ty_generics #where_clause {});
+/// let expected = quote! {
+///     impl<'s, 'a, T> Visitable<'s, 'a> for Node<'s, T> where T: Visitable<'s, 'a> {}
+/// };
+/// assert_eq!(output.to_string(), expected.to_string());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ImplGenerics {
+    /// The generics of the type.
+    base:   Generics,
+    /// The generics of the impl.
+    merged: Generics,
+}
+
+impl ImplGenerics {
+    /// Start with the generics of the type.
+    pub fn new(base: &Generics) -> Self {
+        Self { base: base.clone(), merged: base.clone() }
+    }
+
+    /// Add a lifetime parameter, after the other lifetimes.
+    ///
+    /// If the type already declares the lifetime, its bounds are extended instead.
+    pub fn lifetime(mut self, param: syn::LifetimeParam) -> Self {
+        let existing = self.merged.lifetimes_mut().find(|lt| lt.lifetime == param.lifetime);
+        match existing {
+            Some(existing) => existing.bounds.extend(param.bounds),
+            None => {
+                let position = self.merged.lifetimes().count();
+                self.merged.params.insert(position, GenericParam::Lifetime(param));
+            }
+        }
+        self
+    }
+
+    /// Add a type parameter, after the other parameters.
+    ///
+    /// If the type already declares a parameter of the same name, its bounds are extended instead.
+    pub fn type_param(mut self, param: syn::TypeParam) -> Self {
+        let existing = self.merged.type_params_mut().find(|ty| ty.ident == param.ident);
+        match existing {
+            Some(existing) => existing.bounds.extend(param.bounds),
+            None => self.merged.params.push(GenericParam::Type(param)),
+        }
+        self
+    }
+
+    /// Add a predicate to the where clause.
+    pub fn predicate(mut self, predicate: syn::WherePredicate) -> Self {
+        self.merged.make_where_clause().predicates.push(predicate);
+        self
+    }
+
+    /// Require every type parameter of the type to satisfy the bound.
+    pub fn bound_type_params(self, bound: syn::TypeParamBound) -> Self {
+        let idents = self.base.type_params().map(|param| param.ident.clone()).collect::<Vec<_>>();
+        idents.into_iter().fold(self, |this, ident| this.predicate(parse_quote!(#ident: #bound)))
+    }
+
+    /// The generics of the impl.
+    pub fn generics(&self) -> &Generics {
+        &self.merged
+    }
+
+    /// Like [`Generics::split_for_impl`]: the parameters of the impl, the arguments of the type
+    /// and the where clause of the impl.
+    pub fn split_for_impl(
+        &self,
+    ) -> (syn::ImplGenerics<'_>, syn::TypeGenerics<'_>, Option<&syn::WhereClause>) {
+        let (impl_generics, _, where_clause) = self.merged.split_for_impl();
+        let (_, ty_generics, _) = self.base.split_for_impl();
+        (impl_generics, ty_generics, where_clause)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+    use quote::ToTokens;
+
+    fn impl_header(generics: &ImplGenerics) -> String {
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        quote!(impl #impl_generics X for Y #ty_generics #where_clause).to_string()
+    }
+
+    #[test]
+    fn merging() {
+        let input: syn::DeriveInput = parse_quote!(
+            struct Y<T: Clone, const N: usize = 1>;
+        );
+        let generics = ImplGenerics::new(&input.generics)
+            .lifetime(parse_quote!('a))
+            .type_param(parse_quote!(V: Visitor))
+            .type_param(parse_quote!(T: Copy))
+            .lifetime(parse_quote!('s: 'a))
+            .predicate(parse_quote!(T: 'a));
+        let expected = quote! {
+            impl<'a, 's: 'a, T: Clone + Copy, const N: usize, V: Visitor> X for Y<T, N>
+            where T: 'a
+        };
+        assert_eq!(impl_header(&generics), expected.to_string());
+        assert_eq!(generics.generics().params.len(), 5);
+
+        let input: syn::DeriveInput = parse_quote!(
+            struct Y<'s>;
+        );
+        let generics = ImplGenerics::new(&input.generics).lifetime(parse_quote!('s: 'a));
+        assert_eq!(generics.generics().to_token_stream().to_string(), "< 's : 'a >");
+    }
+}
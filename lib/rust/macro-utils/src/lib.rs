@@ -10,6 +10,15 @@ use syn::WherePredicate;
 
 
 
+// ==============
+// === Export ===
+// ==============
+
+pub mod attribute;
+pub mod generics;
+
+
+
 // ===================
 // === Token Utils ===
 // ===================
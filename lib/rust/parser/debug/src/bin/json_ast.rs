@@ -1,5 +1,5 @@
-//! Run the parser from the command line, and output the a JSON serialization of the AST for
-//! debugging.
+//! Run the parser from the command line, and output the JSON serialization of the AST. See
+//! [`enso_parser::serialization`] for the format.
 
 
 
@@ -14,5 +14,5 @@ fn main() {
         code = code_;
     }
     let ast = enso_parser::Parser::new().run(code);
-    serde_json::to_writer(std::io::stdout(), &ast).unwrap();
+    println!("{}", enso_parser::serialization::to_json(&ast).unwrap());
 }
@@ -1816,6 +1816,387 @@ fn nonsense_inputs_broken() {
 
 
 
+// =======================
+// === Traversal Tests ===
+// =======================
+
+#[test]
+fn mutable_traversal() {
+    use enso_parser::syntax::tree::Variant;
+    let original = parse("main = f (a + b) c");
+    let mut trees = 0;
+    original.visit_trees(|_| trees += 1);
+    let mut ast = original.clone();
+    let mut trees_mut = 0;
+    ast.visit_trees_mut(|_| trees_mut += 1);
+    assert_eq!(trees_mut, trees);
+
+    let swap_operands = |tree: &mut enso_parser::syntax::Tree| {
+        if let Variant::OprApp(opr_app) = &mut tree.variant {
+            std::mem::swap(&mut opr_app.lhs, &mut opr_app.rhs);
+        }
+    };
+    ast.visit_trees_mut(swap_operands);
+    assert_ne!(ast, original);
+    ast.visit_trees_mut(swap_operands);
+    assert_eq!(ast, original);
+}
+
+#[test]
+fn early_exit_traversal() {
+    use enso_parser::syntax::tree::Variant;
+    let ast = parse("main = f (a + b) c");
+    let mut trees = 0;
+    ast.visit_trees(|_| trees += 1);
+    let mut checked = 0;
+    let found = ast.find_tree(|tree| {
+        checked += 1;
+        matches!(&tree.variant, Variant::Ident(ident) if ident.token.code == "a")
+    });
+    assert_eq!(found.map(|tree| tree.trimmed_code()).as_deref(), Some("a"));
+    assert!(checked < trees);
+    assert!(ast.find_tree(|tree| matches!(&tree.variant, Variant::Number(_))).is_none());
+}
+
+#[test]
+fn enter_exit_traversal() {
+    use enso_parser::syntax::item;
+    use enso_parser::syntax::tree::*;
+    /// Records the nesting depth of every identifier.
+    #[derive(Default)]
+    struct Depths {
+        depth:  usize,
+        idents: Vec<(String, usize)>,
+    }
+    impl Visitor for Depths {}
+    impl<'s, 'a> ItemVisitor<'s, 'a> for Depths {
+        fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+            if let item::Ref::Tree(Tree { variant: Variant::Ident(ident), .. }) = item {
+                self.idents.push((ident.token.code.to_string(), self.depth));
+            }
+            self.depth += 1;
+            true
+        }
+        fn exit_item(&mut self, _item: item::Ref<'s, 'a>) {
+            self.depth -= 1;
+        }
+    }
+    let ast = parse("main = f (a + b) c");
+    let mut depths = Depths::default();
+    let _ = ast.visit_item(&mut depths);
+    assert_eq!(depths.depth, 0);
+    let depth = |name: &str| depths.idents.iter().find(|(ident, _)| ident == name).unwrap().1;
+    assert!(depth("f") > depth("main"));
+    assert_eq!(depth("f"), depth("c") + 1);
+    assert!(depth("a") > depth("c"));
+    assert_eq!(depth("a"), depth("b"));
+}
+
+#[test]
+fn depth_limited_traversal() {
+    use enso_parser::syntax::item;
+    use enso_parser::syntax::tree::*;
+    /// Records the depth of every visited tree.
+    #[derive(Default)]
+    struct Depths(Vec<usize>);
+    impl<'s, 'a> DepthItemVisitor<'s, 'a> for Depths {
+        fn visit_item_at(&mut self, item: item::Ref<'s, 'a>, depth: usize) -> bool {
+            if let item::Ref::Tree(_) = item {
+                self.0.push(depth);
+            }
+            true
+        }
+    }
+    let ast = parse("main = ((((((a))))))");
+    let mut trees = 0;
+    ast.visit_trees(|_| trees += 1);
+    let mut unlimited = Depths::default();
+    assert!(!ast.visit_with_depth_limit(&mut unlimited, usize::MAX));
+    assert_eq!(unlimited.0.len(), trees + 1);
+    assert_eq!(unlimited.0[0], 0);
+    let max_depth = *unlimited.0.iter().max().unwrap();
+    let mut limited = Depths::default();
+    assert!(ast.visit_with_depth_limit(&mut limited, max_depth / 2));
+    assert!(limited.0.len() < unlimited.0.len());
+    assert!(limited.0.iter().all(|&depth| depth <= max_depth / 2));
+    let mut exact = Depths::default();
+    assert!(ast.visit_with_depth_limit(&mut exact, max_depth));
+    assert_eq!(exact.0, unlimited.0);
+    assert!(!ast.visit_with_depth_limit(&mut Depths::default(), max_depth + 1));
+}
+
+#[test]
+fn fallible_traversal() {
+    use enso_parser::syntax::item;
+    use enso_parser::syntax::tree::*;
+    /// Rejects the identifiers that are not in the allowed list.
+    struct CheckIdents {
+        allowed: &'static [&'static str],
+        checked: usize,
+    }
+    impl<'s, 'a> TryItemVisitor<'s, 'a> for CheckIdents {
+        type Error = String;
+        fn visit_item_try(&mut self, item: item::Ref<'s, 'a>) -> Result<bool, String> {
+            if let item::Ref::Tree(Tree { variant: Variant::Ident(ident), .. }) = item {
+                self.checked += 1;
+                let name = ident.token.code.repr.0;
+                if !self.allowed.iter().any(|allowed| *allowed == name) {
+                    return Err(format!("Unknown identifier `{name}`."));
+                }
+            }
+            Ok(true)
+        }
+    }
+    let ast = parse("main = f (a + b) c");
+    let mut all = CheckIdents { allowed: &["main", "f", "a", "b", "c"], checked: 0 };
+    assert_eq!(ast.try_visit(&mut all), Ok(()));
+    assert_eq!(all.checked, 5);
+    let mut no_b = CheckIdents { allowed: &["main", "f", "a", "c"], checked: 0 };
+    assert_eq!(ast.try_visit(&mut no_b), Err("Unknown identifier `b`.".to_owned()));
+    assert_eq!(no_b.checked, 4);
+}
+
+#[test]
+fn consuming_traversal() {
+    use enso_parser::syntax::tree::*;
+    use enso_parser::syntax::Token;
+    /// Takes the identifiers out of the tree, and counts the other tokens.
+    #[derive(Default)]
+    struct TakeIdents<'s> {
+        idents: Vec<Tree<'s>>,
+        tokens: usize,
+    }
+    impl<'s> Visitor for TakeIdents<'s> {}
+    impl<'s> ItemIntoVisitor<'s> for TakeIdents<'s> {
+        fn take_tree(&mut self, tree: Tree<'s>) {
+            match &tree.variant {
+                Variant::Ident(_) => self.idents.push(tree),
+                _ => tree.into_visit_children(self),
+            }
+        }
+        fn take_token(&mut self, _token: Token<'s>) {
+            self.tokens += 1;
+        }
+    }
+    let ast = parse("main = f (a + b) c");
+    let mut expected = vec![];
+    ast.visit_trees(|tree| {
+        if let Variant::Ident(_) = &tree.variant {
+            expected.push(tree.clone());
+        }
+    });
+    let mut visitor = TakeIdents::default();
+    ast.into_visit_item(&mut visitor);
+    assert_eq!(visitor.idents, expected);
+    assert!(visitor.tokens > 0);
+}
+
+#[test]
+fn parallel_traversal() {
+    use enso_parser::syntax::item;
+    use enso_parser::syntax::tree::*;
+    /// Collects the code of the identifiers.
+    #[derive(Default)]
+    struct Idents(Vec<String>);
+    impl Visitor for Idents {}
+    impl<'s, 'a> ItemVisitor<'s, 'a> for Idents {
+        fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+            if let item::Ref::Tree(Tree { variant: Variant::Ident(ident), .. }) = item {
+                self.0.push(ident.token.code.to_string());
+            }
+            true
+        }
+    }
+    impl<'s, 'a> ParItemVisitor<'s, 'a> for Idents {
+        fn split(&self) -> Self {
+            Self::default()
+        }
+        fn merge(&mut self, other: Self) {
+            self.0.extend(other.0);
+        }
+    }
+    let code = (0..100).map(|i| format!("f{i} = g{i} x{i}")).collect::<Vec<_>>().join("\n");
+    let ast = parse(&code);
+    let mut sequential = Idents::default();
+    let _ = ast.visit_item(&mut sequential);
+    let mut parallel = Idents::default();
+    let _ = ast.par_visit_item(&mut parallel);
+    assert_eq!(parallel.0.len(), 300);
+    assert_eq!(parallel.0, sequential.0);
+}
+
+#[test]
+fn folding() {
+    use enso_parser::syntax::tree::*;
+    struct SwapOperands;
+    impl Visitor for SwapOperands {}
+    impl<'s> ItemFolder<'s> for SwapOperands {
+        fn fold_tree(&mut self, tree: Tree<'s>) -> Tree<'s> {
+            let mut tree = tree.fold_children(self);
+            if let Variant::OprApp(opr_app) = &mut tree.variant {
+                std::mem::swap(&mut opr_app.lhs, &mut opr_app.rhs);
+            }
+            tree
+        }
+    }
+    let original = parse("main = f (a + (b - c)) d");
+    let folded = original.clone().fold_item(&mut SwapOperands);
+    let mut expected = original.clone();
+    expected.visit_trees_mut(|tree| {
+        if let Variant::OprApp(opr_app) = &mut tree.variant {
+            std::mem::swap(&mut opr_app.lhs, &mut opr_app.rhs);
+        }
+    });
+    assert_ne!(folded, original);
+    assert_eq!(folded, expected);
+}
+
+#[test]
+fn tree_stats() {
+    use enso_parser::syntax::tree::*;
+    let ast = parse("main = f (a + b) c");
+    let stats = ast.stats();
+    let mut trees = 0;
+    ast.visit_trees(|_| trees += 1);
+    assert_eq!(stats.trees, trees);
+    assert_eq!(stats.kinds[NodeKind::Ident], 5);
+    assert_eq!(NodeKind::ALL.iter().map(|&kind| stats.kinds[kind]).sum::<usize>(), trees);
+    assert!(stats.tokens >= 9);
+    let nested = parse("main = f ((a + b)) c").stats();
+    assert_eq!(nested.max_depth, stats.max_depth + 1);
+    let mut both = TreeStats::default();
+    let pair = (ast.clone(), ast.clone());
+    let _ = pair.visit_item(&mut both);
+    assert_eq!(both.trees, 2 * stats.trees);
+    assert_eq!(both.max_depth, stats.max_depth);
+}
+
+#[test]
+fn json_serialization() {
+    use enso_parser::serialization::*;
+    let code = "main =\n    x = f (a + '😀ü')\n    x.y _";
+    let ast = parse(code);
+    let json = to_json(&ast).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(value["variant"]["BodyBlock"]["statements"].is_array());
+    assert!(json.contains(r#""repr":"main""#));
+    let restored = from_json(&json, code).unwrap();
+    assert_eq!(restored.code(), code);
+    assert_eq!(to_json(&restored).unwrap(), json);
+    assert!(from_json(&json, "main").is_err());
+}
+
+#[test]
+fn container_traversal() {
+    use enso_parser::syntax::item;
+    use enso_parser::syntax::tree::*;
+    use std::rc::Rc;
+    struct CountTrees(usize);
+    impl Visitor for CountTrees {}
+    impl<'s, 'a> ItemVisitor<'s, 'a> for CountTrees {
+        fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+            if let item::Ref::Tree(_) = item {
+                self.0 += 1;
+            }
+            true
+        }
+    }
+    struct SwapOperands;
+    impl Visitor for SwapOperands {}
+    impl<'s> ItemFolder<'s> for SwapOperands {
+        fn fold_tree(&mut self, tree: Tree<'s>) -> Tree<'s> {
+            let mut tree = tree.fold_children(self);
+            if let Variant::OprApp(opr_app) = &mut tree.variant {
+                std::mem::swap(&mut opr_app.lhs, &mut opr_app.rhs);
+            }
+            tree
+        }
+    }
+    let ast = parse("main = a + b");
+    let mut trees = 0;
+    ast.visit_trees(|_| trees += 1);
+    let shared = Rc::new(ast.clone());
+    let pair = (ast.clone(), Some(shared.clone()));
+    let mut counter = CountTrees(0);
+    let _ = pair.visit_item(&mut counter);
+    assert_eq!(counter.0, 2 * trees);
+    let (folded, folded_shared) = pair.fold_item(&mut SwapOperands);
+    assert_ne!(folded, ast);
+    assert_eq!(folded_shared.as_deref(), Some(&folded));
+    assert_eq!(*shared, ast);
+}
+
+#[test]
+fn borrowed_fields() {
+    use enso_parser::syntax::item;
+    use enso_parser::syntax::tree::*;
+    use enso_parser_syntax_tree_visitor::Visitor;
+    /// A view of trees owned by another node.
+    #[derive(Visitor)]
+    struct Operands<'s, 't> {
+        lhs: &'t Tree<'s>,
+        rhs: Option<&'t Tree<'s>>,
+    }
+    struct CountTrees(usize);
+    impl Visitor for CountTrees {}
+    impl<'s, 'a> ItemVisitor<'s, 'a> for CountTrees {
+        fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+            if let item::Ref::Tree(_) = item {
+                self.0 += 1;
+            }
+            true
+        }
+    }
+    let ast = parse("main = a + b");
+    let mut trees = 0;
+    ast.visit_trees(|_| trees += 1);
+    let operands = Operands { lhs: &ast, rhs: Some(&ast) };
+    let mut counter = CountTrees(0);
+    let _ = operands.visit_item(&mut counter);
+    assert_eq!(counter.0, 2 * trees);
+}
+
+#[test]
+fn node_kinds() {
+    use enso_parser::syntax::item;
+    use enso_parser::syntax::tree::*;
+    /// Counts the trees of every kind, without descending into the trees of the skipped kinds.
+    struct CountKinds {
+        skip:   KindTable<bool>,
+        counts: KindTable<usize>,
+    }
+    impl Visitor for CountKinds {}
+    impl<'s, 'a> ItemVisitor<'s, 'a> for CountKinds {
+        fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+            match item {
+                item::Ref::Tree(tree) => {
+                    self.counts[tree.kind()] += 1;
+                    !self.skip[tree.kind()]
+                }
+                item::Ref::Token(_) => true,
+            }
+        }
+    }
+    let ast = parse("main = f (a + b) c");
+    let count = |skip: &[NodeKind]| {
+        let mut visitor =
+            CountKinds { skip: KindTable::of(skip), counts: KindTable::from_fn(|_| 0) };
+        let _ = ast.visit_item(&mut visitor);
+        visitor.counts
+    };
+    let all = count(&[]);
+    assert_eq!(all[NodeKind::Ident], 5);
+    assert_eq!(all[NodeKind::Group], 1);
+    let outside_groups = count(&[NodeKind::Group]);
+    assert_eq!(outside_groups[NodeKind::Group], 1);
+    assert_eq!(outside_groups[NodeKind::Ident], 3);
+    assert_eq!(outside_groups[NodeKind::OprApp], all[NodeKind::OprApp] - 1);
+    assert!(NodeKind::ALL.iter().enumerate().all(|(index, kind)| kind.index() == index));
+    assert_eq!(NodeKind::OprApp.name(), "OprApp");
+}
+
+
+
 // ====================
 // === Test Support ===
 // ====================
@@ -2,10 +2,37 @@
 //!
 //! Deserialization is used only for testing, but it is used by dependent crates, so it cannot be
 //! gated with `#[cfg(test)]`.
+//!
+//! # JSON representation
+//! Besides the binary format read by the Java deserializer, a [`Tree`] can be serialized to JSON
+//! with [`to_json`], for tools that inspect the parse results without linking this crate, and read
+//! back with [`from_json`] (with the `debug` feature). The JSON follows the definitions of the Rust
+//! types:
+//! - A struct is an object with a key for every serialized field, e.g. a [`Tree`] has its `span`,
+//!   its `warnings`, and its `variant`.
+//! - An enum value is an object with a single key, the name of the variant, holding the value of
+//!   the variant.
+//! - A code reference (the code of a token, or the whitespace preceding a token or a tree) is an
+//!   object with the code text in `repr`, its `start` location and its `len`gth. The locations and
+//!   lengths are given in UTF-16 code units, and the start locations also in UTF-8 bytes.
+//! - A missing optional number is `null`.
+//!
+//! Changing the names of the types or fields changes the representation, so it is as stable as
+//! the Rust definitions, and it is versioned with the parser.
 
 use crate::prelude::*;
 
 use crate::source::code::StrRef;
+use crate::syntax::tree::Tree;
+
+#[cfg(feature = "debug")]
+use crate::syntax::item;
+#[cfg(feature = "debug")]
+use crate::syntax::tree::ItemVisitableMut;
+#[cfg(feature = "debug")]
+use crate::syntax::tree::ItemVisitorMut;
+#[cfg(feature = "debug")]
+use crate::syntax::tree::Visitor;
 
 
 
@@ -14,19 +41,92 @@ use crate::source::code::StrRef;
 // ============
 
 /// Serialize a `Tree` to its binary representation.
-pub fn serialize_tree(data: &crate::syntax::tree::Tree) -> Result<Vec<u8>, bincode::Error> {
+pub fn serialize_tree(data: &Tree) -> Result<Vec<u8>, bincode::Error> {
     use bincode::Options;
     let options = bincode::DefaultOptions::new().with_fixint_encoding();
     options.serialize(data)
 }
 
 /// Deserialize a `Tree` from its binary representation.
-pub fn deserialize_tree(data: &[u8]) -> Result<crate::syntax::tree::Tree, bincode::Error> {
+pub fn deserialize_tree(data: &[u8]) -> Result<Tree, bincode::Error> {
     use bincode::Options;
     let options = bincode::DefaultOptions::new().with_fixint_encoding();
     options.deserialize(data)
 }
 
+/// Serialize a `Tree` to its JSON representation. See the module documentation for the format.
+pub fn to_json(tree: &Tree) -> Result<String, serde_json::Error> {
+    serde_json::to_string(tree)
+}
+
+/// Deserialize a `Tree` from its JSON representation, produced by [`to_json`] from the tree of the
+/// given source code. The code references of the tree are restored from their locations in the
+/// source code.
+#[cfg(feature = "debug")]
+pub fn from_json<'s>(json: &str, code: &'s str) -> Result<Tree<'s>, serde_json::Error> {
+    let mut tree: Tree<'s> = serde_json::from_str(json)?;
+    let mut restore = RestoreCode { source: code, missing: None };
+    tree.visit_item_mut(&mut restore);
+    match restore.missing {
+        None => Ok(tree),
+        Some(location) => {
+            let message = format!("The code at {location} is not in the source code.");
+            Err(serde::de::Error::custom(message))
+        }
+    }
+}
+
+/// Restores the code references of a deserialized tree from the source code.
+#[cfg(feature = "debug")]
+struct RestoreCode<'s> {
+    /// The source code of the tree.
+    source:  &'s str,
+    /// The location of the first code reference not found in the source code.
+    missing: Option<crate::source::code::Location>,
+}
+
+#[cfg(feature = "debug")]
+impl<'s> RestoreCode<'s> {
+    fn restore(&mut self, code: &mut crate::source::code::Code<'s>) {
+        let rest = self.source.get(code.start.utf8 as usize..);
+        match rest.and_then(|rest| utf16_prefix(rest, code.len.utf16)) {
+            Some(repr) => *code = crate::source::code::Code::from_str_at_location(repr, code.start),
+            None => {
+                self.missing.get_or_insert(code.start);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl Visitor for RestoreCode<'_> {}
+#[cfg(feature = "debug")]
+impl<'s> ItemVisitorMut<'s> for RestoreCode<'s> {
+    fn visit_item_mut(&mut self, item: item::RefMut<'s, '_>) -> bool {
+        match item {
+            item::RefMut::Tree(tree) => self.restore(&mut tree.span.left_offset.code),
+            item::RefMut::Token(token) => {
+                self.restore(&mut token.left_offset.code);
+                self.restore(token.code);
+            }
+        }
+        true
+    }
+}
+
+/// The prefix of the text of the given length in UTF-16 code units, if the text has such a prefix.
+#[cfg(feature = "debug")]
+fn utf16_prefix(text: &str, len16: u32) -> Option<&str> {
+    let mut utf16 = 0;
+    for (index, c) in text.char_indices() {
+        if utf16 >= len16 {
+            return (utf16 == len16).then(|| &text[..index]);
+        }
+        utf16 += c.len_utf16() as u32;
+    }
+    (utf16 == len16).then_some(text)
+}
+
 
 
 // ============
@@ -42,17 +142,25 @@ pub(crate) struct Code {
     len:   u32,
 }
 
-/// Serde wrapper to serialize a `Cow` as the `Code` representation.
+/// Serde wrapper to serialize a `Cow` as the `Code` representation. A human-readable format, like
+/// JSON, gets the code text instead.
 pub(crate) fn serialize_cow<S>(s: &StrRef, ser: S) -> Result<S::Ok, S::Error>
 where S: serde::Serializer {
     let s = s.0;
+    if ser.is_human_readable() {
+        return ser.serialize_str(s);
+    }
     let s = Code { begin: str::as_ptr(s) as u32, len: s.len() as u32 };
     s.serialize(ser)
 }
 
 pub(crate) fn deserialize_cow<'c, 'de, D>(deserializer: D) -> Result<StrRef<'c>, D::Error>
 where D: serde::Deserializer<'de> {
-    let _ = deserializer.deserialize_u64(DeserializeU64);
+    if deserializer.is_human_readable() {
+        serde::de::IgnoredAny::deserialize(deserializer)?;
+    } else {
+        let _ = deserializer.deserialize_u64(DeserializeU64);
+    }
     Ok(StrRef(""))
 }
 
@@ -64,11 +172,13 @@ where D: serde::Deserializer<'de> {
 
 /// Deserialization type for `crate::syntax::tree::Error`.
 #[derive(Deserialize, Debug, Clone)]
-pub(crate) struct Error(String);
+pub(crate) struct Error {
+    message: String,
+}
 
 impl From<Error> for crate::syntax::tree::Error {
     fn from(error: Error) -> Self {
-        let message = error.0.into();
+        let message = error.message.into();
         crate::syntax::tree::Error { message }
     }
 }
@@ -107,6 +217,11 @@ impl<'de> serde::de::Visitor<'de> for DeserializeU32 {
     where E: serde::de::Error {
         Ok(i)
     }
+
+    fn visit_u64<E>(self, i: u64) -> Result<Self::Value, E>
+    where E: serde::de::Error {
+        u32::try_from(i).map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(i), &self))
+    }
 }
 
 
@@ -117,6 +232,9 @@ impl<'de> serde::de::Visitor<'de> for DeserializeU32 {
 
 pub(crate) fn serialize_optional_int<S>(x: &Option<u32>, s: S) -> Result<S::Ok, S::Error>
 where S: serde::Serializer {
+    if s.is_human_readable() {
+        return x.serialize(s);
+    }
     s.serialize_u32(x.unwrap_or(0xFFFF_FFFF))
 }
 
@@ -124,6 +242,9 @@ pub(crate) fn deserialize_optional_int<'c, 'de, D>(
     deserializer: D,
 ) -> Result<Option<u32>, D::Error>
 where D: serde::Deserializer<'de> {
+    if deserializer.is_human_readable() {
+        return Option::deserialize(deserializer);
+    }
     let value = deserializer.deserialize_u32(DeserializeU32)?;
     Ok(match value {
         0xFFFF_FFFF => None,
@@ -110,3 +110,16 @@ pub enum Ref<'s, 'a> {
     Token(token::Ref<'s, 'a>),
     Tree(&'a Tree<'s>),
 }
+
+
+// ==============
+// === RefMut ===
+// ==============
+
+/// A mutably borrowed version of [`Item`]. Used by the AST visitors rewriting the nodes in place.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum RefMut<'s, 'a> {
+    Token(token::RefMut<'s, 'a>),
+    Tree(&'a mut Tree<'s>),
+}
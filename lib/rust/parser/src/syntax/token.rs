@@ -254,6 +254,43 @@ impl<'s, 'a, T: Debug> Debug for Ref<'s, 'a, T> {
 
 
 
+// ==============
+// === RefMut ===
+// ==============
+
+/// A mutable reference of a [`Token`], the counterpart of [`Ref`]. The token variant is converted
+/// the same way, so it is a copy, while the offset and the code can be modified in place.
+#[derive(Deref, DerefMut, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub struct RefMut<'s, 'a, T = Variant> {
+    #[deref]
+    #[deref_mut]
+    pub data:        T,
+    pub left_offset: &'a mut Offset<'s>,
+    pub code:        &'a mut Code<'s>,
+}
+
+impl<'s, 'a, T, S> From<&'a mut Token<'s, T>> for RefMut<'s, 'a, S>
+where T: Copy + Into<S>
+{
+    fn from(token: &'a mut Token<'s, T>) -> Self {
+        RefMut {
+            data:        token.variant.into(),
+            left_offset: &mut token.left_offset,
+            code:        &mut token.code,
+        }
+    }
+}
+
+impl<'s, 'a, T: Debug> Debug for RefMut<'s, 'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[off: {}, repr: \"{}\"] ", self.left_offset.visible, self.code)?;
+        Debug::fmt(&self.data, f)
+    }
+}
+
+
+
 // ===============
 // === Variant ===
 // ===============
@@ -8,8 +8,17 @@ use crate::span_builder;
 use crate::syntax::token::TokenOperatorProperties;
 use crate::syntax::treebuilding::Spacing;
 
+#[cfg(feature = "debug")]
+use enso_parser_syntax_tree_visitor::Foldable;
+#[cfg(feature = "debug")]
+use enso_parser_syntax_tree_visitor::IntoVisitor;
+use enso_parser_syntax_tree_visitor::SpanBuilder;
 #[cfg(feature = "debug")]
 use enso_parser_syntax_tree_visitor::Visitor;
+#[cfg(feature = "debug")]
+use enso_parser_syntax_tree_visitor::VisitorMut;
+#[cfg(feature = "debug")]
+use std::ops::ControlFlow;
 
 
 
@@ -67,8 +76,10 @@ impl<'s> Default for Tree<'s> {
 macro_rules! with_ast_definition { ($f:ident ($($args:tt)*)) => { $f! { $($args)*
     /// [`Tree`] variants definition. See its docs to learn more.
     #[tagged_enum(boxed)]
-    #[cfg_attr(feature = "debug", derive(Visitor))]
+    #[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
     #[derive(Clone, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+    #[tagged_enum(apply_attributes_to = "enum")]
+    #[cfg_attr(feature = "debug", visitor(kind = NodeKind))]
     #[tagged_enum(apply_attributes_to = "variants")]
     #[reflect(inline)]
     pub enum Variant<'s> {
@@ -78,6 +89,7 @@ macro_rules! with_ast_definition { ($f:ident ($($args:tt)*)) => { $f! { $($args)
             pub ast: Tree<'s>,
         },
         /// A sequence of lines introduced by a line ending in an operator.
+        #[cfg_attr(feature = "debug", visitor(parallel))]
         BodyBlock {
             /// The lines of the block.
             pub statements: Vec<block::Line<'s>>,
@@ -386,8 +398,8 @@ with_ast_definition!(generate_ast_definition());
 // === Invalid ===
 
 /// Error of parsing attached to an [`Tree`] node.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 #[allow(missing_docs)]
 #[reflect(transparent)]
 #[serde(from = "crate::serialization::Error")]
@@ -411,18 +423,12 @@ impl<'s> Tree<'s> {
     }
 }
 
-impl<'s> span::Builder<'s> for Error {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span
-    }
-}
-
 
 // === Argument blocks ===
 
 /// An argument specification on its own line.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 pub struct ArgumentDefinitionLine<'s> {
     /// The token beginning the line.
     pub newline:  token::Newline<'s>,
@@ -430,18 +436,12 @@ pub struct ArgumentDefinitionLine<'s> {
     pub argument: Option<ArgumentDefinition<'s>>,
 }
 
-impl<'s> span::Builder<'s> for ArgumentDefinitionLine<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.newline).add(&mut self.argument)
-    }
-}
-
 
 // === Text literals ===
 
 /// A component of a text literal, within the quotation marks.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 pub enum TextElement<'s> {
     /// The text content of the literal. If it is multiline, the offset information may contain
     /// part of the content, after trimming appropriately.
@@ -471,24 +471,12 @@ pub enum TextElement<'s> {
     },
 }
 
-impl<'s> span::Builder<'s> for TextElement<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        match self {
-            TextElement::Section { text } => text.add_to_span(span),
-            TextElement::Escape { token } => span.add(token),
-            TextElement::Splice { open, expression, close } =>
-                span.add(open).add(expression).add(close),
-            TextElement::Newline { newline } => span.add(newline),
-        }
-    }
-}
-
 
 // === Documentation ===
 
 /// A documentation comment.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 pub struct DocComment<'s> {
     /// The comment-initiating token.
     pub open:     token::TextStart<'s>,
@@ -498,17 +486,11 @@ pub struct DocComment<'s> {
     pub newlines: Vec<token::Newline<'s>>,
 }
 
-impl<'s> span::Builder<'s> for DocComment<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.open).add(&mut self.elements).add(&mut self.newlines)
-    }
-}
-
 
 // === Number literals ===
 
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 #[allow(missing_docs)]
 pub struct FractionalDigits<'s> {
     /// The dot operator.
@@ -517,18 +499,12 @@ pub struct FractionalDigits<'s> {
     pub digits: token::Digits<'s>,
 }
 
-impl<'s> span::Builder<'s> for FractionalDigits<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.dot).add(&mut self.digits)
-    }
-}
-
 
 // === Functions ===
 
 /// A function argument definition.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 pub struct ArgumentDefinition<'s> {
     /// Opening parenthesis (outer).
     pub open:       Option<token::OpenSymbol<'s>>,
@@ -549,22 +525,9 @@ pub struct ArgumentDefinition<'s> {
     pub close:      Option<token::CloseSymbol<'s>>,
 }
 
-impl<'s> span::Builder<'s> for ArgumentDefinition<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.open)
-            .add(&mut self.open2)
-            .add(&mut self.suspension)
-            .add(&mut self.pattern)
-            .add(&mut self.type_)
-            .add(&mut self.close2)
-            .add(&mut self.default)
-            .add(&mut self.close)
-    }
-}
-
 /// A default value specification in a function argument definition.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 pub struct ArgumentDefault<'s> {
     /// The `=` token.
     pub equals:     token::AssignmentOperator<'s>,
@@ -572,15 +535,9 @@ pub struct ArgumentDefault<'s> {
     pub expression: Tree<'s>,
 }
 
-impl<'s> span::Builder<'s> for ArgumentDefault<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.equals).add(&mut self.expression)
-    }
-}
-
 /// A type ascribed to an argument definition.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 pub struct ArgumentType<'s> {
     /// The `:` token.
     pub operator: token::TypeAnnotationOperator<'s>,
@@ -589,15 +546,9 @@ pub struct ArgumentType<'s> {
     pub type_:    Tree<'s>,
 }
 
-impl<'s> span::Builder<'s> for ArgumentType<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.operator).add(&mut self.type_)
-    }
-}
-
 /// A function return type specification.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 pub struct ReturnSpecification<'s> {
     /// The `->` operator.
     pub arrow:  token::ArrowOperator<'s>,
@@ -606,18 +557,12 @@ pub struct ReturnSpecification<'s> {
     pub r#type: Tree<'s>,
 }
 
-impl<'s> span::Builder<'s> for ReturnSpecification<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.arrow).add(&mut self.r#type)
-    }
-}
-
 
 // === CaseOf ===
 
 /// A line that may contain a case-expression in a case-of expression.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 pub struct CaseLine<'s> {
     /// The token beginning the line. This will always be present, unless the first case-expression
     /// is on the same line as the initial case-of.
@@ -637,15 +582,9 @@ impl<'s> CaseLine<'s> {
     }
 }
 
-impl<'s> span::Builder<'s> for CaseLine<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.newline).add(&mut self.case)
-    }
-}
-
 /// A case-expression in a case-of expression.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 pub struct Case<'s> {
     /// Documentation, if present.
     pub documentation: Option<DocComment<'s>>,
@@ -668,15 +607,6 @@ impl<'s> Case<'s> {
     }
 }
 
-impl<'s> span::Builder<'s> for Case<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.documentation)
-            .add(&mut self.pattern)
-            .add(&mut self.arrow)
-            .add(&mut self.expression)
-    }
-}
-
 
 // === OprApp ===
 
@@ -684,19 +614,13 @@ impl<'s> span::Builder<'s> for Case<'s> {
 pub type OperatorOrError<'s> = Result<token::Operator<'s>, MultipleOperatorError<'s>>;
 
 /// Error indicating multiple operators found next to each other, like `a + * b`.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 #[allow(missing_docs)]
 pub struct MultipleOperatorError<'s> {
     pub operators: Box<NonEmptyVec<token::Operator<'s>>>,
 }
 
-impl<'s> span::Builder<'s> for MultipleOperatorError<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        self.operators.add_to_span(span)
-    }
-}
-
 /// A sequence of one or more operators.
 pub trait NonEmptyOperatorSequence<'s> {
     /// Return a reference to the first operator.
@@ -724,26 +648,20 @@ impl<'s> NonEmptyOperatorSequence<'s> for OperatorOrError<'s> {
 // === MultiSegmentApp ===
 
 /// A segment of [`MultiSegmentApp`], like `if cond` in the `if cond then ok else fail` expression.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 #[allow(missing_docs)]
 pub struct MultiSegmentAppSegment<'s> {
     pub header: Token<'s>,
     pub body:   Option<Tree<'s>>,
 }
 
-impl<'s> span::Builder<'s> for MultiSegmentAppSegment<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.header).add(&mut self.body)
-    }
-}
-
 
 // === Array and Tuple ===
 
 /// A node following an operator.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Reflect, Deserialize, SpanBuilder)]
 pub struct OperatorDelimitedTree<'s> {
     /// The delimiting operator.
     pub operator: token::Operator<'s>,
@@ -751,12 +669,6 @@ pub struct OperatorDelimitedTree<'s> {
     pub body:     Option<Tree<'s>>,
 }
 
-impl<'s> span::Builder<'s> for OperatorDelimitedTree<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.operator).add(&mut self.body)
-    }
-}
-
 
 
 // ================
@@ -1072,10 +984,52 @@ impl<'s> From<token::Ident<'s>> for Tree<'s> {
 pub trait Visitor {}
 
 /// The visitor trait allowing for [`Item`] traversal.
-#[allow(missing_docs)]
 #[cfg(feature = "debug")]
 pub trait ItemVisitor<'s, 'a>: Visitor {
-    fn visit_item(&mut self, ast: item::Ref<'s, 'a>) -> bool;
+    /// Visit the item, returning whether its children should be visited.
+    fn visit_item(&mut self, _ast: item::Ref<'s, 'a>) -> bool {
+        true
+    }
+
+    /// Visit the item, possibly stopping the whole traversal with [`ControlFlow::Break`].
+    /// Otherwise, returns whether the children of the item should be visited. By default, the
+    /// traversal is never stopped, and [`Self::visit_item`] decides about the children.
+    fn try_visit_item(&mut self, ast: item::Ref<'s, 'a>) -> ControlFlow<(), bool> {
+        ControlFlow::Continue(self.visit_item(ast))
+    }
+
+    /// Leave the item, after its children have been visited (or skipped). Every item entered by
+    /// [`Self::try_visit_item`] is left, unless the traversal is stopped, so the two calls bracket
+    /// the subtree, e.g. to track scopes or the nesting depth.
+    fn exit_item(&mut self, _ast: item::Ref<'s, 'a>) {}
+}
+
+/// The visitor trait allowing for [`Item`] traversal with mutable access, so the visited nodes can
+/// be rewritten in place. If a [`Tree`] is replaced, the traversal continues into the new one.
+#[allow(missing_docs)]
+#[cfg(feature = "debug")]
+pub trait ItemVisitorMut<'s>: Visitor {
+    fn visit_item_mut(&mut self, ast: item::RefMut<'s, '_>) -> bool;
+}
+
+/// The visitor trait allowing for fallible [`Item`] traversal, like a validation pass. The
+/// traversal stops at the first error. See [`Tree::try_visit`].
+#[cfg(feature = "debug")]
+pub trait TryItemVisitor<'s, 'a> {
+    /// The error stopping the traversal.
+    type Error;
+
+    /// Visit the item, returning whether its children should be visited, or the error.
+    fn visit_item_try(&mut self, item: item::Ref<'s, 'a>) -> Result<bool, Self::Error>;
+}
+
+/// The visitor trait allowing for [`Item`] traversal that knows the nesting depth of the items. See
+/// [`Tree::visit_with_depth_limit`].
+#[cfg(feature = "debug")]
+pub trait DepthItemVisitor<'s, 'a> {
+    /// Visit the item at the given depth, the root being at depth 0. Returns whether its children
+    /// should be visited.
+    fn visit_item_at(&mut self, item: item::Ref<'s, 'a>, depth: usize) -> bool;
 }
 
 macro_rules! define_visitor {
@@ -1086,17 +1040,22 @@ macro_rules! define_visitor {
         $visitable:ident
     ) => {
         /// The visitable trait. See documentation of [`define_visitor`] to learn more.
+        ///
+        /// The traversal stops as soon as the visitor breaks, and the break is returned.
         #[cfg(feature = "debug")]
         #[allow(missing_docs)]
         pub trait $visitable<'s, 'a> {
-            fn $visit<V: $visitor<'s, 'a>>(&'a self, _visitor: &mut V) {}
+            fn $visit<V: $visitor<'s, 'a>>(&'a self, _visitor: &mut V) -> ControlFlow<()> {
+                ControlFlow::Continue(())
+            }
         }
 
         #[cfg(feature = "debug")]
         impl<'s, 'a, T: $visitable<'s, 'a>> $visitable<'s, 'a> for Option<T> {
-            fn $visit<V: $visitor<'s, 'a>>(&'a self, visitor: &mut V) {
-                if let Some(elem) = self {
-                    $visitable::$visit(elem, visitor)
+            fn $visit<V: $visitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+                match self {
+                    Some(elem) => $visitable::$visit(elem, visitor),
+                    None => ControlFlow::Continue(()),
                 }
             }
         }
@@ -1105,7 +1064,7 @@ macro_rules! define_visitor {
         impl<'s, 'a, T: $visitable<'s, 'a>, E: $visitable<'s, 'a>> $visitable<'s, 'a>
             for Result<T, E>
         {
-            fn $visit<V: $visitor<'s, 'a>>(&'a self, visitor: &mut V) {
+            fn $visit<V: $visitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
                 match self {
                     Ok(elem) => $visitable::$visit(elem, visitor),
                     Err(elem) => $visitable::$visit(elem, visitor),
@@ -1115,15 +1074,15 @@ macro_rules! define_visitor {
 
         #[cfg(feature = "debug")]
         impl<'s, 'a, T: $visitable<'s, 'a>> $visitable<'s, 'a> for Vec<T> {
-            fn $visit<V: $visitor<'s, 'a>>(&'a self, visitor: &mut V) {
-                self.iter().map(|t| $visitable::$visit(t, visitor)).for_each(drop);
+            fn $visit<V: $visitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+                self.iter().try_for_each(|t| $visitable::$visit(t, visitor))
             }
         }
 
         #[cfg(feature = "debug")]
         impl<'s, 'a, T: $visitable<'s, 'a>> $visitable<'s, 'a> for NonEmptyVec<T> {
-            fn $visit<V: $visitor<'s, 'a>>(&'a self, visitor: &mut V) {
-                self.iter().map(|t| $visitable::$visit(t, visitor)).for_each(drop);
+            fn $visit<V: $visitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+                self.iter().try_for_each(|t| $visitable::$visit(t, visitor))
             }
         }
     };
@@ -1131,6 +1090,415 @@ macro_rules! define_visitor {
 
 define_visitor!(Item, visit_item, ItemVisitor, ItemVisitable);
 
+macro_rules! define_visitor_mut {
+    (
+        $name:ident,
+        $visit:ident,
+        $visitor:ident,
+        $visitable:ident
+    ) => {
+        /// The visitable trait traversing mutable references. See documentation of
+        /// [`define_visitor`] to learn more.
+        #[cfg(feature = "debug")]
+        #[allow(missing_docs)]
+        pub trait $visitable<'s> {
+            fn $visit<V: $visitor<'s>>(&mut self, _visitor: &mut V) {}
+        }
+
+        #[cfg(feature = "debug")]
+        impl<'s, T: $visitable<'s>> $visitable<'s> for Option<T> {
+            fn $visit<V: $visitor<'s>>(&mut self, visitor: &mut V) {
+                if let Some(elem) = self {
+                    $visitable::$visit(elem, visitor)
+                }
+            }
+        }
+
+        #[cfg(feature = "debug")]
+        impl<'s, T: $visitable<'s>, E: $visitable<'s>> $visitable<'s> for Result<T, E> {
+            fn $visit<V: $visitor<'s>>(&mut self, visitor: &mut V) {
+                match self {
+                    Ok(elem) => $visitable::$visit(elem, visitor),
+                    Err(elem) => $visitable::$visit(elem, visitor),
+                }
+            }
+        }
+
+        #[cfg(feature = "debug")]
+        impl<'s, T: $visitable<'s>> $visitable<'s> for Vec<T> {
+            fn $visit<V: $visitor<'s>>(&mut self, visitor: &mut V) {
+                self.iter_mut().for_each(|t| $visitable::$visit(t, visitor));
+            }
+        }
+
+        #[cfg(feature = "debug")]
+        impl<'s, T: $visitable<'s>> $visitable<'s> for NonEmptyVec<T> {
+            fn $visit<V: $visitor<'s>>(&mut self, visitor: &mut V) {
+                self.iter_mut().for_each(|t| $visitable::$visit(t, visitor));
+            }
+        }
+    };
+}
+
+define_visitor_mut!(Item, visit_item_mut, ItemVisitorMut, ItemVisitableMut);
+
+
+// === Folding ===
+
+/// The folder trait allowing for [`Item`] transformation. Folding consumes the visited node and
+/// rebuilds it from the folded children, so a folder can replace any [`Tree`] or [`Token`] with a
+/// different one.
+#[cfg(feature = "debug")]
+pub trait ItemFolder<'s>: Visitor + Sized {
+    /// Transform the tree. By default, its children are folded.
+    fn fold_tree(&mut self, tree: Tree<'s>) -> Tree<'s> {
+        tree.fold_children(self)
+    }
+
+    /// Transform the token. By default, it is returned unchanged.
+    fn fold_token<T>(&mut self, token: Token<'s, T>) -> Token<'s, T> {
+        token
+    }
+}
+
+/// The foldable trait. See [`ItemFolder`] to learn more.
+#[cfg(feature = "debug")]
+#[allow(missing_docs)]
+pub trait ItemFoldable<'s>: Sized {
+    fn fold_item<F: ItemFolder<'s>>(self, _folder: &mut F) -> Self {
+        self
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemFoldable<'s>> ItemFoldable<'s> for Option<T> {
+    fn fold_item<F: ItemFolder<'s>>(self, folder: &mut F) -> Self {
+        self.map(|elem| elem.fold_item(folder))
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemFoldable<'s>, E: ItemFoldable<'s>> ItemFoldable<'s> for Result<T, E> {
+    fn fold_item<F: ItemFolder<'s>>(self, folder: &mut F) -> Self {
+        match self {
+            Ok(elem) => Ok(elem.fold_item(folder)),
+            Err(elem) => Err(elem.fold_item(folder)),
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemFoldable<'s>> ItemFoldable<'s> for Vec<T> {
+    fn fold_item<F: ItemFolder<'s>>(self, folder: &mut F) -> Self {
+        self.into_iter().map(|elem| elem.fold_item(folder)).collect()
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemFoldable<'s>> ItemFoldable<'s> for NonEmptyVec<T> {
+    fn fold_item<F: ItemFolder<'s>>(self, folder: &mut F) -> Self {
+        NonEmptyVec { elems: self.elems.fold_item(folder) }
+    }
+}
+
+
+// === Consuming Traversal ===
+
+/// The visitor trait allowing for [`Item`] traversal by value, so the visitor can move the nodes
+/// out of the tree without cloning them. The nodes the visitor does not keep are dropped.
+#[cfg(feature = "debug")]
+pub trait ItemIntoVisitor<'s>: Visitor + Sized {
+    /// Take the tree. By default, its children are visited.
+    fn take_tree(&mut self, tree: Tree<'s>) {
+        tree.into_visit_children(self)
+    }
+
+    /// Take the token. By default, it is dropped.
+    fn take_token(&mut self, _token: Token<'s>) {}
+}
+
+/// The visitable trait traversing by value. See [`ItemIntoVisitor`] to learn more.
+#[cfg(feature = "debug")]
+#[allow(missing_docs)]
+pub trait ItemIntoVisitable<'s>: Sized {
+    fn into_visit_item<V: ItemIntoVisitor<'s>>(self, _visitor: &mut V) {}
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemIntoVisitable<'s>> ItemIntoVisitable<'s> for Option<T> {
+    fn into_visit_item<V: ItemIntoVisitor<'s>>(self, visitor: &mut V) {
+        if let Some(elem) = self {
+            elem.into_visit_item(visitor)
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemIntoVisitable<'s>, E: ItemIntoVisitable<'s>> ItemIntoVisitable<'s>
+    for Result<T, E>
+{
+    fn into_visit_item<V: ItemIntoVisitor<'s>>(self, visitor: &mut V) {
+        match self {
+            Ok(elem) => elem.into_visit_item(visitor),
+            Err(elem) => elem.into_visit_item(visitor),
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemIntoVisitable<'s>> ItemIntoVisitable<'s> for Vec<T> {
+    fn into_visit_item<V: ItemIntoVisitor<'s>>(self, visitor: &mut V) {
+        self.into_iter().for_each(|elem| elem.into_visit_item(visitor));
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemIntoVisitable<'s>> ItemIntoVisitable<'s> for NonEmptyVec<T> {
+    fn into_visit_item<V: ItemIntoVisitor<'s>>(self, visitor: &mut V) {
+        self.elems.into_visit_item(visitor)
+    }
+}
+
+
+// === Parallel Traversal ===
+
+/// The visitor trait allowing for [`Item`] traversal on multiple threads. Sibling subtrees are
+/// visited in parallel, each by a visitor [split](Self::split) from the current one, and the split
+/// visitors are [merged](Self::merge) back in the order of the subtrees. If a split visitor stops
+/// the traversal, its siblings may still have been visited, but the later ones are not merged.
+///
+/// The parallel traversal starts at the nodes deriving [`Visitor`] with `#[visitor(parallel)]`,
+/// like the statements of a [`BodyBlock`], and continues sequentially below them.
+#[cfg(feature = "debug")]
+pub trait ParItemVisitor<'s, 'a>: ItemVisitor<'s, 'a> + Send + Sync {
+    /// A visitor for a subtree visited on another thread.
+    fn split(&self) -> Self;
+    /// Include the results of a visitor split from this one.
+    fn merge(&mut self, other: Self);
+}
+
+/// The visitable trait traversing on multiple threads. See [`ParItemVisitor`] to learn more.
+#[cfg(feature = "debug")]
+#[allow(missing_docs)]
+pub trait ParItemVisitable<'s, 'a> {
+    fn par_visit_item<V: ParItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()>;
+}
+
+#[cfg(feature = "debug")]
+impl<'s, 'a, T: ParItemVisitable<'s, 'a>> ParItemVisitable<'s, 'a> for Option<T> {
+    fn par_visit_item<V: ParItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        match self {
+            Some(elem) => elem.par_visit_item(visitor),
+            None => ControlFlow::Continue(()),
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, 'a, T: ParItemVisitable<'s, 'a>, E: ParItemVisitable<'s, 'a>> ParItemVisitable<'s, 'a>
+    for Result<T, E>
+{
+    fn par_visit_item<V: ParItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        match self {
+            Ok(elem) => elem.par_visit_item(visitor),
+            Err(elem) => elem.par_visit_item(visitor),
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, 'a, T: ParItemVisitable<'s, 'a>> ParItemVisitable<'s, 'a> for Box<T> {
+    fn par_visit_item<V: ParItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        Box::as_ref(self).par_visit_item(visitor)
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, 'a, T: ItemVisitable<'s, 'a> + Sync> ParItemVisitable<'s, 'a> for Vec<T> {
+    fn par_visit_item<V: ParItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        par_visit_elements(self, visitor)
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, 'a, T: ItemVisitable<'s, 'a> + Sync> ParItemVisitable<'s, 'a> for NonEmptyVec<T> {
+    fn par_visit_item<V: ParItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        par_visit_elements(self, visitor)
+    }
+}
+
+/// Visit the elements in parallel, each sequentially with a visitor split from `visitor`.
+#[cfg(feature = "debug")]
+fn par_visit_elements<'s, 'a, T, V>(elements: &'a [T], visitor: &mut V) -> ControlFlow<()>
+where
+    T: ItemVisitable<'s, 'a> + Sync,
+    V: ParItemVisitor<'s, 'a>, {
+    use rayon::prelude::*;
+    let parent = &*visitor;
+    let visited: Vec<_> = elements
+        .par_iter()
+        .map(|elem| {
+            let mut split = parent.split();
+            let flow = elem.visit_item(&mut split);
+            (split, flow)
+        })
+        .collect();
+    for (split, flow) in visited {
+        visitor.merge(split);
+        flow?;
+    }
+    ControlFlow::Continue(())
+}
+
+
+// === Node Kinds ===
+
+/// A value for every [`NodeKind`], like a handler of the nodes of the kind, or whether a visitor is
+/// interested in them. Looking a kind up is an array access, so a visitor can check the
+/// [kind](Variant::kind) of every [`Tree`] before descending into it.
+#[cfg(feature = "debug")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KindTable<T> {
+    entries: [T; NodeKind::COUNT],
+}
+
+#[cfg(feature = "debug")]
+impl<T> KindTable<T> {
+    /// Constructor. Computes the value for every kind.
+    pub fn from_fn(f: impl FnMut(NodeKind) -> T) -> Self {
+        Self { entries: NodeKind::ALL.map(f) }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<T: Default> Default for KindTable<T> {
+    fn default() -> Self {
+        Self::from_fn(|_| default())
+    }
+}
+
+#[cfg(feature = "debug")]
+impl KindTable<bool> {
+    /// The table of the given kinds, mapping them to `true`.
+    pub fn of(kinds: &[NodeKind]) -> Self {
+        Self::from_fn(|kind| kinds.contains(&kind))
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<T> Index<NodeKind> for KindTable<T> {
+    type Output = T;
+    fn index(&self, kind: NodeKind) -> &T {
+        &self.entries[kind.index()]
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<T> IndexMut<NodeKind> for KindTable<T> {
+    fn index_mut(&mut self, kind: NodeKind) -> &mut T {
+        &mut self.entries[kind.index()]
+    }
+}
+
+
+// === Trait Implementations for Standard Containers ===
+
+// The impls for `Option`, `Result`, `Vec` and `Box` are defined along with the traits.
+
+/// A shared node is traversed like the node. Mutating or consuming it clones the node, unless this
+/// is its only reference, so the other references are not affected.
+#[cfg(feature = "debug")]
+impl<'s, 'a, T: ItemVisitable<'s, 'a>> ItemVisitable<'s, 'a> for Rc<T> {
+    fn visit_item<V: ItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        Rc::as_ref(self).visit_item(visitor)
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemVisitableMut<'s> + Clone> ItemVisitableMut<'s> for Rc<T> {
+    fn visit_item_mut<V: ItemVisitorMut<'s>>(&mut self, visitor: &mut V) {
+        Rc::make_mut(self).visit_item_mut(visitor)
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemFoldable<'s> + Clone> ItemFoldable<'s> for Rc<T> {
+    fn fold_item<F: ItemFolder<'s>>(self, folder: &mut F) -> Self {
+        Rc::new(Rc::unwrap_or_clone(self).fold_item(folder))
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemIntoVisitable<'s> + Clone> ItemIntoVisitable<'s> for Rc<T> {
+    fn into_visit_item<V: ItemIntoVisitor<'s>>(self, visitor: &mut V) {
+        Rc::unwrap_or_clone(self).into_visit_item(visitor)
+    }
+}
+
+/// A borrowed node is traversed like the node, so a type borrowing its fields for longer than the
+/// traversal can derive [`Visitor`]. It cannot be mutated or consumed.
+#[cfg(feature = "debug")]
+impl<'s, 'a, 't: 'a, T: ItemVisitable<'s, 'a>> ItemVisitable<'s, 'a> for &'t T {
+    fn visit_item<V: ItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        T::visit_item(*self, visitor)
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, 'a, 't: 'a, T: ParItemVisitable<'s, 'a>> ParItemVisitable<'s, 'a> for &'t T {
+    fn par_visit_item<V: ParItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        T::par_visit_item(*self, visitor)
+    }
+}
+
+/// Implements the visitable traits for the tuple of the given element types. The elements are
+/// traversed in order.
+macro_rules! tuple_impls {
+    ($($elem:ident),*) => {
+        #[cfg(feature = "debug")]
+        #[allow(non_snake_case)]
+        impl<'s, 'a, $($elem: ItemVisitable<'s, 'a>),*> ItemVisitable<'s, 'a> for ($($elem,)*) {
+            fn visit_item<V: ItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+                let ($($elem,)*) = self;
+                $(ItemVisitable::visit_item($elem, visitor)?;)*
+                ControlFlow::Continue(())
+            }
+        }
+
+        #[cfg(feature = "debug")]
+        #[allow(non_snake_case)]
+        impl<'s, $($elem: ItemVisitableMut<'s>),*> ItemVisitableMut<'s> for ($($elem,)*) {
+            fn visit_item_mut<V: ItemVisitorMut<'s>>(&mut self, visitor: &mut V) {
+                let ($($elem,)*) = self;
+                $(ItemVisitableMut::visit_item_mut($elem, visitor);)*
+            }
+        }
+
+        #[cfg(feature = "debug")]
+        #[allow(non_snake_case)]
+        impl<'s, $($elem: ItemFoldable<'s>),*> ItemFoldable<'s> for ($($elem,)*) {
+            fn fold_item<F: ItemFolder<'s>>(self, folder: &mut F) -> Self {
+                let ($($elem,)*) = self;
+                ($(ItemFoldable::fold_item($elem, folder),)*)
+            }
+        }
+
+        #[cfg(feature = "debug")]
+        #[allow(non_snake_case)]
+        impl<'s, $($elem: ItemIntoVisitable<'s>),*> ItemIntoVisitable<'s> for ($($elem,)*) {
+            fn into_visit_item<V: ItemIntoVisitor<'s>>(self, visitor: &mut V) {
+                let ($($elem,)*) = self;
+                $(ItemIntoVisitable::into_visit_item($elem, visitor);)*
+            }
+        }
+    };
+}
+
+tuple_impls!(A);
+tuple_impls!(A, B);
+tuple_impls!(A, B, C);
+tuple_impls!(A, B, C, D);
+
 
 // === Trait Implementations for Simple Leaf Types ===
 
@@ -1138,6 +1506,12 @@ macro_rules! spanless_leaf_impls {
     ($ty:ty) => {
         #[cfg(feature = "debug")]
         impl<'a, 's> ItemVisitable<'s, 'a> for $ty {}
+        #[cfg(feature = "debug")]
+        impl<'s> ItemVisitableMut<'s> for $ty {}
+        #[cfg(feature = "debug")]
+        impl<'s> ItemFoldable<'s> for $ty {}
+        #[cfg(feature = "debug")]
+        impl<'s> ItemIntoVisitable<'s> for $ty {}
         impl<'s> span::Builder<'s> for $ty {
             fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
                 span
@@ -1158,10 +1532,13 @@ spanless_leaf_impls!(Cow<'static, str>);
 
 #[cfg(feature = "debug")]
 impl<'s, 'a> ItemVisitable<'s, 'a> for Tree<'s> {
-    fn visit_item<V: ItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) {
-        if visitor.visit_item(item::Ref::Tree(self)) {
-            self.variant.visit_item(visitor)
+    fn visit_item<V: ItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        let item = item::Ref::Tree(self);
+        if visitor.try_visit_item(item)? {
+            self.variant.visit_item(visitor)?;
         }
+        visitor.exit_item(item);
+        ControlFlow::Continue(())
     }
 }
 
@@ -1169,19 +1546,142 @@ impl<'s, 'a> ItemVisitable<'s, 'a> for Tree<'s> {
 impl<'s: 'a, 'a, T: 'a> ItemVisitable<'s, 'a> for Token<'s, T>
 where &'a Token<'s, T>: Into<token::Ref<'s, 'a>>
 {
-    fn visit_item<V: ItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) {
-        visitor.visit_item(item::Ref::Token(self.into()));
+    fn visit_item<V: ItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        let item = item::Ref::Token(self.into());
+        visitor.try_visit_item(item)?;
+        visitor.exit_item(item);
+        ControlFlow::Continue(())
     }
 }
 
 #[cfg(feature = "debug")]
 impl<'s, 'a, T: ItemVisitable<'s, 'a>> ItemVisitable<'s, 'a> for Box<T> {
-    fn visit_item<V: ItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) {
+    fn visit_item<V: ItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
         Box::as_ref(self).visit_item(visitor)
     }
 }
 
 
+// === ParItemVisitable special cases ===
+
+#[cfg(feature = "debug")]
+impl<'s, 'a> ParItemVisitable<'s, 'a> for Tree<'s> {
+    fn par_visit_item<V: ParItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        let item = item::Ref::Tree(self);
+        if visitor.try_visit_item(item)? {
+            match &self.variant {
+                Variant::BodyBlock(block) => block.par_visit_item(visitor)?,
+                variant => variant.visit_item(visitor)?,
+            }
+        }
+        visitor.exit_item(item);
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s: 'a, 'a, T: 'a> ParItemVisitable<'s, 'a> for Token<'s, T>
+where &'a Token<'s, T>: Into<token::Ref<'s, 'a>>
+{
+    fn par_visit_item<V: ParItemVisitor<'s, 'a>>(&'a self, visitor: &mut V) -> ControlFlow<()> {
+        self.visit_item(visitor)
+    }
+}
+
+
+// === ItemVisitableMut special cases ===
+
+#[cfg(feature = "debug")]
+impl<'s> ItemVisitableMut<'s> for Tree<'s> {
+    fn visit_item_mut<V: ItemVisitorMut<'s>>(&mut self, visitor: &mut V) {
+        if visitor.visit_item_mut(item::RefMut::Tree(self)) {
+            self.variant.visit_item_mut(visitor)
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T> ItemVisitableMut<'s> for Token<'s, T>
+where T: Copy + Into<token::Variant>
+{
+    fn visit_item_mut<V: ItemVisitorMut<'s>>(&mut self, visitor: &mut V) {
+        visitor.visit_item_mut(item::RefMut::Token(self.into()));
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemVisitableMut<'s>> ItemVisitableMut<'s> for Box<T> {
+    fn visit_item_mut<V: ItemVisitorMut<'s>>(&mut self, visitor: &mut V) {
+        Box::as_mut(self).visit_item_mut(visitor)
+    }
+}
+
+
+// === ItemFoldable special cases ===
+
+#[cfg(feature = "debug")]
+impl<'s> ItemFoldable<'s> for Tree<'s> {
+    fn fold_item<F: ItemFolder<'s>>(self, folder: &mut F) -> Self {
+        folder.fold_tree(self)
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T> ItemFoldable<'s> for Token<'s, T> {
+    fn fold_item<F: ItemFolder<'s>>(self, folder: &mut F) -> Self {
+        folder.fold_token(self)
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemFoldable<'s>> ItemFoldable<'s> for Box<T> {
+    fn fold_item<F: ItemFolder<'s>>(mut self, folder: &mut F) -> Self {
+        *self = (*self).fold_item(folder);
+        self
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s> Tree<'s> {
+    /// Fold the children of the tree, keeping the tree itself. See [`ItemFolder`].
+    pub fn fold_children<F: ItemFolder<'s>>(self, folder: &mut F) -> Self {
+        Tree { variant: self.variant.fold_item(folder), ..self }
+    }
+}
+
+
+// === ItemIntoVisitable special cases ===
+
+#[cfg(feature = "debug")]
+impl<'s> ItemIntoVisitable<'s> for Tree<'s> {
+    fn into_visit_item<V: ItemIntoVisitor<'s>>(self, visitor: &mut V) {
+        visitor.take_tree(self)
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: Into<token::Variant>> ItemIntoVisitable<'s> for Token<'s, T> {
+    fn into_visit_item<V: ItemIntoVisitor<'s>>(self, visitor: &mut V) {
+        visitor.take_token(self.map_variant(Into::into))
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s, T: ItemIntoVisitable<'s>> ItemIntoVisitable<'s> for Box<T> {
+    fn into_visit_item<V: ItemIntoVisitor<'s>>(self, visitor: &mut V) {
+        (*self).into_visit_item(visitor)
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s> Tree<'s> {
+    /// Visit the children of the tree by value, dropping the tree itself. See [`ItemIntoVisitor`].
+    pub fn into_visit_children<V: ItemIntoVisitor<'s>>(self, visitor: &mut V) {
+        self.variant.into_visit_item(visitor)
+    }
+}
+
+
 
 // ==========================
 // === CodePrinterVisitor ===
@@ -1216,20 +1716,77 @@ impl<'s> Tree<'s> {
     /// Code generator of this AST.
     pub fn code(&self) -> String {
         let mut visitor = CodePrinterVisitor::default();
-        self.visit_item(&mut visitor);
+        let _ = self.visit_item(&mut visitor);
         visitor.code
     }
 
     /// Return source code of this AST, excluding initial whitespace.
     pub fn trimmed_code(&self) -> String {
         let mut visitor = CodePrinterVisitor::default();
-        self.variant.visit_item(&mut visitor);
+        let _ = self.variant.visit_item(&mut visitor);
         visitor.code
     }
 }
 
 
 
+// =================
+// === TreeStats ===
+// =================
+
+/// Size metrics of the traversed items, for reporting the size of an AST. It is a visitor, so it
+/// can collect the metrics of any [`ItemVisitable`] value; [`Tree::stats`] computes the metrics of
+/// a tree.
+#[cfg(feature = "debug")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TreeStats {
+    /// The number of the trees.
+    pub trees:     usize,
+    /// The number of the tokens.
+    pub tokens:    usize,
+    /// The number of the trees on the longest path from a traversed tree to a leaf tree.
+    pub max_depth: usize,
+    /// The number of the trees of every kind.
+    pub kinds:     KindTable<usize>,
+    /// The number of the trees entered and not yet left.
+    depth:         usize,
+}
+
+#[cfg(feature = "debug")]
+impl Visitor for TreeStats {}
+#[cfg(feature = "debug")]
+impl<'s, 'a> ItemVisitor<'s, 'a> for TreeStats {
+    fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+        match item {
+            item::Ref::Tree(tree) => {
+                self.trees += 1;
+                self.kinds[tree.kind()] += 1;
+                self.depth += 1;
+                self.max_depth = self.max_depth.max(self.depth);
+            }
+            item::Ref::Token(_) => self.tokens += 1,
+        }
+        true
+    }
+
+    fn exit_item(&mut self, item: item::Ref<'s, 'a>) {
+        if let item::Ref::Tree(_) = item {
+            self.depth -= 1;
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<'s> Tree<'s> {
+    /// The size metrics of this AST.
+    pub fn stats(&self) -> TreeStats {
+        let mut stats = TreeStats::default();
+        let _ = self.visit_item(&mut stats);
+        stats
+    }
+}
+
+
 // =====================
 // === ItemFnVisitor ===
 // =====================
@@ -1251,7 +1808,7 @@ impl<'s> Tree<'s> {
                 false
             }
         }
-        self.variant.visit_item(&mut ItemFnVisitor { f });
+        let _ = self.variant.visit_item(&mut ItemFnVisitor { f });
     }
 
     /// Apply the provided function recursively to each [`Tree`] that is a descendant of the node.
@@ -1271,7 +1828,114 @@ impl<'s> Tree<'s> {
                 true
             }
         }
-        self.variant.visit_item(&mut ItemFnVisitor { f });
+        let _ = self.variant.visit_item(&mut ItemFnVisitor { f });
+    }
+
+    /// Find the first [`Tree`] that is a descendant of the node and satisfies the predicate. The
+    /// descendants are checked in the pre-order, and the traversal stops at the first match.
+    pub fn find_tree<'a, F>(&'a self, f: F) -> Option<&'a Tree<'s>>
+    where F: FnMut(&'a Tree<'s>) -> bool {
+        struct FindVisitor<'s, 'a, F> {
+            f:     F,
+            found: Option<&'a Tree<'s>>,
+        }
+        impl<'s, 'a, F> Visitor for FindVisitor<'s, 'a, F> {}
+        impl<'s: 'a, 'a, F> ItemVisitor<'s, 'a> for FindVisitor<'s, 'a, F>
+        where F: FnMut(&'a Tree<'s>) -> bool
+        {
+            fn try_visit_item(&mut self, item: item::Ref<'s, 'a>) -> ControlFlow<(), bool> {
+                if let item::Ref::Tree(tree) = item {
+                    if (self.f)(tree) {
+                        self.found = Some(tree);
+                        return ControlFlow::Break(());
+                    }
+                }
+                ControlFlow::Continue(true)
+            }
+        }
+        let mut visitor = FindVisitor { f, found: None };
+        let _ = self.variant.visit_item(&mut visitor);
+        visitor.found
+    }
+
+    /// Visit the node and its descendants with the fallible visitor, stopping at the first error.
+    pub fn try_visit<'a, V>(&'a self, visitor: &mut V) -> Result<(), V::Error>
+    where V: TryItemVisitor<'s, 'a> {
+        struct TryVisitor<'v, V, E> {
+            visitor: &'v mut V,
+            error:   Option<E>,
+        }
+        impl<V, E> Visitor for TryVisitor<'_, V, E> {}
+        impl<'s, 'a, V> ItemVisitor<'s, 'a> for TryVisitor<'_, V, <V as TryItemVisitor<'s, 'a>>::Error>
+        where V: TryItemVisitor<'s, 'a>
+        {
+            fn try_visit_item(&mut self, item: item::Ref<'s, 'a>) -> ControlFlow<(), bool> {
+                match self.visitor.visit_item_try(item) {
+                    Ok(visit_children) => ControlFlow::Continue(visit_children),
+                    Err(error) => {
+                        self.error = Some(error);
+                        ControlFlow::Break(())
+                    }
+                }
+            }
+        }
+        let mut try_visitor = TryVisitor { visitor, error: None };
+        let _ = self.visit_item(&mut try_visitor);
+        try_visitor.error.map_or(Ok(()), Err)
+    }
+
+    /// Visit the node and its descendants with their depth, without descending into the children of
+    /// the items at `max_depth`, so that pathologically nested trees can be traversed without
+    /// exhausting the stack. Returns whether any descendants were cut off by the limit.
+    pub fn visit_with_depth_limit<'a, V>(&'a self, visitor: &mut V, max_depth: usize) -> bool
+    where V: DepthItemVisitor<'s, 'a> {
+        struct DepthVisitor<'v, V> {
+            visitor:   &'v mut V,
+            depth:     usize,
+            max_depth: usize,
+            truncated: bool,
+        }
+        impl<V> Visitor for DepthVisitor<'_, V> {}
+        impl<'s, 'a, V> ItemVisitor<'s, 'a> for DepthVisitor<'_, V>
+        where V: DepthItemVisitor<'s, 'a>
+        {
+            fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+                let visit_children = self.visitor.visit_item_at(item, self.depth);
+                self.depth += 1;
+                let cut_off = self.depth > self.max_depth && matches!(item, item::Ref::Tree(_));
+                self.truncated |= visit_children && cut_off;
+                visit_children && !cut_off
+            }
+
+            fn exit_item(&mut self, _item: item::Ref<'s, 'a>) {
+                self.depth -= 1;
+            }
+        }
+        let mut depth_visitor = DepthVisitor { visitor, depth: 0, max_depth, truncated: false };
+        let _ = self.visit_item(&mut depth_visitor);
+        depth_visitor.truncated
+    }
+
+    /// Apply the provided function recursively to each [`Tree`] that is a descendant of the node,
+    /// allowing it to modify the tree in place. The descendants of a modified tree are visited
+    /// after the modification.
+    pub fn visit_trees_mut<F>(&mut self, f: F)
+    where F: FnMut(&mut Tree<'s>) {
+        struct TreeFnVisitor<F> {
+            f: F,
+        }
+        impl<F> Visitor for TreeFnVisitor<F> {}
+        impl<'s, F> ItemVisitorMut<'s> for TreeFnVisitor<F>
+        where F: FnMut(&mut Tree<'s>)
+        {
+            fn visit_item_mut(&mut self, item: item::RefMut<'s, '_>) -> bool {
+                if let item::RefMut::Tree(tree) = item {
+                    (self.f)(tree);
+                }
+                true
+            }
+        }
+        self.variant.visit_item_mut(&mut TreeFnVisitor { f });
     }
 }
 
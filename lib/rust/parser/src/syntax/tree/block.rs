@@ -11,8 +11,8 @@ use crate::syntax::statement::BodyBlockParser;
 // =============
 
 /// A line of code.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Debug, Clone, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Debug, Clone, PartialEq, Eq, Reflect, Serialize, Deserialize, SpanBuilder)]
 pub struct Line<'s> {
     /// Token ending the previous line, if any.
     pub newline:    token::Newline<'s>,
@@ -36,12 +36,6 @@ impl<'s> From<token::Newline<'s>> for Line<'s> {
     }
 }
 
-impl<'s> span::Builder<'s> for Line<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.newline).add(&mut self.expression)
-    }
-}
-
 
 
 // ==================
@@ -193,8 +187,8 @@ impl<'s> From<Prefix<'s>> for Tree<'s> {
 // ======================
 
 /// The content of a line in an operator block.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Debug, Clone, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Debug, Clone, PartialEq, Eq, Reflect, Serialize, Deserialize, SpanBuilder)]
 pub struct OperatorBlockExpression<'s> {
     /// The operator at the beginning of the line.
     pub operator:   OperatorOrError<'s>,
@@ -221,18 +215,12 @@ fn to_operator_block_expression<'s>(
     }
 }
 
-impl<'s> span::Builder<'s> for OperatorBlockExpression<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.operator).add(&mut self.expression)
-    }
-}
-
 
 // === Operator block lines ====
 
 /// A line in an operator block.
-#[cfg_attr(feature = "debug", derive(Visitor))]
-#[derive(Debug, Clone, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Visitor, VisitorMut, Foldable, IntoVisitor))]
+#[derive(Debug, Clone, PartialEq, Eq, Reflect, Serialize, Deserialize, SpanBuilder)]
 pub struct OperatorLine<'s> {
     /// Token ending the previous line, if any.
     pub newline:    token::Newline<'s>,
@@ -246,12 +234,6 @@ impl<'s> From<token::Newline<'s>> for OperatorLine<'s> {
     }
 }
 
-impl<'s> span::Builder<'s> for OperatorLine<'s> {
-    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
-        span.add(&mut self.newline).add(&mut self.expression)
-    }
-}
-
 
 
 // =====================
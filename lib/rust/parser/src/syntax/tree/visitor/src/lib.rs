@@ -1,4 +1,6 @@
-//! Definition of [`Visitor`] deriving. It implements the visitor pattern for [`Ast`].
+//! Definition of [`Visitor`], [`VisitorMut`], [`Foldable`], [`IntoVisitor`] and [`SpanBuilder`]
+//! deriving. They implement the visitor pattern for [`Ast`]. The types defined in other crates get
+//! the same impls with [`visitor_remote!`].
 
 // === Non-Standard Linter Configuration ===
 #![allow(clippy::option_map_unit_fn)]
@@ -13,15 +15,20 @@
 
 extern crate proc_macro;
 
+use enso_macro_utils::attribute::Arguments;
 use enso_macro_utils::field_names;
+use enso_macro_utils::generics::ImplGenerics;
 use enso_macro_utils::identifier_sequence;
-use enso_macro_utils::index_sequence;
 use proc_macro2::TokenStream;
+use quote::format_ident;
 use quote::quote;
+use quote::ToTokens;
+use syn::parse_quote;
 use syn::Data;
 use syn::DataEnum;
 use syn::DataStruct;
 use syn::DeriveInput;
+use syn::Field;
 use syn::Fields;
 use syn::Variant;
 
@@ -30,100 +37,688 @@ use syn::Variant;
 /// ======================
 /// === Derive Visitor ===
 /// ======================
-use quote::ToTokens;
 
 /// Implements [`ItemVisitable`].
 /// This trait is defined in the [`crate::ast`] module. Macros in this module hardcode the names
 /// of the traits and are not implemented in a generic way because the current Rust implementation
 /// does not understand generic definition. See the [`crate::ast`] module to learn more about the
 /// design and the Rust compiler issue.
-#[proc_macro_derive(Visitor)]
+///
+/// The type can have the `'s` lifetime parameter of the source code and the `'a` lifetime
+/// parameter of the traversal borrow. Other lifetime parameters are bounded by `'a`, so the data
+/// they borrow outlives the traversal. Such a type only supports the traversal by shared
+/// references: the other derives report an error for it.
+///
+/// The fields are traversed in the declaration order, except the ones marked with
+/// `#[visitor(skip)]`. A field marked with `#[visitor(order = N)]` is traversed as if it was
+/// declared at the index `N`, so e.g. trailing tokens stored in leading fields can be visited in
+/// the source order.
+///
+/// Other visitor hierarchies can reuse the derive by naming their traits and method:
+/// `#[visitor(trait = "Visitable", visitor_trait = "Visitor", method = "visit")]`. They must have
+/// the shape of the default ones: the traits are generic over the `'s` and `'a` lifetimes, and the
+/// method takes the visitor and returns [`std::ops::ControlFlow`].
+///
+/// With `#[visitor(parallel)]` on the type, also implements [`ParItemVisitable`], traversing the
+/// fields in parallel with `rayon`, each with its own [`ParItemVisitor`].
+///
+/// With `#[visitor(kind = NodeKind)]` on an enum, also defines the `NodeKind` enum with a variant
+/// for every variant of the type, and the `kind` method returning it. Visitors can filter the nodes
+/// by their kinds without matching on the fields.
+#[proc_macro_derive(Visitor, attributes(visitor))]
 pub fn derive_visitor(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let decl = syn::parse_macro_input!(input as DeriveInput);
-    let ident = &decl.ident;
-    let (impl_generics, ty_generics, _inherent_where_clause_opt) = &decl.generics.split_for_impl();
-    let body_item = gen_body(quote!(ItemVisitable::visit_item), &decl.data, false);
-
-    let impl_generics_vec: Vec<_> = impl_generics.to_token_stream().into_iter().collect();
-    let impl_generics_len = impl_generics_vec.len();
-    let mut impl_generics;
-    if impl_generics_len > 0 {
-        let v: Vec<_> = impl_generics_vec.into_iter().take(impl_generics_len - 1).skip(1).collect();
-        impl_generics = quote!(#(#v)*);
-        if !v.is_empty() {
-            impl_generics = quote!(#impl_generics,);
-        }
-    } else {
-        impl_generics = quote!('s,);
-    }
-    let impl_generics = quote!(<#impl_generics 'a>);
+    derive(input.into()).into()
+}
+
+/// Implements [`ItemVisitableMut`], the counterpart of [`ItemVisitable`] traversing the fields by
+/// mutable references. See [`derive_visitor`] to learn more.
+#[proc_macro_derive(VisitorMut, attributes(visitor))]
+pub fn derive_visitor_mut(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_mut(input.into()).into()
+}
+
+/// Implements [`ItemFoldable`], consuming the node and rebuilding it from the folded fields. The
+/// fields marked with `#[visitor(skip)]` are moved to the new node unchanged. See
+/// [`derive_visitor`] to learn more.
+#[proc_macro_derive(Foldable, attributes(visitor))]
+pub fn derive_foldable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_fold(input.into()).into()
+}
+
+/// Implements [`ItemIntoVisitable`], the counterpart of [`ItemVisitable`] consuming the node and
+/// passing the fields by value, so the visitor can take them without cloning. The fields marked
+/// with `#[visitor(skip)]` are dropped. See [`derive_visitor`] to learn more.
+#[proc_macro_derive(IntoVisitor, attributes(visitor))]
+pub fn derive_into_visitor(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_into(input.into()).into()
+}
+
+/// Implements [`span::Builder`], adding the fields to the span in the order they are visited by
+/// [`derive_visitor`], so the two traversals cannot get out of sync. The parser builds the spans in
+/// every build, so unlike the other derives this one is not used only with the `debug` feature.
+#[proc_macro_derive(SpanBuilder, attributes(visitor))]
+pub fn derive_span_builder(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_spans(input.into()).into()
+}
 
-    let output = quote! {
-        impl #impl_generics ItemVisitable #impl_generics for #ident #ty_generics {
-            fn visit_item<T: ItemVisitor #impl_generics>(&'a self, visitor:&mut T) {
-                #body_item
+/// Implements the derived traits for a type defined in another crate, which cannot be annotated
+/// with the derives. The input is a mirror of the type's definition, with the derives to apply and
+/// the path of the actual type:
+///
+/// ```ignore
+/// visitor_remote! {
+///     #[derive(Visitor, VisitorMut)]
+///     #[visitor(remote = other_crate::Wrapper)]
+///     struct Wrapper<'s> {
+///         pub token: Token<'s>,
+///         pub tree:  Tree<'s>,
+///     }
+/// }
+/// ```
+///
+/// The mirror itself is not emitted. It must have the generics and the fields of the actual type,
+/// and the fields must be accessible from the invoking module. The traversal is the same as if the
+/// actual type had the derives.
+#[proc_macro]
+pub fn visitor_remote(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    remote(input.into()).into()
+}
+
+/// Implementation of [`visitor_remote`].
+fn remote(input: TokenStream) -> TokenStream {
+    let expand = |decl: DeriveInput| {
+        if ContainerOptions::parse(&decl)?.remote.is_none() {
+            let message = "Expected `#[visitor(remote = path::to::Type)]` on the mirror type.";
+            return Err(syn::Error::new_spanned(&decl.ident, message));
+        }
+        let derives = decl.attrs.iter().filter(|attr| attr.path().is_ident("derive"));
+        let mut output = TokenStream::new();
+        for attr in derives {
+            let paths = attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+            )?;
+            for path in paths {
+                let derive = match path.get_ident().map(|ident| ident.to_string()).as_deref() {
+                    Some("Visitor") => derive,
+                    Some("VisitorMut") => derive_mut,
+                    Some("Foldable") => derive_fold,
+                    Some("IntoVisitor") => derive_into,
+                    Some("SpanBuilder") => derive_spans,
+                    _ => {
+                        let message = "Expected one of: `Visitor`, `VisitorMut`, `Foldable`, \
+                            `IntoVisitor`, `SpanBuilder`.";
+                        return Err(syn::Error::new_spanned(path, message));
+                    }
+                };
+                output.extend(derive(decl.to_token_stream()));
             }
         }
+        Ok(output)
     };
+    with_decl(input, expand)
+}
 
-    output.into()
+/// Parse the derive input and generate the output, or report the error at the offending tokens.
+fn with_decl(
+    input: TokenStream,
+    f: impl FnOnce(DeriveInput) -> syn::Result<TokenStream>,
+) -> TokenStream {
+    syn::parse2::<DeriveInput>(input).and_then(f).unwrap_or_else(|error| error.to_compile_error())
 }
 
-fn gen_body(f: TokenStream, data: &Data, is_mut: bool) -> TokenStream {
-    match data {
-        Data::Struct(t) => body_for_struct(&f, t, is_mut),
-        Data::Enum(t) => body_for_enum(&f, t),
-        Data::Union(_) => panic!("Untagged union types not supported."),
-    }
+/// Bound the lifetime parameters of the type other than `'s` and `'a` by the `'a` lifetime of the
+/// traversal borrow, so the data they borrow outlives the traversal.
+fn bound_other_lifetimes(generics: ImplGenerics, base: &syn::Generics) -> ImplGenerics {
+    let others = base.lifetimes().map(|param| &param.lifetime);
+    let others = others.filter(|lifetime| lifetime.ident != "s" && lifetime.ident != "a");
+    others.fold(generics, |generics, lifetime| generics.lifetime(parse_quote!(#lifetime: 'a)))
 }
 
-fn body_for_struct(f: &TokenStream, data: &DataStruct, is_mut: bool) -> TokenStream {
-    match &data.fields {
-        Fields::Unit => quote!({}),
-        Fields::Unnamed(fields) => {
-            let indices = index_sequence(fields.unnamed.len());
-            if is_mut {
-                quote!(#( #f(&mut self.#indices, visitor); )*)
-            } else {
-                quote!(#( #f(&self.#indices, visitor); )*)
+/// Report the lifetime parameters other than `'s`. The traits traversing the nodes by mutable
+/// references or by value are implemented only for the `'s` lifetime of the source code, so they
+/// cannot be derived for a type borrowing its fields.
+fn check_owned(generics: &syn::Generics, derive: &str) -> syn::Result<()> {
+    let borrowed = generics.lifetimes().filter(|param| param.lifetime.ident != "s").map(|param| {
+        let lifetime = &param.lifetime;
+        let message = format!(
+            "{derive} cannot be derived for a type borrowing data for the `{lifetime}` lifetime."
+        );
+        syn::Error::new_spanned(lifetime, message)
+    });
+    borrowed
+        .reduce(|mut errors, error| {
+            errors.combine(error);
+            errors
+        })
+        .map_or(Ok(()), Err)
+}
+
+/// The error reported for a union, which has no variant telling which field to traverse.
+fn union_error(data: &syn::DataUnion) -> syn::Error {
+    syn::Error::new_spanned(data.union_token, "Untagged union types are not supported.")
+}
+
+/// Implementation of [`derive_visitor`], operating on [`proc_macro2`] tokens so it can be tested.
+fn derive(input: TokenStream) -> TokenStream {
+    with_decl(input, |decl| {
+        let options = ContainerOptions::parse(&decl)?;
+        let ident = options.target(&decl);
+        let ContainerOptions { visitable, visitor, method, .. } = &options;
+        let generics = ImplGenerics::new(&decl.generics)
+            .lifetime(parse_quote!('s))
+            .lifetime(parse_quote!('a))
+            .bound_type_params(parse_quote!(#visitable<'s, 'a>));
+        let generics = bound_other_lifetimes(generics, &decl.generics);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let t = method_type_param(&decl.generics);
+        let traversal = Traversal {
+            f:         quote!(#visitable::#method),
+            access:    Access::Ref,
+            propagate: true,
+        };
+        let body_item = gen_body(&traversal, &decl.data)?;
+        let par_visitor =
+            options.parallel.then(|| derive_par_visitor(&decl, &ident)).transpose()?;
+        let kinds = options.kind.as_ref().map(|kind| derive_kinds(&decl, &options, kind));
+        let kinds = kinds.transpose()?;
+        Ok(quote! {
+            impl #impl_generics #visitable<'s, 'a> for #ident #ty_generics #where_clause {
+                fn #method<#t: #visitor<'s, 'a>>(&'a self, visitor:&mut #t)
+                -> std::ops::ControlFlow<()> {
+                    #body_item
+                    std::ops::ControlFlow::Continue(())
+                }
+            }
+            #par_visitor
+            #kinds
+        })
+    })
+}
+
+/// Implementation of [`derive_visitor_mut`].
+fn derive_mut(input: TokenStream) -> TokenStream {
+    with_decl(input, |decl| {
+        check_owned(&decl.generics, "`VisitorMut`")?;
+        let ident = ContainerOptions::parse(&decl)?.target(&decl);
+        let generics = ImplGenerics::new(&decl.generics)
+            .lifetime(parse_quote!('s))
+            .bound_type_params(parse_quote!(ItemVisitableMut<'s>));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let t = method_type_param(&decl.generics);
+        let f = quote!(ItemVisitableMut::visit_item_mut);
+        let traversal = Traversal { f, access: Access::Mut, propagate: false };
+        let body_item = gen_body(&traversal, &decl.data)?;
+        Ok(quote! {
+            impl #impl_generics ItemVisitableMut<'s> for #ident #ty_generics #where_clause {
+                fn visit_item_mut<#t: ItemVisitorMut<'s>>(&mut self, visitor:&mut #t) {
+                    #body_item
+                }
             }
+        })
+    })
+}
+
+/// Implementation of [`derive_into_visitor`].
+fn derive_into(input: TokenStream) -> TokenStream {
+    with_decl(input, |decl| {
+        check_owned(&decl.generics, "`IntoVisitor`")?;
+        let ident = ContainerOptions::parse(&decl)?.target(&decl);
+        let generics = ImplGenerics::new(&decl.generics)
+            .lifetime(parse_quote!('s))
+            .bound_type_params(parse_quote!(ItemIntoVisitable<'s>));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let t = method_type_param(&decl.generics);
+        let f = quote!(ItemIntoVisitable::into_visit_item);
+        let traversal = Traversal { f, access: Access::Owned, propagate: false };
+        let body_item = gen_body(&traversal, &decl.data)?;
+        Ok(quote! {
+            impl #impl_generics ItemIntoVisitable<'s> for #ident #ty_generics #where_clause {
+                fn into_visit_item<#t: ItemIntoVisitor<'s>>(self, visitor:&mut #t) {
+                    #body_item
+                }
+            }
+        })
+    })
+}
+
+/// Implementation of [`derive_foldable`].
+fn derive_fold(input: TokenStream) -> TokenStream {
+    with_decl(input, |decl| {
+        check_owned(&decl.generics, "`Foldable`")?;
+        let ident = ContainerOptions::parse(&decl)?.target(&decl);
+        let generics = ImplGenerics::new(&decl.generics)
+            .lifetime(parse_quote!('s))
+            .bound_type_params(parse_quote!(ItemFoldable<'s>));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let t = method_type_param(&decl.generics);
+        let f = quote!(ItemFoldable::fold_item);
+        let body = match &decl.data {
+            Data::Struct(data) => {
+                let (pattern, value) = fold_fields(&f, quote!(Self), &data.fields)?;
+                quote!(let #pattern = self; #value)
+            }
+            Data::Enum(data) => {
+                let arms = data.variants.iter().map(|variant| {
+                    let variant_ident = &variant.ident;
+                    let (pattern, value) =
+                        fold_fields(&f, quote!(Self::#variant_ident), &variant.fields)?;
+                    Ok(quote!(#pattern => #value,))
+                });
+                let arms = arms.collect::<syn::Result<Vec<_>>>()?;
+                quote!(match self { #(#arms)* })
+            }
+            Data::Union(data) => return Err(union_error(data)),
+        };
+        Ok(quote! {
+            impl #impl_generics ItemFoldable<'s> for #ident #ty_generics #where_clause {
+                fn fold_item<#t: ItemFolder<'s>>(self, folder:&mut #t) -> Self {
+                    #body
+                }
+            }
+        })
+    })
+}
+
+/// Implementation of [`derive_span_builder`].
+fn derive_spans(input: TokenStream) -> TokenStream {
+    with_decl(input, |decl| {
+        check_owned(&decl.generics, "`SpanBuilder`")?;
+        let ident = ContainerOptions::parse(&decl)?.target(&decl);
+        let generics = ImplGenerics::new(&decl.generics)
+            .lifetime(parse_quote!('s))
+            .bound_type_params(parse_quote!(span::Builder<'s>));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let body = match &decl.data {
+            Data::Struct(data) => {
+                let members = visited_members(&data.fields)?;
+                quote!(span #(.add(&mut self.#members))*)
+            }
+            Data::Enum(data) => {
+                let arms = data.variants.iter().map(|variant| {
+                    let (pattern, bindings) = variant_pattern(variant)?;
+                    Ok(quote!(#pattern => span #(.add(#bindings))*,))
+                });
+                let arms = arms.collect::<syn::Result<Vec<_>>>()?;
+                quote!(match self { #(#arms)* })
+            }
+            Data::Union(data) => return Err(union_error(data)),
+        };
+        Ok(quote! {
+            impl #impl_generics span::Builder<'s> for #ident #ty_generics #where_clause {
+                fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+                    #body
+                }
+            }
+        })
+    })
+}
+
+/// Implements [`ParItemVisitable`] for the `#[visitor(parallel)]` option of [`derive_visitor`].
+fn derive_par_visitor(decl: &DeriveInput, ident: &TokenStream) -> syn::Result<TokenStream> {
+    let generics = ImplGenerics::new(&decl.generics)
+        .lifetime(parse_quote!('s))
+        .lifetime(parse_quote!('a))
+        .bound_type_params(parse_quote!(ParItemVisitable<'s, 'a>))
+        .bound_type_params(parse_quote!(Sync));
+    let generics = bound_other_lifetimes(generics, &decl.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let t = method_type_param(&decl.generics);
+    let body = match &decl.data {
+        Data::Struct(data) => {
+            let members = visited_members(&data.fields)?;
+            par_visit_fields(
+                &members.iter().map(|member| quote!(&self.#member)).collect::<Vec<_>>(),
+            )
         }
-        Fields::Named(fields) => {
-            let names = field_names(fields);
-            if is_mut {
-                quote!(#( #f(&mut self.#names, visitor); )*)
-            } else {
-                quote!(#( #f(&self.#names, visitor); )*)
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let (pattern, bindings) = variant_pattern(variant)?;
+                let fields = bindings.iter().map(|binding| quote!(#binding)).collect::<Vec<_>>();
+                let body = par_visit_fields(&fields);
+                Ok(quote!(#pattern => #body,))
+            });
+            let arms = arms.collect::<syn::Result<Vec<_>>>()?;
+            quote!(match self { #(#arms)* })
+        }
+        Data::Union(data) => return Err(union_error(data)),
+    };
+    Ok(quote! {
+        impl #impl_generics ParItemVisitable<'s, 'a> for #ident #ty_generics #where_clause {
+            fn par_visit_item<#t: ParItemVisitor<'s, 'a>>(&'a self, visitor:&mut #t)
+            -> std::ops::ControlFlow<()> {
+                #body
+            }
+        }
+    })
+}
+
+/// Defines the enum of the kinds of the variants and the `kind` method for the
+/// `#[visitor(kind = Kind)]` option of [`derive_visitor`]. A visitor can match on the kind of a
+/// node, or look it up in a table indexed by the kinds, before descending into the node.
+fn derive_kinds(
+    decl: &DeriveInput,
+    options: &ContainerOptions,
+    kind: &syn::Ident,
+) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &decl.data else {
+        return Err(syn::Error::new_spanned(kind, "Only an enum can have node kinds."));
+    };
+    if options.remote.is_some() {
+        return Err(syn::Error::new_spanned(kind, "A remote type cannot have node kinds."));
+    }
+    let ident = &decl.ident;
+    let vis = &decl.vis;
+    let (impl_generics, ty_generics, where_clause) = decl.generics.split_for_impl();
+    let variants = data.variants.iter().map(|variant| &variant.ident).collect::<Vec<_>>();
+    let names = variants.iter().map(|variant| variant.to_string());
+    let docs = variants.iter().map(|variant| format!("The kind of [`{ident}::{variant}`]."));
+    let count = variants.len();
+    let doc = format!("The kinds of the variants of [`{ident}`].");
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #vis enum #kind {
+            #(#[doc = #docs] #variants,)*
+        }
+        impl #kind {
+            /// The number of the kinds.
+            pub const COUNT: usize = #count;
+            /// All the kinds, in the order of the declaration.
+            pub const ALL: [Self; #count] = [#(Self::#variants),*];
+            /// The position of the kind in [`Self::ALL`].
+            pub const fn index(self) -> usize {
+                self as usize
             }
+            /// The name of the variant.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    #(Self::#variants => #names,)*
+                }
+            }
+        }
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// The kind of the variant.
+            pub fn kind(&self) -> #kind {
+                match self {
+                    #(Self::#variants { .. } => #kind::#variants,)*
+                }
+            }
+        }
+    })
+}
+
+/// The expression visiting the fields in parallel. Every field is visited with a visitor split from
+/// `visitor`, and the split visitors are merged back in the order of the fields.
+fn par_visit_fields(fields: &[TokenStream]) -> TokenStream {
+    let visit = |field: &TokenStream, visitor: TokenStream| quote!(ParItemVisitable::par_visit_item(#field, #visitor));
+    match fields {
+        [] => quote!(std::ops::ControlFlow::Continue(())),
+        [field] => visit(field, quote!(visitor)),
+        _ => {
+            let visitors =
+                (0..fields.len()).map(|i| format_ident!("visitor{i}")).collect::<Vec<_>>();
+            let flows = (0..fields.len()).map(|i| format_ident!("flow{i}")).collect::<Vec<_>>();
+            let last = fields.len() - 1;
+            let last_visitor = &visitors[last];
+            let mut join = visit(&fields[last], quote!(&mut #last_visitor));
+            let mut pattern = flows[last].to_token_stream();
+            for i in (0..last).rev() {
+                let visitor = &visitors[i];
+                let first = visit(&fields[i], quote!(&mut #visitor));
+                let flow = &flows[i];
+                join = quote!(rayon::join(|| #first, || #join));
+                pattern = quote!((#flow, #pattern));
+            }
+            quote!({
+                #(let mut #visitors = visitor.split();)*
+                let #pattern = #join;
+                #(
+                    visitor.merge(#visitors);
+                    #flows?;
+                )*
+                std::ops::ControlFlow::Continue(())
+            })
         }
     }
 }
 
-/// Prepares a match arm for a single variant that `clone_ref`s such value.
-fn arm_for_variant(f: &TokenStream, variant: &Variant) -> TokenStream {
-    let variant_ident = &variant.ident;
-    match &variant.fields {
-        Fields::Unit => {
-            quote!(Self::#variant_ident => {})
+/// The type parameter of the generated method, named `T` unless the type has a parameter of that
+/// name.
+fn method_type_param(generics: &syn::Generics) -> syn::Ident {
+    let mut name = String::from("T");
+    while generics.type_params().any(|param| param.ident == name) {
+        name.push('_');
+    }
+    syn::Ident::new(&name, proc_macro2::Span::call_site())
+}
+
+/// The `#[visitor(...)]` arguments of the derived type. All the derives accept the same arguments,
+/// so they can be applied together, but only [`derive_visitor`] uses them.
+struct ContainerOptions {
+    /// Whether to implement [`ParItemVisitable`] too.
+    parallel:  bool,
+    /// The implemented trait, [`ItemVisitable`] by default.
+    visitable: syn::Path,
+    /// The trait bound of the visitor, [`ItemVisitor`] by default.
+    visitor:   syn::Path,
+    /// The method of [`Self::visitable`], `visit_item` by default.
+    method:    syn::Ident,
+    /// The type to implement the traits for, if the derive input is its mirror. See
+    /// [`visitor_remote`].
+    remote:    Option<syn::Path>,
+    /// The name of the enum of the kinds of the variants to define, if any.
+    kind:      Option<syn::Ident>,
+}
+
+impl ContainerOptions {
+    fn parse(decl: &DeriveInput) -> syn::Result<Self> {
+        let mut arguments = Arguments::parse("visitor", &decl.attrs)?;
+        let parallel = arguments.flag("parallel")?;
+        let visitable = arguments.value("trait")?.unwrap_or_else(|| parse_quote!(ItemVisitable));
+        let visitor =
+            arguments.value("visitor_trait")?.unwrap_or_else(|| parse_quote!(ItemVisitor));
+        let method = arguments.value("method")?.unwrap_or_else(|| parse_quote!(visit_item));
+        let remote = arguments.value("remote")?;
+        let kind = arguments.value("kind")?;
+        arguments.finish()?;
+        Ok(Self { parallel, visitable, visitor, method, remote, kind })
+    }
+
+    /// The type to implement the traits for, without the generic arguments.
+    fn target(&self, decl: &DeriveInput) -> TokenStream {
+        match &self.remote {
+            Some(remote) => remote.to_token_stream(),
+            None => decl.ident.to_token_stream(),
         }
+    }
+}
+
+/// How the generated traversal visits the fields.
+struct Traversal {
+    /// The function called with every field and the visitor.
+    f:         TokenStream,
+    /// How the fields are passed to the function.
+    access:    Access,
+    /// Whether the function returns a value to propagate with `?`, stopping the traversal early.
+    propagate: bool,
+}
+
+/// How the generated traversal passes the fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Access {
+    /// By shared references.
+    Ref,
+    /// By mutable references.
+    Mut,
+    /// By value, consuming the traversed node.
+    Owned,
+}
+
+impl Traversal {
+    /// The statement visiting the field.
+    fn visit(&self, field: impl ToTokens) -> TokenStream {
+        let f = &self.f;
+        let propagate = self.propagate.then(|| quote!(?));
+        quote!(#f(#field, visitor)#propagate;)
+    }
+}
+
+fn gen_body(traversal: &Traversal, data: &Data) -> syn::Result<TokenStream> {
+    match data {
+        Data::Struct(t) => body_for_struct(traversal, t),
+        Data::Enum(t) => body_for_enum(traversal, t),
+        Data::Union(t) => Err(union_error(t)),
+    }
+}
+
+/// The position of the field in the traversal, or [`None`] if it is marked with
+/// `#[visitor(skip)]`. It is given by `#[visitor(order = N)]`, and defaults to the index of the
+/// field in the declaration.
+fn field_order(index: usize, field: &Field) -> syn::Result<Option<usize>> {
+    let mut arguments = Arguments::parse("visitor", &field.attrs)?;
+    let skip = arguments.flag("skip")?;
+    let order = arguments.value::<syn::LitInt>("order")?;
+    arguments.finish()?;
+    match (skip, order) {
+        (true, Some(order)) =>
+            Err(syn::Error::new_spanned(order, "A skipped field cannot have a traversal order.")),
+        (true, None) => Ok(None),
+        (false, Some(order)) => order.base10_parse().map(Some),
+        (false, None) => Ok(Some(index)),
+    }
+}
+
+/// The indices of the traversed fields, in the traversal order. Fields of the same
+/// [order](field_order) are traversed in the declaration order.
+fn traversal_order(fields: &Fields) -> syn::Result<Vec<usize>> {
+    let mut order = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        if let Some(position) = field_order(index, field)? {
+            order.push((position, index));
+        }
+    }
+    order.sort();
+    Ok(order.into_iter().map(|(_, index)| index).collect())
+}
+
+/// For every field, whether it is traversed.
+fn visited_fields(fields: &Fields) -> syn::Result<Vec<bool>> {
+    let order = traversal_order(fields)?;
+    Ok((0..fields.len()).map(|index| order.contains(&index)).collect())
+}
+
+/// The members of the traversed fields, in the traversal order.
+fn visited_members(fields: &Fields) -> syn::Result<Vec<syn::Member>> {
+    let members = fields.members().collect::<Vec<_>>();
+    Ok(traversal_order(fields)?.into_iter().map(|index| members[index].clone()).collect())
+}
+
+fn body_for_struct(traversal: &Traversal, data: &DataStruct) -> syn::Result<TokenStream> {
+    let members = visited_members(&data.fields)?;
+    let statements = members.iter().map(|member| match traversal.access {
+        Access::Ref => traversal.visit(quote!(&self.#member)),
+        Access::Mut => traversal.visit(quote!(&mut self.#member)),
+        Access::Owned => traversal.visit(quote!(self.#member)),
+    });
+    Ok(quote!(#(#statements)*))
+}
+
+/// The pattern matching the variant, and the bindings of its traversed fields in the traversal
+/// order.
+fn variant_pattern(variant: &Variant) -> syn::Result<(TokenStream, Vec<syn::Ident>)> {
+    let variant_ident = &variant.ident;
+    let order = traversal_order(&variant.fields)?;
+    let visited = visited_fields(&variant.fields)?;
+    Ok(match &variant.fields {
+        Fields::Unit => (quote!(Self::#variant_ident), vec![]),
         Fields::Named(fields) => {
             let names = field_names(fields);
-            quote!(Self::#variant_ident { #(#names),* } => {
-                #( #f(#names, visitor); )*
-            })
+            let names = order.iter().map(|&index| names[index].clone()).collect::<Vec<_>>();
+            let rest = visited.contains(&false).then(|| quote!(..));
+            (quote!(Self::#variant_ident { #(#names,)* #rest }), names)
         }
         Fields::Unnamed(fields) => {
             let names = identifier_sequence(fields.unnamed.len());
-            quote!(Self::#variant_ident(#(#names),*) => {
-                #( #f(#names, visitor); )*
-            })
+            let patterns = names.iter().zip(&visited).map(|(name, visited)| match visited {
+                true => quote!(#name),
+                false => quote!(_),
+            });
+            let pattern = quote!(Self::#variant_ident(#(#patterns),*));
+            (pattern, order.iter().map(|&index| names[index].clone()).collect())
         }
+    })
+}
+
+/// Prepares a match arm for a single variant that visits its fields.
+fn arm_for_variant(traversal: &Traversal, variant: &Variant) -> syn::Result<TokenStream> {
+    let (pattern, names) = variant_pattern(variant)?;
+    let statements = names.iter().map(|name| traversal.visit(name));
+    Ok(quote!(#pattern => {
+        #(#statements)*
+    }))
+}
+
+/// The pattern binding the fields of the `path` struct or variant, and the expression rebuilding it
+/// from the bindings, with the traversed ones passed through `f`.
+fn fold_fields(
+    f: &TokenStream,
+    path: TokenStream,
+    fields: &Fields,
+) -> syn::Result<(TokenStream, TokenStream)> {
+    let order = traversal_order(fields)?;
+    let visited = visited_fields(fields)?;
+    let bindings: Vec<syn::Ident> = match fields {
+        Fields::Named(fields) => field_names(fields).into_iter().cloned().collect(),
+        Fields::Unnamed(fields) => identifier_sequence(fields.unnamed.len()),
+        Fields::Unit => vec![],
+    };
+    if order.windows(2).any(|pair| pair[0] > pair[1]) {
+        // The fields are folded in the traversal order before the node is rebuilt.
+        let folded = order.iter().map(|&index| &bindings[index]).collect::<Vec<_>>();
+        let pattern = match fields {
+            Fields::Named(_) => quote!(#path { #(#bindings),* }),
+            _ => quote!(#path(#(#bindings),*)),
+        };
+        let value = quote!({
+            #(let #folded = #f(#folded, folder);)*
+            #pattern
+        });
+        return Ok((pattern, value));
     }
+    let values = bindings.iter().zip(&visited).map(|(binding, visited)| match visited {
+        true => quote!(#f(#binding, folder)),
+        false => quote!(#binding),
+    });
+    let values = values.collect::<Vec<_>>();
+    Ok(match fields {
+        Fields::Named(_) =>
+            (quote!(#path { #(#bindings),* }), quote!(#path { #(#bindings: #values),* })),
+        Fields::Unnamed(_) => (quote!(#path(#(#bindings),*)), quote!(#path(#(#values),*))),
+        Fields::Unit => (path.clone(), path),
+    })
 }
 
-fn body_for_enum(f: &TokenStream, data: &DataEnum) -> TokenStream {
-    let make_arm = |variant| arm_for_variant(f, variant);
-    let arms = data.variants.iter().map(make_arm);
+fn body_for_enum(traversal: &Traversal, data: &DataEnum) -> syn::Result<TokenStream> {
+    let make_arm = |variant| arm_for_variant(traversal, variant);
+    let arms = data.variants.iter().map(make_arm).collect::<syn::Result<Vec<_>>>()?;
     let body = quote!(match self { #(#arms)* });
-    body
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expansions() {
+        enso_macro_test::check_derive("tests/expand", |input| {
+            let mut output = derive(input.clone());
+            output.extend(derive_mut(input.clone()));
+            output.extend(derive_fold(input.clone()));
+            output.extend(derive_into(input.clone()));
+            output.extend(derive_spans(input));
+            output
+        });
+        enso_macro_test::check_derive("tests/remote", remote);
+    }
 }
@@ -0,0 +1,55 @@
+impl<'a, 's: 'a> ItemVisitable<'s, 'a> for Lifetimes<'a, 's> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.0, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+::core::compile_error! {
+    "`VisitorMut` cannot be derived for a type borrowing data for the `'a` lifetime."
+}
+::core::compile_error! {
+    "`Foldable` cannot be derived for a type borrowing data for the `'a` lifetime."
+}
+::core::compile_error! {
+    "`IntoVisitor` cannot be derived for a type borrowing data for the `'a` lifetime."
+}
+::core::compile_error! {
+    "`SpanBuilder` cannot be derived for a type borrowing data for the `'a` lifetime."
+}
+impl<'s, 't: 'a, 'u: 'a, 'a> ItemVisitable<'s, 'a> for Borrowed<'s, 't, 'u> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.tree, visitor)?;
+        ItemVisitable::visit_item(&self.other, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+::core::compile_error! {
+    "`VisitorMut` cannot be derived for a type borrowing data for the `'t` lifetime."
+}
+::core::compile_error! {
+    "`VisitorMut` cannot be derived for a type borrowing data for the `'u` lifetime."
+}
+::core::compile_error! {
+    "`Foldable` cannot be derived for a type borrowing data for the `'t` lifetime."
+}
+::core::compile_error! {
+    "`Foldable` cannot be derived for a type borrowing data for the `'u` lifetime."
+}
+::core::compile_error! {
+    "`IntoVisitor` cannot be derived for a type borrowing data for the `'t` lifetime."
+}
+::core::compile_error! {
+    "`IntoVisitor` cannot be derived for a type borrowing data for the `'u` lifetime."
+}
+::core::compile_error! {
+    "`SpanBuilder` cannot be derived for a type borrowing data for the `'t` lifetime."
+}
+::core::compile_error! {
+    "`SpanBuilder` cannot be derived for a type borrowing data for the `'u` lifetime."
+}
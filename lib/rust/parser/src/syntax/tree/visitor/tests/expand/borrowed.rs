@@ -0,0 +1,6 @@
+struct Lifetimes<'a, 's: 'a>(&'a Tree<'s>);
+
+struct Borrowed<'s, 't, 'u> {
+    pub tree:  &'t Tree<'s>,
+    pub other: &'u Tree<'s>,
+}
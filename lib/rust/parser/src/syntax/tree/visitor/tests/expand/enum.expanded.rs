@@ -0,0 +1,77 @@
+impl<'s, 'a> ItemVisitable<'s, 'a> for Variant<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        match self {
+            Self::Empty => {}
+            Self::Unnamed(field0, field1) => {
+                ItemVisitable::visit_item(field0, visitor)?;
+                ItemVisitable::visit_item(field1, visitor)?;
+            }
+            Self::Named { name, body } => {
+                ItemVisitable::visit_item(name, visitor)?;
+                ItemVisitable::visit_item(body, visitor)?;
+            }
+        }
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Variant<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        match self {
+            Self::Empty => {}
+            Self::Unnamed(field0, field1) => {
+                ItemVisitableMut::visit_item_mut(field0, visitor);
+                ItemVisitableMut::visit_item_mut(field1, visitor);
+            }
+            Self::Named { name, body } => {
+                ItemVisitableMut::visit_item_mut(name, visitor);
+                ItemVisitableMut::visit_item_mut(body, visitor);
+            }
+        }
+    }
+}
+impl<'s> ItemFoldable<'s> for Variant<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        match self {
+            Self::Empty => Self::Empty,
+            Self::Unnamed(field0, field1) => {
+                Self::Unnamed(
+                    ItemFoldable::fold_item(field0, folder),
+                    ItemFoldable::fold_item(field1, folder),
+                )
+            }
+            Self::Named { name, body } => {
+                Self::Named {
+                    name: ItemFoldable::fold_item(name, folder),
+                    body: ItemFoldable::fold_item(body, folder),
+                }
+            }
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Variant<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        match self {
+            Self::Empty => {}
+            Self::Unnamed(field0, field1) => {
+                ItemIntoVisitable::into_visit_item(field0, visitor);
+                ItemIntoVisitable::into_visit_item(field1, visitor);
+            }
+            Self::Named { name, body } => {
+                ItemIntoVisitable::into_visit_item(name, visitor);
+                ItemIntoVisitable::into_visit_item(body, visitor);
+            }
+        }
+    }
+}
+impl<'s> span::Builder<'s> for Variant<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        match self {
+            Self::Empty => span,
+            Self::Unnamed(field0, field1) => span.add(field0).add(field1),
+            Self::Named { name, body } => span.add(name).add(body),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+enum Variant<'s> {
+    Empty,
+    Unnamed(Token<'s>, Tree<'s>),
+    Named { name: Token<'s>, body: Option<Tree<'s>> },
+}
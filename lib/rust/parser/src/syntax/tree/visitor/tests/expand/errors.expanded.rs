@@ -0,0 +1,15 @@
+::core::compile_error! {
+    "Untagged union types are not supported."
+}
+::core::compile_error! {
+    "Untagged union types are not supported."
+}
+::core::compile_error! {
+    "Untagged union types are not supported."
+}
+::core::compile_error! {
+    "Untagged union types are not supported."
+}
+::core::compile_error! {
+    "Untagged union types are not supported."
+}
@@ -0,0 +1,4 @@
+union Untagged<'s> {
+    pub token:  std::mem::ManuallyDrop<Token<'s>>,
+    pub offset: u32,
+}
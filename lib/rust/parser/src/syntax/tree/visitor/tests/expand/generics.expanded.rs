@@ -0,0 +1,112 @@
+impl<'s, 'a, U> ItemVisitable<'s, 'a> for Operand<'s, U>
+where
+    U: Clone,
+    U: ItemVisitable<'s, 'a>,
+{
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.elided, visitor)?;
+        ItemVisitable::visit_item(&self.value, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s, U> ItemVisitableMut<'s> for Operand<'s, U>
+where
+    U: Clone,
+    U: ItemVisitableMut<'s>,
+{
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.elided, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.value, visitor);
+    }
+}
+impl<'s, U> ItemFoldable<'s> for Operand<'s, U>
+where
+    U: Clone,
+    U: ItemFoldable<'s>,
+{
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self { elided, value } = self;
+        Self {
+            elided: ItemFoldable::fold_item(elided, folder),
+            value: ItemFoldable::fold_item(value, folder),
+        }
+    }
+}
+impl<'s, U> ItemIntoVisitable<'s> for Operand<'s, U>
+where
+    U: Clone,
+    U: ItemIntoVisitable<'s>,
+{
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.elided, visitor);
+        ItemIntoVisitable::into_visit_item(self.value, visitor);
+    }
+}
+impl<'s, U> span::Builder<'s> for Operand<'s, U>
+where
+    U: Clone,
+    U: span::Builder<'s>,
+{
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.elided).add(&mut self.value)
+    }
+}
+impl<'s, 'a, T, const N: usize> ItemVisitable<'s, 'a> for Parameters<'s, T, N>
+where
+    T: Copy,
+    T: ItemVisitable<'s, 'a>,
+{
+    fn visit_item<T_: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T_,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.elements, visitor)?;
+        ItemVisitable::visit_item(&self.value, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s, T, const N: usize> ItemVisitableMut<'s> for Parameters<'s, T, N>
+where
+    T: Copy,
+    T: ItemVisitableMut<'s>,
+{
+    fn visit_item_mut<T_: ItemVisitorMut<'s>>(&mut self, visitor: &mut T_) {
+        ItemVisitableMut::visit_item_mut(&mut self.elements, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.value, visitor);
+    }
+}
+impl<'s, T, const N: usize> ItemFoldable<'s> for Parameters<'s, T, N>
+where
+    T: Copy,
+    T: ItemFoldable<'s>,
+{
+    fn fold_item<T_: ItemFolder<'s>>(self, folder: &mut T_) -> Self {
+        let Self { elements, value } = self;
+        Self {
+            elements: ItemFoldable::fold_item(elements, folder),
+            value: ItemFoldable::fold_item(value, folder),
+        }
+    }
+}
+impl<'s, T, const N: usize> ItemIntoVisitable<'s> for Parameters<'s, T, N>
+where
+    T: Copy,
+    T: ItemIntoVisitable<'s>,
+{
+    fn into_visit_item<T_: ItemIntoVisitor<'s>>(self, visitor: &mut T_) {
+        ItemIntoVisitable::into_visit_item(self.elements, visitor);
+        ItemIntoVisitable::into_visit_item(self.value, visitor);
+    }
+}
+impl<'s, T, const N: usize> span::Builder<'s> for Parameters<'s, T, N>
+where
+    T: Copy,
+    T: span::Builder<'s>,
+{
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.elements).add(&mut self.value)
+    }
+}
@@ -0,0 +1,13 @@
+struct Operand<'s, U>
+where U: Clone
+{
+    pub elided: Token<'s>,
+    pub value: U,
+}
+
+struct Parameters<'s, T, const N: usize>
+where T: Copy
+{
+    pub elements: [Token<'s>; N],
+    pub value: Option<T>,
+}
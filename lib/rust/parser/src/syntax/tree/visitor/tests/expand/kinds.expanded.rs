@@ -0,0 +1,211 @@
+impl<'s, 'a, T> ItemVisitable<'s, 'a> for Shape<'s, T>
+where
+    T: ItemVisitable<'s, 'a>,
+{
+    fn visit_item<T_: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T_,
+    ) -> std::ops::ControlFlow<()> {
+        match self {
+            Self::Point => {}
+            Self::Circle(field0, field1) => {
+                ItemVisitable::visit_item(field0, visitor)?;
+                ItemVisitable::visit_item(field1, visitor)?;
+            }
+            Self::Rectangle { width, height } => {
+                ItemVisitable::visit_item(width, visitor)?;
+                ItemVisitable::visit_item(height, visitor)?;
+            }
+        }
+        std::ops::ControlFlow::Continue(())
+    }
+}
+///The kinds of the variants of [`Shape`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ShapeKind {
+    ///The kind of [`Shape::Point`].
+    Point,
+    ///The kind of [`Shape::Circle`].
+    Circle,
+    ///The kind of [`Shape::Rectangle`].
+    Rectangle,
+}
+impl ShapeKind {
+    /// The number of the kinds.
+    pub const COUNT: usize = 3usize;
+    /// All the kinds, in the order of the declaration.
+    pub const ALL: [Self; 3usize] = [Self::Point, Self::Circle, Self::Rectangle];
+    /// The position of the kind in [`Self::ALL`].
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+    /// The name of the variant.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Point => "Point",
+            Self::Circle => "Circle",
+            Self::Rectangle => "Rectangle",
+        }
+    }
+}
+impl<'s, T> Shape<'s, T> {
+    /// The kind of the variant.
+    pub fn kind(&self) -> ShapeKind {
+        match self {
+            Self::Point { .. } => ShapeKind::Point,
+            Self::Circle { .. } => ShapeKind::Circle,
+            Self::Rectangle { .. } => ShapeKind::Rectangle,
+        }
+    }
+}
+impl<'s, T> ItemVisitableMut<'s> for Shape<'s, T>
+where
+    T: ItemVisitableMut<'s>,
+{
+    fn visit_item_mut<T_: ItemVisitorMut<'s>>(&mut self, visitor: &mut T_) {
+        match self {
+            Self::Point => {}
+            Self::Circle(field0, field1) => {
+                ItemVisitableMut::visit_item_mut(field0, visitor);
+                ItemVisitableMut::visit_item_mut(field1, visitor);
+            }
+            Self::Rectangle { width, height } => {
+                ItemVisitableMut::visit_item_mut(width, visitor);
+                ItemVisitableMut::visit_item_mut(height, visitor);
+            }
+        }
+    }
+}
+impl<'s, T> ItemFoldable<'s> for Shape<'s, T>
+where
+    T: ItemFoldable<'s>,
+{
+    fn fold_item<T_: ItemFolder<'s>>(self, folder: &mut T_) -> Self {
+        match self {
+            Self::Point => Self::Point,
+            Self::Circle(field0, field1) => {
+                Self::Circle(
+                    ItemFoldable::fold_item(field0, folder),
+                    ItemFoldable::fold_item(field1, folder),
+                )
+            }
+            Self::Rectangle { width, height } => {
+                Self::Rectangle {
+                    width: ItemFoldable::fold_item(width, folder),
+                    height: ItemFoldable::fold_item(height, folder),
+                }
+            }
+        }
+    }
+}
+impl<'s, T> ItemIntoVisitable<'s> for Shape<'s, T>
+where
+    T: ItemIntoVisitable<'s>,
+{
+    fn into_visit_item<T_: ItemIntoVisitor<'s>>(self, visitor: &mut T_) {
+        match self {
+            Self::Point => {}
+            Self::Circle(field0, field1) => {
+                ItemIntoVisitable::into_visit_item(field0, visitor);
+                ItemIntoVisitable::into_visit_item(field1, visitor);
+            }
+            Self::Rectangle { width, height } => {
+                ItemIntoVisitable::into_visit_item(width, visitor);
+                ItemIntoVisitable::into_visit_item(height, visitor);
+            }
+        }
+    }
+}
+impl<'s, T> span::Builder<'s> for Shape<'s, T>
+where
+    T: span::Builder<'s>,
+{
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        match self {
+            Self::Point => span,
+            Self::Circle(field0, field1) => span.add(field0).add(field1),
+            Self::Rectangle { width, height } => span.add(width).add(height),
+        }
+    }
+}
+impl<'s, 'a> ItemVisitable<'s, 'a> for Empty {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        match self {}
+        std::ops::ControlFlow::Continue(())
+    }
+}
+///The kinds of the variants of [`Empty`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum EmptyKind {}
+impl EmptyKind {
+    /// The number of the kinds.
+    pub const COUNT: usize = 0usize;
+    /// All the kinds, in the order of the declaration.
+    pub const ALL: [Self; 0usize] = [];
+    /// The position of the kind in [`Self::ALL`].
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+    /// The name of the variant.
+    pub const fn name(self) -> &'static str {
+        match self {}
+    }
+}
+impl Empty {
+    /// The kind of the variant.
+    pub fn kind(&self) -> EmptyKind {
+        match self {}
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Empty {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        match self {}
+    }
+}
+impl<'s> ItemFoldable<'s> for Empty {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        match self {}
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Empty {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        match self {}
+    }
+}
+impl<'s> span::Builder<'s> for Empty {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        match self {}
+    }
+}
+::core::compile_error! {
+    "Only an enum can have node kinds."
+}
+impl<'s> ItemVisitableMut<'s> for Pair<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.lhs, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.rhs, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for Pair<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self { lhs, rhs } = self;
+        Self {
+            lhs: ItemFoldable::fold_item(lhs, folder),
+            rhs: ItemFoldable::fold_item(rhs, folder),
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Pair<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.lhs, visitor);
+        ItemIntoVisitable::into_visit_item(self.rhs, visitor);
+    }
+}
+impl<'s> span::Builder<'s> for Pair<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.lhs).add(&mut self.rhs)
+    }
+}
@@ -0,0 +1,15 @@
+#[visitor(kind = ShapeKind)]
+pub enum Shape<'s, T> {
+    Point,
+    Circle(Tree<'s>, T),
+    Rectangle { width: Tree<'s>, height: Tree<'s> },
+}
+
+#[visitor(kind = EmptyKind)]
+enum Empty {}
+
+#[visitor(kind = PairKind)]
+struct Pair<'s> {
+    pub lhs: Tree<'s>,
+    pub rhs: Tree<'s>,
+}
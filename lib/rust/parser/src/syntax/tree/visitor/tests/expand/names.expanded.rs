@@ -0,0 +1,115 @@
+impl<'s, 'a> TypedVisitable<'s, 'a> for Typed<'s> {
+    fn visit_typed<T: TypedVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        TypedVisitable::visit_typed(&self.expression, visitor)?;
+        TypedVisitable::visit_typed(&self.type_, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Typed<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.expression, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.type_, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for Typed<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self { expression, type_ } = self;
+        Self {
+            expression: ItemFoldable::fold_item(expression, folder),
+            type_: ItemFoldable::fold_item(type_, folder),
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Typed<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.expression, visitor);
+        ItemIntoVisitable::into_visit_item(self.type_, visitor);
+    }
+}
+impl<'s> span::Builder<'s> for Typed<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.expression).add(&mut self.type_)
+    }
+}
+impl<'s, 'a> typed::Visitable<'s, 'a> for Type<'s> {
+    fn visit<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        match self {
+            Self::Named(field0) => {
+                typed::Visitable::visit(field0, visitor)?;
+            }
+            Self::Function { argument, result } => {
+                typed::Visitable::visit(argument, visitor)?;
+                typed::Visitable::visit(result, visitor)?;
+            }
+        }
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Type<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        match self {
+            Self::Named(field0) => {
+                ItemVisitableMut::visit_item_mut(field0, visitor);
+            }
+            Self::Function { argument, result } => {
+                ItemVisitableMut::visit_item_mut(argument, visitor);
+                ItemVisitableMut::visit_item_mut(result, visitor);
+            }
+        }
+    }
+}
+impl<'s> ItemFoldable<'s> for Type<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        match self {
+            Self::Named(field0) => Self::Named(ItemFoldable::fold_item(field0, folder)),
+            Self::Function { argument, result } => {
+                Self::Function {
+                    argument: ItemFoldable::fold_item(argument, folder),
+                    result: ItemFoldable::fold_item(result, folder),
+                }
+            }
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Type<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        match self {
+            Self::Named(field0) => {
+                ItemIntoVisitable::into_visit_item(field0, visitor);
+            }
+            Self::Function { argument, result } => {
+                ItemIntoVisitable::into_visit_item(argument, visitor);
+                ItemIntoVisitable::into_visit_item(result, visitor);
+            }
+        }
+    }
+}
+impl<'s> span::Builder<'s> for Type<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        match self {
+            Self::Named(field0) => span.add(field0),
+            Self::Function { argument, result } => span.add(argument).add(result),
+        }
+    }
+}
+::core::compile_error! {
+    "unexpected token"
+}
+::core::compile_error! {
+    "unexpected token"
+}
+::core::compile_error! {
+    "unexpected token"
+}
+::core::compile_error! {
+    "unexpected token"
+}
+::core::compile_error! {
+    "unexpected token"
+}
@@ -0,0 +1,14 @@
+#[visitor(trait = "TypedVisitable", visitor_trait = "TypedVisitor", method = "visit_typed")]
+struct Typed<'s> {
+    pub expression: Tree<'s>,
+    pub type_:      Option<Type<'s>>,
+}
+
+#[visitor(trait = typed::Visitable, method = visit)]
+enum Type<'s> {
+    Named(Token<'s>),
+    Function { argument: Box<Type<'s>>, result: Box<Type<'s>> },
+}
+
+#[visitor(trait = "Visitable", method = "not an identifier")]
+struct Invalid;
@@ -0,0 +1,187 @@
+impl<'s, 'a> ItemVisitable<'s, 'a> for Named<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.open, visitor)?;
+        ItemVisitable::visit_item(&self.close, visitor)?;
+        ItemVisitable::visit_item(&self.body, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Named<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.open, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.close, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.body, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for Named<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self { close, open, cache, body } = self;
+        {
+            let open = ItemFoldable::fold_item(open, folder);
+            let close = ItemFoldable::fold_item(close, folder);
+            let body = ItemFoldable::fold_item(body, folder);
+            Self { close, open, cache, body }
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Named<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.open, visitor);
+        ItemIntoVisitable::into_visit_item(self.close, visitor);
+        ItemIntoVisitable::into_visit_item(self.body, visitor);
+    }
+}
+impl<'s> span::Builder<'s> for Named<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.open).add(&mut self.close).add(&mut self.body)
+    }
+}
+impl<'s, 'a> ItemVisitable<'s, 'a> for Tuple<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.1, visitor)?;
+        ItemVisitable::visit_item(&self.0, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Tuple<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.1, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.0, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for Tuple<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self(field0, field1) = self;
+        {
+            let field1 = ItemFoldable::fold_item(field1, folder);
+            let field0 = ItemFoldable::fold_item(field0, folder);
+            Self(field0, field1)
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Tuple<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.1, visitor);
+        ItemIntoVisitable::into_visit_item(self.0, visitor);
+    }
+}
+impl<'s> span::Builder<'s> for Tuple<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.1).add(&mut self.0)
+    }
+}
+impl<'s, 'a> ItemVisitable<'s, 'a> for Variant<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        match self {
+            Self::Unnamed(field0, field1) => {
+                ItemVisitable::visit_item(field1, visitor)?;
+                ItemVisitable::visit_item(field0, visitor)?;
+            }
+            Self::Named { body, close, open, .. } => {
+                ItemVisitable::visit_item(body, visitor)?;
+                ItemVisitable::visit_item(close, visitor)?;
+                ItemVisitable::visit_item(open, visitor)?;
+            }
+        }
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Variant<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        match self {
+            Self::Unnamed(field0, field1) => {
+                ItemVisitableMut::visit_item_mut(field1, visitor);
+                ItemVisitableMut::visit_item_mut(field0, visitor);
+            }
+            Self::Named { body, close, open, .. } => {
+                ItemVisitableMut::visit_item_mut(body, visitor);
+                ItemVisitableMut::visit_item_mut(close, visitor);
+                ItemVisitableMut::visit_item_mut(open, visitor);
+            }
+        }
+    }
+}
+impl<'s> ItemFoldable<'s> for Variant<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        match self {
+            Self::Unnamed(field0, field1) => {
+                let field1 = ItemFoldable::fold_item(field1, folder);
+                let field0 = ItemFoldable::fold_item(field0, folder);
+                Self::Unnamed(field0, field1)
+            }
+            Self::Named { close, cache, body, open } => {
+                let body = ItemFoldable::fold_item(body, folder);
+                let close = ItemFoldable::fold_item(close, folder);
+                let open = ItemFoldable::fold_item(open, folder);
+                Self::Named {
+                    close,
+                    cache,
+                    body,
+                    open,
+                }
+            }
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Variant<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        match self {
+            Self::Unnamed(field0, field1) => {
+                ItemIntoVisitable::into_visit_item(field1, visitor);
+                ItemIntoVisitable::into_visit_item(field0, visitor);
+            }
+            Self::Named { body, close, open, .. } => {
+                ItemIntoVisitable::into_visit_item(body, visitor);
+                ItemIntoVisitable::into_visit_item(close, visitor);
+                ItemIntoVisitable::into_visit_item(open, visitor);
+            }
+        }
+    }
+}
+impl<'s> span::Builder<'s> for Variant<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        match self {
+            Self::Unnamed(field0, field1) => span.add(field1).add(field0),
+            Self::Named { body, close, open, .. } => span.add(body).add(close).add(open),
+        }
+    }
+}
+::core::compile_error! {
+    "A skipped field cannot have a traversal order."
+}
+::core::compile_error! {
+    "A skipped field cannot have a traversal order."
+}
+::core::compile_error! {
+    "A skipped field cannot have a traversal order."
+}
+::core::compile_error! {
+    "A skipped field cannot have a traversal order."
+}
+::core::compile_error! {
+    "A skipped field cannot have a traversal order."
+}
+::core::compile_error! {
+    "expected integer literal"
+}
+::core::compile_error! {
+    "expected integer literal"
+}
+::core::compile_error! {
+    "expected integer literal"
+}
+::core::compile_error! {
+    "expected integer literal"
+}
+::core::compile_error! {
+    "expected integer literal"
+}
@@ -0,0 +1,32 @@
+struct Named<'s> {
+    #[visitor(order = 2)]
+    pub close: Token<'s>,
+    pub open: Token<'s>,
+    #[visitor(skip)]
+    pub cache: Cache,
+    pub body: Tree<'s>,
+}
+
+struct Tuple<'s>(#[visitor(order = 9)] Token<'s>, Tree<'s>);
+
+enum Variant<'s> {
+    Unnamed(#[visitor(order = 2)] Token<'s>, Tree<'s>),
+    Named {
+        #[visitor(order = 3)]
+        close: Token<'s>,
+        #[visitor(skip)]
+        cache: Cache,
+        body: Tree<'s>,
+        open: Token<'s>,
+    },
+}
+
+struct SkippedOrder<'s> {
+    #[visitor(skip, order = 1)]
+    pub token: Token<'s>,
+}
+
+struct InvalidOrder<'s> {
+    #[visitor(order = "last")]
+    pub tree: Tree<'s>,
+}
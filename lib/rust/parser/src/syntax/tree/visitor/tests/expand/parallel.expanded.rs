@@ -0,0 +1,223 @@
+impl<'s, 'a> ItemVisitable<'s, 'a> for BodyBlock<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.statements, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s, 'a> ParItemVisitable<'s, 'a> for BodyBlock<'s> {
+    fn par_visit_item<T: ParItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ParItemVisitable::par_visit_item(&self.statements, visitor)
+    }
+}
+impl<'s> ItemVisitableMut<'s> for BodyBlock<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.statements, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for BodyBlock<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self { statements } = self;
+        Self {
+            statements: ItemFoldable::fold_item(statements, folder),
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for BodyBlock<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.statements, visitor);
+    }
+}
+impl<'s> span::Builder<'s> for BodyBlock<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.statements)
+    }
+}
+impl<'s, 'a, T> ItemVisitable<'s, 'a> for Pair<'s, T>
+where
+    T: ItemVisitable<'s, 'a>,
+{
+    fn visit_item<T_: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T_,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.lhs, visitor)?;
+        ItemVisitable::visit_item(&self.operator, visitor)?;
+        ItemVisitable::visit_item(&self.rhs, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s, 'a, T> ParItemVisitable<'s, 'a> for Pair<'s, T>
+where
+    T: ParItemVisitable<'s, 'a>,
+    T: Sync,
+{
+    fn par_visit_item<T_: ParItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T_,
+    ) -> std::ops::ControlFlow<()> {
+        {
+            let mut visitor0 = visitor.split();
+            let mut visitor1 = visitor.split();
+            let mut visitor2 = visitor.split();
+            let (flow0, (flow1, flow2)) = rayon::join(
+                || ParItemVisitable::par_visit_item(&self.lhs, &mut visitor0),
+                || rayon::join(
+                    || ParItemVisitable::par_visit_item(&self.operator, &mut visitor1),
+                    || ParItemVisitable::par_visit_item(&self.rhs, &mut visitor2),
+                ),
+            );
+            visitor.merge(visitor0);
+            flow0?;
+            visitor.merge(visitor1);
+            flow1?;
+            visitor.merge(visitor2);
+            flow2?;
+            std::ops::ControlFlow::Continue(())
+        }
+    }
+}
+impl<'s, T> ItemVisitableMut<'s> for Pair<'s, T>
+where
+    T: ItemVisitableMut<'s>,
+{
+    fn visit_item_mut<T_: ItemVisitorMut<'s>>(&mut self, visitor: &mut T_) {
+        ItemVisitableMut::visit_item_mut(&mut self.lhs, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.operator, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.rhs, visitor);
+    }
+}
+impl<'s, T> ItemFoldable<'s> for Pair<'s, T>
+where
+    T: ItemFoldable<'s>,
+{
+    fn fold_item<T_: ItemFolder<'s>>(self, folder: &mut T_) -> Self {
+        let Self { lhs, operator, cache, rhs } = self;
+        Self {
+            lhs: ItemFoldable::fold_item(lhs, folder),
+            operator: ItemFoldable::fold_item(operator, folder),
+            cache: cache,
+            rhs: ItemFoldable::fold_item(rhs, folder),
+        }
+    }
+}
+impl<'s, T> ItemIntoVisitable<'s> for Pair<'s, T>
+where
+    T: ItemIntoVisitable<'s>,
+{
+    fn into_visit_item<T_: ItemIntoVisitor<'s>>(self, visitor: &mut T_) {
+        ItemIntoVisitable::into_visit_item(self.lhs, visitor);
+        ItemIntoVisitable::into_visit_item(self.operator, visitor);
+        ItemIntoVisitable::into_visit_item(self.rhs, visitor);
+    }
+}
+impl<'s, T> span::Builder<'s> for Pair<'s, T>
+where
+    T: span::Builder<'s>,
+{
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.lhs).add(&mut self.operator).add(&mut self.rhs)
+    }
+}
+impl<'s, 'a> ItemVisitable<'s, 'a> for Either<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        match self {
+            Self::Left(field0, field1) => {
+                ItemVisitable::visit_item(field0, visitor)?;
+                ItemVisitable::visit_item(field1, visitor)?;
+            }
+            Self::Right { token } => {
+                ItemVisitable::visit_item(token, visitor)?;
+            }
+            Self::Neither => {}
+        }
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s, 'a> ParItemVisitable<'s, 'a> for Either<'s> {
+    fn par_visit_item<T: ParItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        match self {
+            Self::Left(field0, field1) => {
+                let mut visitor0 = visitor.split();
+                let mut visitor1 = visitor.split();
+                let (flow0, flow1) = rayon::join(
+                    || ParItemVisitable::par_visit_item(field0, &mut visitor0),
+                    || ParItemVisitable::par_visit_item(field1, &mut visitor1),
+                );
+                visitor.merge(visitor0);
+                flow0?;
+                visitor.merge(visitor1);
+                flow1?;
+                std::ops::ControlFlow::Continue(())
+            }
+            Self::Right { token } => ParItemVisitable::par_visit_item(token, visitor),
+            Self::Neither => std::ops::ControlFlow::Continue(()),
+        }
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Either<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        match self {
+            Self::Left(field0, field1) => {
+                ItemVisitableMut::visit_item_mut(field0, visitor);
+                ItemVisitableMut::visit_item_mut(field1, visitor);
+            }
+            Self::Right { token } => {
+                ItemVisitableMut::visit_item_mut(token, visitor);
+            }
+            Self::Neither => {}
+        }
+    }
+}
+impl<'s> ItemFoldable<'s> for Either<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        match self {
+            Self::Left(field0, field1) => {
+                Self::Left(
+                    ItemFoldable::fold_item(field0, folder),
+                    ItemFoldable::fold_item(field1, folder),
+                )
+            }
+            Self::Right { token } => {
+                Self::Right {
+                    token: ItemFoldable::fold_item(token, folder),
+                }
+            }
+            Self::Neither => Self::Neither,
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Either<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        match self {
+            Self::Left(field0, field1) => {
+                ItemIntoVisitable::into_visit_item(field0, visitor);
+                ItemIntoVisitable::into_visit_item(field1, visitor);
+            }
+            Self::Right { token } => {
+                ItemIntoVisitable::into_visit_item(token, visitor);
+            }
+            Self::Neither => {}
+        }
+    }
+}
+impl<'s> span::Builder<'s> for Either<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        match self {
+            Self::Left(field0, field1) => span.add(field0).add(field1),
+            Self::Right { token } => span.add(token),
+            Self::Neither => span,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+#[visitor(parallel)]
+struct BodyBlock<'s> {
+    pub statements: Vec<Line<'s>>,
+}
+
+#[visitor(parallel)]
+struct Pair<'s, T> {
+    pub lhs: Tree<'s>,
+    pub operator: Token<'s>,
+    #[visitor(skip)]
+    pub cache: Cache,
+    pub rhs: T,
+}
+
+#[visitor(parallel)]
+enum Either<'s> {
+    Left(Tree<'s>, Tree<'s>),
+    Right { token: Token<'s> },
+    Neither,
+}
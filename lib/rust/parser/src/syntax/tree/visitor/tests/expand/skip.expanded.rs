@@ -0,0 +1,133 @@
+impl<'s, 'a> ItemVisitable<'s, 'a> for Named<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.body, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Named<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.body, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for Named<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self { body, cache } = self;
+        Self {
+            body: ItemFoldable::fold_item(body, folder),
+            cache: cache,
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Named<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.body, visitor);
+    }
+}
+impl<'s> span::Builder<'s> for Named<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.body)
+    }
+}
+impl<'s, 'a> ItemVisitable<'s, 'a> for Tuple<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.1, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Tuple<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.1, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for Tuple<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self(field0, field1) = self;
+        Self(field0, ItemFoldable::fold_item(field1, folder))
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Tuple<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.1, visitor);
+    }
+}
+impl<'s> span::Builder<'s> for Tuple<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.1)
+    }
+}
+impl<'s, 'a> ItemVisitable<'s, 'a> for Variant<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        match self {
+            Self::Unnamed(field0, _, field2) => {
+                ItemVisitable::visit_item(field0, visitor)?;
+                ItemVisitable::visit_item(field2, visitor)?;
+            }
+            Self::Named { body, .. } => {
+                ItemVisitable::visit_item(body, visitor)?;
+            }
+        }
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Variant<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        match self {
+            Self::Unnamed(field0, _, field2) => {
+                ItemVisitableMut::visit_item_mut(field0, visitor);
+                ItemVisitableMut::visit_item_mut(field2, visitor);
+            }
+            Self::Named { body, .. } => {
+                ItemVisitableMut::visit_item_mut(body, visitor);
+            }
+        }
+    }
+}
+impl<'s> ItemFoldable<'s> for Variant<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        match self {
+            Self::Unnamed(field0, field1, field2) => {
+                Self::Unnamed(
+                    ItemFoldable::fold_item(field0, folder),
+                    field1,
+                    ItemFoldable::fold_item(field2, folder),
+                )
+            }
+            Self::Named { cache, body } => {
+                Self::Named {
+                    cache: cache,
+                    body: ItemFoldable::fold_item(body, folder),
+                }
+            }
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Variant<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        match self {
+            Self::Unnamed(field0, _, field2) => {
+                ItemIntoVisitable::into_visit_item(field0, visitor);
+                ItemIntoVisitable::into_visit_item(field2, visitor);
+            }
+            Self::Named { body, .. } => {
+                ItemIntoVisitable::into_visit_item(body, visitor);
+            }
+        }
+    }
+}
+impl<'s> span::Builder<'s> for Variant<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        match self {
+            Self::Unnamed(field0, _, field2) => span.add(field0).add(field2),
+            Self::Named { body, .. } => span.add(body),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+struct Named<'s> {
+    pub body: Tree<'s>,
+    #[visitor(skip)]
+    pub cache: Cache,
+}
+
+struct Tuple<'s>(#[visitor(skip)] Metadata, Token<'s>);
+
+enum Variant<'s> {
+    Unnamed(Token<'s>, #[visitor(skip)] Metadata, Tree<'s>),
+    Named {
+        #[visitor(skip)]
+        cache: Cache,
+        body: Tree<'s>,
+    },
+}
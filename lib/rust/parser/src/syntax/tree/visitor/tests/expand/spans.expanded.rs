@@ -0,0 +1,136 @@
+impl<'s, 'a> ItemVisitable<'s, 'a> for ArgumentDefinitionLine<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.newline, visitor)?;
+        ItemVisitable::visit_item(&self.argument, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for ArgumentDefinitionLine<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.newline, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.argument, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for ArgumentDefinitionLine<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self { newline, argument, cache } = self;
+        Self {
+            newline: ItemFoldable::fold_item(newline, folder),
+            argument: ItemFoldable::fold_item(argument, folder),
+            cache: cache,
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for ArgumentDefinitionLine<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.newline, visitor);
+        ItemIntoVisitable::into_visit_item(self.argument, visitor);
+    }
+}
+impl<'s> span::Builder<'s> for ArgumentDefinitionLine<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.newline).add(&mut self.argument)
+    }
+}
+impl<'s, 'a> ItemVisitable<'s, 'a> for TextElement<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        match self {
+            Self::Section { text } => {
+                ItemVisitable::visit_item(text, visitor)?;
+            }
+            Self::Splice { open, expression, close } => {
+                ItemVisitable::visit_item(open, visitor)?;
+                ItemVisitable::visit_item(expression, visitor)?;
+                ItemVisitable::visit_item(close, visitor)?;
+            }
+            Self::Unnamed(field0, _, field2) => {
+                ItemVisitable::visit_item(field0, visitor)?;
+                ItemVisitable::visit_item(field2, visitor)?;
+            }
+            Self::Empty => {}
+        }
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for TextElement<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        match self {
+            Self::Section { text } => {
+                ItemVisitableMut::visit_item_mut(text, visitor);
+            }
+            Self::Splice { open, expression, close } => {
+                ItemVisitableMut::visit_item_mut(open, visitor);
+                ItemVisitableMut::visit_item_mut(expression, visitor);
+                ItemVisitableMut::visit_item_mut(close, visitor);
+            }
+            Self::Unnamed(field0, _, field2) => {
+                ItemVisitableMut::visit_item_mut(field0, visitor);
+                ItemVisitableMut::visit_item_mut(field2, visitor);
+            }
+            Self::Empty => {}
+        }
+    }
+}
+impl<'s> ItemFoldable<'s> for TextElement<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        match self {
+            Self::Section { text } => {
+                Self::Section {
+                    text: ItemFoldable::fold_item(text, folder),
+                }
+            }
+            Self::Splice { open, expression, close } => {
+                Self::Splice {
+                    open: ItemFoldable::fold_item(open, folder),
+                    expression: ItemFoldable::fold_item(expression, folder),
+                    close: ItemFoldable::fold_item(close, folder),
+                }
+            }
+            Self::Unnamed(field0, field1, field2) => {
+                Self::Unnamed(
+                    ItemFoldable::fold_item(field0, folder),
+                    field1,
+                    ItemFoldable::fold_item(field2, folder),
+                )
+            }
+            Self::Empty => Self::Empty,
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for TextElement<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        match self {
+            Self::Section { text } => {
+                ItemIntoVisitable::into_visit_item(text, visitor);
+            }
+            Self::Splice { open, expression, close } => {
+                ItemIntoVisitable::into_visit_item(open, visitor);
+                ItemIntoVisitable::into_visit_item(expression, visitor);
+                ItemIntoVisitable::into_visit_item(close, visitor);
+            }
+            Self::Unnamed(field0, _, field2) => {
+                ItemIntoVisitable::into_visit_item(field0, visitor);
+                ItemIntoVisitable::into_visit_item(field2, visitor);
+            }
+            Self::Empty => {}
+        }
+    }
+}
+impl<'s> span::Builder<'s> for TextElement<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        match self {
+            Self::Section { text } => span.add(text),
+            Self::Splice { open, expression, close } => {
+                span.add(open).add(expression).add(close)
+            }
+            Self::Unnamed(field0, _, field2) => span.add(field0).add(field2),
+            Self::Empty => span,
+        }
+    }
+}
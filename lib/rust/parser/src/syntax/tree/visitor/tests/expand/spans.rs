@@ -0,0 +1,13 @@
+struct ArgumentDefinitionLine<'s> {
+    pub newline:  token::Newline<'s>,
+    pub argument: Option<ArgumentDefinition<'s>>,
+    #[visitor(skip)]
+    pub cache:    Cache,
+}
+
+enum TextElement<'s> {
+    Section { text: token::TextSection<'s> },
+    Splice { open: token::OpenSymbol<'s>, expression: Option<Tree<'s>>, close: token::CloseSymbol<'s> },
+    Unnamed(Token<'s>, #[visitor(skip)] Metadata, Tree<'s>),
+    Empty,
+}
@@ -0,0 +1,97 @@
+impl<'s, 'a> ItemVisitable<'s, 'a> for Unit {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Unit {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {}
+}
+impl<'s> ItemFoldable<'s> for Unit {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self = self;
+        Self
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Unit {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {}
+}
+impl<'s> span::Builder<'s> for Unit {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span
+    }
+}
+impl<'s, 'a> ItemVisitable<'s, 'a> for Tuple<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.0, visitor)?;
+        ItemVisitable::visit_item(&self.1, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Tuple<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.0, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.1, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for Tuple<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self(field0, field1) = self;
+        Self(
+            ItemFoldable::fold_item(field0, folder),
+            ItemFoldable::fold_item(field1, folder),
+        )
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Tuple<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.0, visitor);
+        ItemIntoVisitable::into_visit_item(self.1, visitor);
+    }
+}
+impl<'s> span::Builder<'s> for Tuple<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.0).add(&mut self.1)
+    }
+}
+impl<'s, 'a> ItemVisitable<'s, 'a> for Named<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.operator, visitor)?;
+        ItemVisitable::visit_item(&self.arguments, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> ItemVisitableMut<'s> for Named<'s> {
+    fn visit_item_mut<T: ItemVisitorMut<'s>>(&mut self, visitor: &mut T) {
+        ItemVisitableMut::visit_item_mut(&mut self.operator, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.arguments, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for Named<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        let Self { operator, arguments } = self;
+        Self {
+            operator: ItemFoldable::fold_item(operator, folder),
+            arguments: ItemFoldable::fold_item(arguments, folder),
+        }
+    }
+}
+impl<'s> ItemIntoVisitable<'s> for Named<'s> {
+    fn into_visit_item<T: ItemIntoVisitor<'s>>(self, visitor: &mut T) {
+        ItemIntoVisitable::into_visit_item(self.operator, visitor);
+        ItemIntoVisitable::into_visit_item(self.arguments, visitor);
+    }
+}
+impl<'s> span::Builder<'s> for Named<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        span.add(&mut self.operator).add(&mut self.arguments)
+    }
+}
@@ -0,0 +1,8 @@
+struct Unit;
+
+struct Tuple<'s>(Token<'s>, Option<Tree<'s>>);
+
+struct Named<'s> {
+    pub operator: Token<'s>,
+    pub arguments: Vec<Tree<'s>>,
+}
@@ -0,0 +1,15 @@
+::core::compile_error! {
+    "Unknown argument `rename` of `#[visitor]`, expected one of: `skip`, `order`."
+}
+::core::compile_error! {
+    "Unknown argument `rename` of `#[visitor]`, expected one of: `skip`, `order`."
+}
+::core::compile_error! {
+    "Unknown argument `rename` of `#[visitor]`, expected one of: `skip`, `order`."
+}
+::core::compile_error! {
+    "Unknown argument `rename` of `#[visitor]`, expected one of: `skip`, `order`."
+}
+::core::compile_error! {
+    "Unknown argument `rename` of `#[visitor]`, expected one of: `skip`, `order`."
+}
@@ -0,0 +1,4 @@
+struct Named<'s> {
+    #[visitor(skip, rename = "node")]
+    pub body: Tree<'s>,
+}
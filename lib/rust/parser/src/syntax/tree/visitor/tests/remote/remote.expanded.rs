@@ -0,0 +1,64 @@
+impl<'s, 'a, T> ItemVisitable<'s, 'a> for other::Wrapper<'s, T>
+where
+    T: ItemVisitable<'s, 'a>,
+{
+    fn visit_item<T_: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T_,
+    ) -> std::ops::ControlFlow<()> {
+        ItemVisitable::visit_item(&self.token, visitor)?;
+        ItemVisitable::visit_item(&self.inner, visitor)?;
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s, T> ItemVisitableMut<'s> for other::Wrapper<'s, T>
+where
+    T: ItemVisitableMut<'s>,
+{
+    fn visit_item_mut<T_: ItemVisitorMut<'s>>(&mut self, visitor: &mut T_) {
+        ItemVisitableMut::visit_item_mut(&mut self.token, visitor);
+        ItemVisitableMut::visit_item_mut(&mut self.inner, visitor);
+    }
+}
+impl<'s> ItemFoldable<'s> for other::Either<'s> {
+    fn fold_item<T: ItemFolder<'s>>(self, folder: &mut T) -> Self {
+        match self {
+            Self::Left(field0) => Self::Left(ItemFoldable::fold_item(field0, folder)),
+            Self::Right { token } => {
+                Self::Right {
+                    token: ItemFoldable::fold_item(token, folder),
+                }
+            }
+        }
+    }
+}
+impl<'s, 'a> ItemVisitable<'s, 'a> for other::Either<'s> {
+    fn visit_item<T: ItemVisitor<'s, 'a>>(
+        &'a self,
+        visitor: &mut T,
+    ) -> std::ops::ControlFlow<()> {
+        match self {
+            Self::Left(field0) => {
+                ItemVisitable::visit_item(field0, visitor)?;
+            }
+            Self::Right { token } => {
+                ItemVisitable::visit_item(token, visitor)?;
+            }
+        }
+        std::ops::ControlFlow::Continue(())
+    }
+}
+impl<'s> span::Builder<'s> for other::Either<'s> {
+    fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
+        match self {
+            Self::Left(field0) => span.add(field0),
+            Self::Right { token } => span.add(token),
+        }
+    }
+}
+::core::compile_error! {
+    "Expected `#[visitor(remote = path::to::Type)]` on the mirror type."
+}
+::core::compile_error! {
+    "Expected one of: `Visitor`, `VisitorMut`, `Foldable`, `IntoVisitor`, `SpanBuilder`."
+}
@@ -0,0 +1,23 @@
+#[derive(Visitor, VisitorMut)]
+#[visitor(remote = other::Wrapper)]
+struct Wrapper<'s, T> {
+    pub token: Token<'s>,
+    pub inner: T,
+    #[visitor(skip)]
+    pub id: usize,
+}
+
+#[derive(Foldable)]
+#[derive(Visitor, SpanBuilder)]
+#[visitor(remote = "other::Either")]
+enum Either<'s> {
+    Left(Tree<'s>),
+    Right { token: Token<'s> },
+}
+
+#[derive(Visitor)]
+struct NotRemote<'s>(Tree<'s>);
+
+#[derive(Visitor, Debug)]
+#[visitor(remote = other::Unknown)]
+struct UnknownDerive;